@@ -1,9 +1,28 @@
+use serde::Serialize;
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::State;
 
+use super::diagnostics::tls_handshake;
+use super::worktree::expand_home;
+use crate::crypto::TokenCrypto;
+use crate::db::{record_event, AppEventType, DbPool};
 use crate::error::AppError;
+use crate::grpc::tls::{load_ca_file, TrustedCa};
 use crate::grpc::JobworkerpClient;
 
+/// Upper bound for connecting to the backend before the TLS handshake check
+const TCP_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// CA certificate file trusted for the backend
+#[derive(Debug, Serialize)]
+pub struct TrustedCertInfo {
+    pub path: String,
+    pub certificate_count: usize,
+    /// Earliest expiry among the certificates (RFC 3339)
+    pub not_after: String,
+}
+
 /// Check connection to jobworkerp-rs backend
 #[tauri::command]
 pub async fn check_jobworkerp_connection(
@@ -11,3 +30,174 @@ pub async fn check_jobworkerp_connection(
 ) -> Result<bool, AppError> {
     grpc.check_connection().await
 }
+
+/// Point the app at a different jobworkerp-rs backend
+///
+/// The URL is only saved after a test connection succeeds; otherwise the
+/// current backend is kept and the connection error is returned.
+#[tauri::command]
+pub async fn set_grpc_url(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    url: String,
+) -> Result<String, AppError> {
+    let url = validate_grpc_url(&url)?;
+    grpc.set_url(&url).await?;
+
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    conn.execute(
+        "UPDATE app_settings SET grpc_server_url = ?1, updated_at = datetime('now') WHERE id = 1",
+        [&url],
+    )?;
+    record_event(
+        &conn,
+        AppEventType::SettingsChanged,
+        &format!("jobworkerp-rs backend URL set to {}", grpc.url()),
+    );
+
+    tracing::info!("jobworkerp-rs backend switched to {}", grpc.url());
+    Ok(url)
+}
+
+/// Switch the auth token sent to the backend
+///
+/// The client is rebuilt with the new token and tested; only when the backend
+/// accepts it is the token applied and saved, encrypted. Otherwise the
+/// previous token stays in use and the connection error is returned, so a bad
+/// token cannot lock the app out of the backend.
+#[tauri::command]
+pub async fn rotate_backend_auth(
+    db: State<'_, DbPool>,
+    crypto: State<'_, TokenCrypto>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    new_token: String,
+) -> Result<(), AppError> {
+    let token = new_token.trim();
+    if token.is_empty() {
+        return Err(AppError::validation("new_token", "Token must not be empty"));
+    }
+    let encrypted = crypto
+        .encrypt(token)
+        .map_err(|e| AppError::Crypto(e.to_string()))?;
+    grpc.set_auth_token(token).await?;
+
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    conn.execute(
+        "UPDATE app_settings SET grpc_auth_token_encrypted = ?1, updated_at = datetime('now')
+         WHERE id = 1",
+        [&encrypted],
+    )?;
+    record_event(
+        &conn,
+        AppEventType::SettingsChanged,
+        "jobworkerp-rs auth token rotated",
+    );
+
+    tracing::info!("jobworkerp-rs auth token rotated");
+    Ok(())
+}
+
+/// Trust a PEM CA certificate for a backend with a self-signed certificate
+///
+/// The file must hold certificates that are currently valid, and a TLS
+/// handshake with the backend must succeed with them; the client is then
+/// rebuilt and tested before the path is saved. Pass None or a blank path to
+/// stop trusting the saved certificate.
+#[tauri::command]
+pub async fn trust_backend_cert(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    cert_path: Option<String>,
+) -> Result<Option<TrustedCertInfo>, AppError> {
+    let Some(path) = cert_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    else {
+        grpc.set_trusted_ca(None).await?;
+        let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+        conn.execute(
+            "UPDATE app_settings SET grpc_ca_cert_path = NULL, updated_at = datetime('now')
+             WHERE id = 1",
+            [],
+        )?;
+        record_event(
+            &conn,
+            AppEventType::SettingsChanged,
+            "Trusted backend certificate removed",
+        );
+        return Ok(None);
+    };
+
+    let path = expand_home(path);
+    let ca = load_ca_file(&path)?;
+    check_tls_handshake(&grpc.url(), &ca).await?;
+    grpc.set_trusted_ca(Some(ca.clone())).await?;
+
+    let path = path.to_string_lossy().into_owned();
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    conn.execute(
+        "UPDATE app_settings SET grpc_ca_cert_path = ?1, updated_at = datetime('now') WHERE id = 1",
+        [&path],
+    )?;
+    record_event(
+        &conn,
+        AppEventType::SettingsChanged,
+        &format!("Trusted backend certificate set to {}", path),
+    );
+
+    tracing::info!("Trusting backend certificate {}", path);
+    Ok(Some(TrustedCertInfo {
+        path,
+        certificate_count: ca.certificates.len(),
+        not_after: ca.not_after.to_rfc3339(),
+    }))
+}
+
+/// Handshake with the backend trusting `ca`, for a clear error before the gRPC check
+async fn check_tls_handshake(url: &str, ca: &TrustedCa) -> Result<(), AppError> {
+    let parsed = url::Url::parse(url)
+        .map_err(|e| AppError::Config(format!("Invalid backend URL: {}", e)))?;
+    if parsed.scheme() != "https" {
+        return Err(AppError::validation(
+            "cert_path",
+            "The backend URL uses plain http; switch it to https first",
+        ));
+    }
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| AppError::Config(format!("Backend URL {} has no host", url)))?
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    let port = parsed.port_or_known_default().unwrap_or(443);
+
+    let tcp = tokio::time::timeout(
+        TCP_CONNECT_TIMEOUT,
+        tokio::net::TcpStream::connect((host, port)),
+    )
+    .await
+    .map_err(|_| AppError::Grpc(format!("Timed out connecting to {}:{}", host, port)))??;
+    tls_handshake(tcp, host, Some(ca)).await.map_err(|e| {
+        AppError::validation(
+            "cert_path",
+            format!(
+                "TLS handshake with {} failed with this certificate: {}",
+                host, e
+            ),
+        )
+    })?;
+    Ok(())
+}
+
+fn validate_grpc_url(input: &str) -> Result<String, AppError> {
+    let trimmed = input.trim();
+    let parsed = url::Url::parse(trimmed)
+        .map_err(|e| AppError::validation("grpc_url", format!("Invalid URL: {}", e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") || !parsed.has_host() {
+        return Err(AppError::validation(
+            "grpc_url",
+            "URL must be http(s) with a host",
+        ));
+    }
+    Ok(trimmed.trim_end_matches('/').to_string())
+}
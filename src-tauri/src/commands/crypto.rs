@@ -0,0 +1,77 @@
+use rusqlite::OptionalExtension;
+use serde::Serialize;
+use tauri::State;
+
+use crate::crypto::{CryptoStatus, TokenCrypto};
+use crate::db::DbPool;
+use crate::error::AppError;
+
+/// Known plaintext used for the encrypt/decrypt round trip
+const SENTINEL: &str = "local-code-agent crypto check";
+
+/// Result of `verify_crypto`
+#[derive(Debug, Serialize)]
+pub struct CryptoHealth {
+    /// A fresh value encrypts and decrypts with the current key
+    pub roundtrip_ok: bool,
+    /// A stored token decrypts with the current key; None if no token is stored
+    pub stored_decrypt_ok: Option<bool>,
+}
+
+/// Report whether the encryption key lives in the OS keyring or the fallback file
+#[tauri::command]
+pub async fn crypto_status(crypto: State<'_, TokenCrypto>) -> Result<CryptoStatus, AppError> {
+    Ok(crypto.status())
+}
+
+/// Move a file-based encryption key into the OS keyring
+///
+/// Leaves the key file untouched if the keyring write or verification fails.
+#[tauri::command]
+pub async fn migrate_key_to_keyring(
+    crypto: State<'_, TokenCrypto>,
+) -> Result<CryptoStatus, AppError> {
+    crypto
+        .migrate_key_to_keyring()
+        .map_err(|e| AppError::Crypto(e.to_string()))
+}
+
+/// Check that the current key can encrypt, decrypt, and read stored tokens
+///
+/// Catches a rotated or lost key before an agent run fails on it.
+#[tauri::command]
+pub async fn verify_crypto(
+    db: State<'_, DbPool>,
+    crypto: State<'_, TokenCrypto>,
+) -> Result<CryptoHealth, AppError> {
+    let roundtrip_ok = match crypto.encrypt(SENTINEL).and_then(|e| crypto.decrypt(&e)) {
+        Ok(plaintext) => plaintext.as_str() == SENTINEL,
+        Err(e) => {
+            tracing::warn!("Crypto round trip failed: {}", e);
+            false
+        }
+    };
+
+    let stored: Option<Vec<u8>> = {
+        let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+        conn.query_row(
+            "SELECT encrypted_token FROM token_stores ORDER BY id DESC LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?
+    };
+
+    let stored_decrypt_ok = stored.map(|encrypted| match crypto.decrypt(&encrypted) {
+        Ok(_) => true,
+        Err(e) => {
+            tracing::warn!("Stored token could not be decrypted: {}", e);
+            false
+        }
+    });
+
+    Ok(CryptoHealth {
+        roundtrip_ok,
+        stored_decrypt_ok,
+    })
+}
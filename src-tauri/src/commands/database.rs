@@ -0,0 +1,47 @@
+use tauri::State;
+
+use crate::db::{encryption, status_check, DbEncryptionStatus, DbPool, StatusConstraintReport};
+use crate::error::AppError;
+
+/// Get whether the SQLite database is encrypted with SQLCipher
+#[tauri::command]
+pub async fn db_encryption_status(db: State<'_, DbPool>) -> Result<DbEncryptionStatus, AppError> {
+    encryption::encryption_status(&db)
+}
+
+/// Encrypt an existing plaintext database with SQLCipher
+///
+/// The database is re-keyed on next start, before any connection is opened;
+/// the returned status has `migration_pending` set until then. Requires the
+/// `sqlcipher` build feature.
+#[tauri::command]
+pub async fn migrate_to_encrypted_db(
+    db: State<'_, DbPool>,
+) -> Result<DbEncryptionStatus, AppError> {
+    if !cfg!(feature = "sqlcipher") {
+        return Err(AppError::Config(
+            "Database encryption requires building with the `sqlcipher` feature".into(),
+        ));
+    }
+    encryption::request_encryption(&db)
+}
+
+/// Compare the agent job statuses known to the app with the database CHECK constraint
+#[tauri::command]
+pub async fn check_status_constraint(
+    db: State<'_, DbPool>,
+) -> Result<StatusConstraintReport, AppError> {
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    status_check::check_status_constraint(&conn)
+}
+
+/// Rebuild the agent_jobs status CHECK constraint to match the app's statuses
+///
+/// Runs automatically at startup; exposed for recovery when that step failed.
+#[tauri::command]
+pub async fn repair_status_constraint(
+    db: State<'_, DbPool>,
+) -> Result<StatusConstraintReport, AppError> {
+    let mut conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    status_check::repair_status_constraint(&mut conn)
+}
@@ -0,0 +1,614 @@
+use rusqlite::OptionalExtension;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+
+use super::issues::list_issues;
+use super::settings::{fetch_settings, AppSettings};
+use super::workflows::list_workflows;
+use crate::crypto::TokenCrypto;
+use crate::db::{current_schema_version, DbPool};
+use crate::error::AppError;
+use crate::grpc::tls::TrustedCa;
+use crate::grpc::JobworkerpClient;
+use crate::hooks::redact_hook;
+use crate::logging::LogController;
+
+/// Upper bound for backend probes so diagnostics never hang on a dead server
+const BACKEND_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Upper bound for the self-test MCP call, which may start the server's container
+const MCP_CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Known plaintext for the self-test encryption round trip
+const SELF_TEST_SENTINEL: &str = "local-code-agent self test";
+
+/// Event emitted for each completed step of `diagnose_connection`
+const DIAGNOSTIC_STEP_EVENT: &str = "connection-diagnostic-step";
+
+/// Redacted snapshot of the app configuration for support requests
+///
+/// Never contains tokens: the post-job hook is reduced to its webhook host, the
+/// backend URL has credentials stripped and the auth token is reported only as
+/// configured or not.
+#[derive(Debug, Serialize)]
+pub struct DiagnosticsReport {
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub schema_version: Option<i64>,
+    pub log_level: String,
+    pub settings: AppSettings,
+    pub backend: BackendDiagnostics,
+    pub mcp_servers: Vec<String>,
+    pub job_counts: BTreeMap<String, i64>,
+}
+
+/// Backend reachability as seen from this app
+#[derive(Debug, Serialize)]
+pub struct BackendDiagnostics {
+    pub url: String,
+    pub auth_configured: bool,
+    pub reachable: bool,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Stage of the connection to the backend checked by `diagnose_connection`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticStepKind {
+    Dns,
+    Tcp,
+    Tls,
+    Grpc,
+    Auth,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticOutcome {
+    Passed,
+    Failed,
+    /// Not applicable (e.g. TLS for http) or not reached after an earlier failure
+    Skipped,
+}
+
+/// Result of one diagnostic step, also emitted as `connection-diagnostic-step`
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticStep {
+    pub step: DiagnosticStepKind,
+    pub outcome: DiagnosticOutcome,
+    pub duration_ms: u64,
+    pub detail: String,
+}
+
+/// Summary of `diagnose_connection`
+#[derive(Debug, Serialize)]
+pub struct ConnectionDiagnostics {
+    pub url: String,
+    pub steps: Vec<DiagnosticStep>,
+    /// First step that failed, or `None` when the backend is fully usable
+    pub failed_step: Option<DiagnosticStepKind>,
+    pub total_ms: u64,
+}
+
+/// Part of the app checked by `self_test`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfTestCheck {
+    Crypto,
+    Database,
+    Backend,
+    McpIssues,
+    Workflows,
+}
+
+/// Result of one `self_test` check
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestResult {
+    pub check: SelfTestCheck,
+    pub outcome: DiagnosticOutcome,
+    pub duration_ms: u64,
+    /// What was checked, or what went wrong and how to fix it
+    pub detail: String,
+}
+
+/// Summary of `self_test`
+#[derive(Debug, Serialize)]
+pub struct SelfTestReport {
+    pub checks: Vec<SelfTestResult>,
+    /// No check failed
+    pub passed: bool,
+}
+
+/// Change the log filter at runtime
+///
+/// Accepts a level (`debug`) or a directive string (`local_code_agent_lib=trace`).
+/// Returns the filter now in effect.
+#[tauri::command]
+pub async fn set_log_level(
+    logging: State<'_, LogController>,
+    level: String,
+) -> Result<String, AppError> {
+    logging.set_level(&level)
+}
+
+/// Collect a redacted diagnostics bundle to paste into bug reports
+#[tauri::command]
+pub async fn diagnostics(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    logging: State<'_, LogController>,
+) -> Result<DiagnosticsReport, AppError> {
+    let (mut settings, job_counts) = {
+        let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+        (fetch_settings(&conn)?, count_jobs_by_status(&conn)?)
+    };
+    settings.post_job_hook = settings.post_job_hook.as_deref().map(redact_hook);
+    let schema_version = current_schema_version(&db)?;
+
+    let started = Instant::now();
+    let backend = match tokio::time::timeout(BACKEND_CHECK_TIMEOUT, grpc.check_connection()).await {
+        Ok(Ok(_)) => BackendDiagnostics {
+            url: grpc.url(),
+            auth_configured: grpc.has_auth_token(),
+            reachable: true,
+            latency_ms: Some(started.elapsed().as_millis() as u64),
+            error: None,
+        },
+        Ok(Err(e)) => BackendDiagnostics {
+            url: grpc.url(),
+            auth_configured: grpc.has_auth_token(),
+            reachable: false,
+            latency_ms: None,
+            error: Some(e.to_string()),
+        },
+        Err(_) => BackendDiagnostics {
+            url: grpc.url(),
+            auth_configured: grpc.has_auth_token(),
+            reachable: false,
+            latency_ms: None,
+            error: Some(format!(
+                "Timed out after {} seconds",
+                BACKEND_CHECK_TIMEOUT.as_secs()
+            )),
+        },
+    };
+
+    // Runner listing is best-effort; an unreachable backend is already reported above
+    let mcp_servers = if backend.reachable {
+        match tokio::time::timeout(BACKEND_CHECK_TIMEOUT, grpc.list_mcp_servers()).await {
+            Ok(Ok(servers)) => servers.into_iter().map(|s| s.name).collect(),
+            Ok(Err(e)) => {
+                tracing::warn!("diagnostics: failed to list MCP servers: {:?}", e);
+                Vec::new()
+            }
+            Err(_) => {
+                tracing::warn!("diagnostics: listing MCP servers timed out");
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
+    Ok(DiagnosticsReport {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        schema_version,
+        log_level: logging.current_level(),
+        settings,
+        backend,
+        mcp_servers,
+        job_counts,
+    })
+}
+
+/// Check the backend connection step by step, emitting each result as it completes
+///
+/// Runs DNS resolution, TCP connect, TLS handshake (https only), a gRPC request
+/// and an auth-token check in order; steps after the first failure are
+/// skipped. Probes the backend host directly, bypassing any configured proxy.
+#[tauri::command]
+pub async fn diagnose_connection(
+    app: AppHandle,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+) -> Result<ConnectionDiagnostics, AppError> {
+    let url = grpc.url();
+    let parsed = url::Url::parse(&url)
+        .map_err(|e| AppError::Config(format!("Invalid backend URL {}: {}", url, e)))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| AppError::Config(format!("Backend URL {} has no host", url)))?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = parsed.port_or_known_default().unwrap_or(80);
+    let https = parsed.scheme() == "https";
+
+    let started = Instant::now();
+    let mut steps: Vec<DiagnosticStep> = Vec::new();
+    let mut record = |step: DiagnosticStep| {
+        if let Err(e) = app.emit(DIAGNOSTIC_STEP_EVENT, &step) {
+            tracing::warn!("Failed to emit {}: {}", DIAGNOSTIC_STEP_EVENT, e);
+        }
+        let passed = step.outcome != DiagnosticOutcome::Failed;
+        steps.push(step);
+        passed
+    };
+
+    let step_started = Instant::now();
+    let resolved = timed(tokio::net::lookup_host((host.as_str(), port)))
+        .await
+        .map(|addrs| addrs.collect::<Vec<std::net::SocketAddr>>());
+    let addrs = resolved.clone().unwrap_or_default();
+    let mut reachable = record(step_result(
+        DiagnosticStepKind::Dns,
+        step_started,
+        resolved.map(|addrs| {
+            let ips: Vec<String> = addrs.iter().map(|a| a.ip().to_string()).collect();
+            format!("{} resolved to {}", host, ips.join(", "))
+        }),
+    ));
+
+    let mut stream = None;
+    if reachable {
+        let step_started = Instant::now();
+        let result = match addrs.first() {
+            Some(addr) => timed(tokio::net::TcpStream::connect(addr)).await,
+            None => Err(format!("{} has no addresses", host)),
+        };
+        let outcome = result.map(|tcp| {
+            let peer = tcp
+                .peer_addr()
+                .map(|a| a.to_string())
+                .unwrap_or_else(|_| format!("{}:{}", host, port));
+            stream = Some(tcp);
+            format!("Connected to {}", peer)
+        });
+        reachable = record(step_result(DiagnosticStepKind::Tcp, step_started, outcome));
+    } else {
+        record(skipped(DiagnosticStepKind::Tcp, "Not reached"));
+    }
+
+    match stream {
+        Some(tcp) if reachable && https => {
+            let step_started = Instant::now();
+            let outcome = tls_handshake(tcp, &host, grpc.trusted_ca().as_ref()).await;
+            reachable = record(step_result(DiagnosticStepKind::Tls, step_started, outcome));
+        }
+        _ if !https => {
+            record(skipped(
+                DiagnosticStepKind::Tls,
+                "Backend URL uses plain http",
+            ));
+        }
+        _ => {
+            record(skipped(DiagnosticStepKind::Tls, "Not reached"));
+        }
+    }
+
+    if reachable {
+        let step_started = Instant::now();
+        let probe = tokio::time::timeout(BACKEND_CHECK_TIMEOUT, grpc.probe()).await;
+        let (grpc_outcome, auth_outcome) = match probe {
+            Err(_) => (
+                Err(format!(
+                    "Timed out after {} seconds",
+                    BACKEND_CHECK_TIMEOUT.as_secs()
+                )),
+                None,
+            ),
+            Ok(Ok(())) => (
+                Ok("Backend answered a worker listing request".to_string()),
+                Some(Ok(if grpc.has_auth_token() {
+                    "Auth token accepted".to_string()
+                } else {
+                    "No auth token configured; backend does not require one".to_string()
+                })),
+            ),
+            Ok(Err(status))
+                if matches!(
+                    status.code(),
+                    tonic::Code::Unauthenticated | tonic::Code::PermissionDenied
+                ) =>
+            {
+                (
+                    Ok("Backend answered".to_string()),
+                    Some(Err(if grpc.has_auth_token() {
+                        format!("Auth token rejected: {}", status.message())
+                    } else {
+                        format!(
+                            "Backend requires a token; set JOBWORKERP_AUTH_TOKEN ({})",
+                            status.message()
+                        )
+                    })),
+                )
+            }
+            Ok(Err(status)) => (Err(AppError::from(status).to_string()), None),
+        };
+        // The auth verdict comes from the same request as the gRPC step
+        record(step_result(
+            DiagnosticStepKind::Grpc,
+            step_started,
+            grpc_outcome,
+        ));
+        match auth_outcome {
+            Some(outcome) => record(step_result(DiagnosticStepKind::Auth, step_started, outcome)),
+            None => record(skipped(DiagnosticStepKind::Auth, "Not reached")),
+        };
+    } else {
+        record(skipped(DiagnosticStepKind::Grpc, "Not reached"));
+        record(skipped(DiagnosticStepKind::Auth, "Not reached"));
+    }
+
+    let failed_step = steps
+        .iter()
+        .find(|s| s.outcome == DiagnosticOutcome::Failed)
+        .map(|s| s.step);
+    Ok(ConnectionDiagnostics {
+        url,
+        steps,
+        failed_step,
+        total_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Check crypto, database, backend, MCP access and workflow files in one go
+///
+/// Backs the "run diagnostics" button. The database check writes inside a
+/// transaction that is rolled back; the MCP check lists issues of the first
+/// registered repository and is skipped when there is none or the backend
+/// is unreachable. The workflow check is skipped when there are no workflow
+/// files. See `diagnose_connection` for a detailed backend check.
+#[tauri::command]
+pub async fn self_test(
+    app: AppHandle,
+    db: State<'_, DbPool>,
+    crypto: State<'_, TokenCrypto>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+) -> Result<SelfTestReport, AppError> {
+    let mut checks = Vec::new();
+    let check_result = |check: SelfTestCheck, started: Instant, outcome: Result<String, String>| {
+        let (outcome, detail) = match outcome {
+            Ok(detail) => (DiagnosticOutcome::Passed, detail),
+            Err(detail) => (DiagnosticOutcome::Failed, detail),
+        };
+        SelfTestResult {
+            check,
+            outcome,
+            duration_ms: started.elapsed().as_millis() as u64,
+            detail,
+        }
+    };
+
+    let started = Instant::now();
+    let outcome = match crypto
+        .encrypt(SELF_TEST_SENTINEL)
+        .and_then(|encrypted| crypto.decrypt(&encrypted))
+    {
+        Ok(plaintext) if plaintext.as_str() == SELF_TEST_SENTINEL => Ok(format!(
+            "Encryption key ({:?}) round trip succeeded",
+            crypto.status().backend
+        )),
+        Ok(_) => {
+            Err("Decrypted text does not match; the encryption key is inconsistent".to_string())
+        }
+        Err(e) => Err(format!("Encryption round trip failed: {}", e)),
+    };
+    checks.push(check_result(SelfTestCheck::Crypto, started, outcome));
+
+    let started = Instant::now();
+    let outcome = db
+        .get()
+        .map_err(|e| e.to_string())
+        .and_then(|mut conn| {
+            let tx = conn.transaction().map_err(|e| e.to_string())?;
+            tx.execute(
+                "UPDATE app_settings SET updated_at = datetime('now') WHERE id = 1",
+                [],
+            )
+            .map_err(|e| format!("Write failed: {}", e))?;
+            fetch_settings(&tx).map_err(|e| format!("Read failed: {}", e))?;
+            // Dropping the transaction rolls the write back
+            Ok("Settings written and read back".to_string())
+        })
+        .map_err(|e| format!("Database check failed: {}", e));
+    checks.push(check_result(SelfTestCheck::Database, started, outcome));
+
+    let started = Instant::now();
+    let outcome = match tokio::time::timeout(BACKEND_CHECK_TIMEOUT, grpc.check_connection()).await {
+        Ok(Ok(_)) => Ok(format!("Connected to {}", grpc.url())),
+        Ok(Err(e)) => Err(format!(
+            "Cannot reach {}: {}; run the connection diagnosis for details",
+            grpc.url(),
+            e
+        )),
+        Err(_) => Err(format!(
+            "Timed out connecting to {} after {} seconds",
+            grpc.url(),
+            BACKEND_CHECK_TIMEOUT.as_secs()
+        )),
+    };
+    let backend_reachable = outcome.is_ok();
+    checks.push(check_result(SelfTestCheck::Backend, started, outcome));
+
+    let first_repository: Option<(i64, String, String)> = {
+        let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+        conn.query_row(
+            "SELECT id, name, mcp_server_name FROM repositories ORDER BY id LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?
+    };
+    checks.push(match first_repository {
+        Some(_) if !backend_reachable => SelfTestResult {
+            check: SelfTestCheck::McpIssues,
+            outcome: DiagnosticOutcome::Skipped,
+            duration_ms: 0,
+            detail: "Backend is unreachable".to_string(),
+        },
+        Some((id, name, server)) => {
+            let started = Instant::now();
+            let outcome = match tokio::time::timeout(
+                MCP_CHECK_TIMEOUT,
+                list_issues(db.clone(), grpc.clone(), id, None),
+            )
+            .await
+            {
+                Ok(Ok(issues)) => Ok(format!(
+                    "Listed {} open issue(s) of {} via MCP server '{}'",
+                    issues.len(),
+                    name,
+                    server
+                )),
+                Ok(Err(e)) => Err(format!(
+                    "Listing issues of {} via MCP server '{}' failed: {}; check the server's token and runner",
+                    name, server, e
+                )),
+                Err(_) => Err(format!(
+                    "Listing issues of {} via MCP server '{}' timed out",
+                    name, server
+                )),
+            };
+            check_result(SelfTestCheck::McpIssues, started, outcome)
+        }
+        None => SelfTestResult {
+            check: SelfTestCheck::McpIssues,
+            outcome: DiagnosticOutcome::Skipped,
+            duration_ms: 0,
+            detail: "No repository registered".to_string(),
+        },
+    });
+
+    let started = Instant::now();
+    checks.push(match list_workflows(app).await {
+        Ok(workflows) if workflows.is_empty() => SelfTestResult {
+            check: SelfTestCheck::Workflows,
+            outcome: DiagnosticOutcome::Skipped,
+            duration_ms: 0,
+            detail: "No workflow files found".to_string(),
+        },
+        Ok(workflows) => check_result(
+            SelfTestCheck::Workflows,
+            started,
+            Ok(format!("Found {} workflow file(s)", workflows.len())),
+        ),
+        Err(e) => check_result(
+            SelfTestCheck::Workflows,
+            started,
+            Err(format!("Reading workflow files failed: {}", e)),
+        ),
+    });
+
+    let passed = checks
+        .iter()
+        .all(|c| c.outcome != DiagnosticOutcome::Failed);
+    Ok(SelfTestReport { checks, passed })
+}
+
+/// Run a network step with the backend timeout, flattening errors to text
+async fn timed<T, E: std::fmt::Display>(
+    future: impl std::future::Future<Output = Result<T, E>>,
+) -> Result<T, String> {
+    match tokio::time::timeout(BACKEND_CHECK_TIMEOUT, future).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(_) => Err(format!(
+            "Timed out after {} seconds",
+            BACKEND_CHECK_TIMEOUT.as_secs()
+        )),
+    }
+}
+
+fn step_result(
+    step: DiagnosticStepKind,
+    started: Instant,
+    outcome: Result<String, String>,
+) -> DiagnosticStep {
+    let (outcome, detail) = match outcome {
+        Ok(detail) => (DiagnosticOutcome::Passed, detail),
+        Err(detail) => (DiagnosticOutcome::Failed, detail),
+    };
+    DiagnosticStep {
+        step,
+        outcome,
+        duration_ms: started.elapsed().as_millis() as u64,
+        detail,
+    }
+}
+
+fn skipped(step: DiagnosticStepKind, reason: &str) -> DiagnosticStep {
+    DiagnosticStep {
+        step,
+        outcome: DiagnosticOutcome::Skipped,
+        duration_ms: 0,
+        detail: reason.to_string(),
+    }
+}
+
+/// TLS handshake offering HTTP/2 as gRPC does
+///
+/// Trusts the public web PKI plus the backend CA certificates, if any.
+pub(super) async fn tls_handshake(
+    tcp: tokio::net::TcpStream,
+    host: &str,
+    trusted_ca: Option<&TrustedCa>,
+) -> Result<String, String> {
+    use tokio_rustls::rustls;
+
+    let mut roots = rustls::RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    if let Some(ca) = trusted_ca {
+        let (_, ignored) = roots.add_parsable_certificates(ca.certificates.iter().cloned());
+        if ignored > 0 {
+            return Err(format!(
+                "{} trusted CA certificate(s) could not be used",
+                ignored
+            ));
+        }
+    }
+    let mut config = rustls::ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .map_err(|e| e.to_string())?
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+    config.alpn_protocols = vec![b"h2".to_vec()];
+
+    let server_name =
+        rustls::pki_types::ServerName::try_from(host.to_string()).map_err(|e| e.to_string())?;
+    let tls =
+        timed(tokio_rustls::TlsConnector::from(Arc::new(config)).connect(server_name, tcp)).await?;
+    let (_, session) = tls.get_ref();
+    Ok(format!(
+        "{}, ALPN {}",
+        session
+            .protocol_version()
+            .map(|v| format!("{:?}", v))
+            .unwrap_or_else(|| "unknown version".to_string()),
+        session
+            .alpn_protocol()
+            .map(|p| String::from_utf8_lossy(p).into_owned())
+            .unwrap_or_else(|| "none".to_string())
+    ))
+}
+
+/// Count agent jobs per status
+fn count_jobs_by_status(
+    conn: &r2d2::PooledConnection<r2d2_sqlite::SqliteConnectionManager>,
+) -> Result<BTreeMap<String, i64>, AppError> {
+    let mut stmt = conn.prepare("SELECT status, COUNT(*) FROM agent_jobs GROUP BY status")?;
+
+    let counts = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<BTreeMap<_, _>, _>>()?;
+
+    Ok(counts)
+}
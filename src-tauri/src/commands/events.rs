@@ -0,0 +1,60 @@
+use tauri::State;
+
+use crate::db::{AppEvent, AppEventType, DbPool};
+use crate::error::AppError;
+
+/// Events returned when the caller does not specify a limit
+const DEFAULT_EVENT_LIMIT: u32 = 50;
+
+/// Hard cap on events returned per call
+const MAX_EVENT_LIMIT: u32 = 200;
+
+/// List recent app-wide activity, newest first
+///
+/// Pass the `id` of the last event received as `after_id` to fetch the next
+/// (older) page.
+#[tauri::command]
+pub async fn list_app_events(
+    db: State<'_, DbPool>,
+    limit: Option<u32>,
+    after_id: Option<i64>,
+) -> Result<Vec<AppEvent>, AppError> {
+    let limit = limit
+        .unwrap_or(DEFAULT_EVENT_LIMIT)
+        .clamp(1, MAX_EVENT_LIMIT);
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, event_type, message, created_at
+         FROM app_events
+         WHERE ?1 IS NULL OR id < ?1
+         ORDER BY id DESC
+         LIMIT ?2",
+    )?;
+
+    let rows = stmt
+        .query_map(rusqlite::params![after_id, limit], |row| {
+            Ok((
+                row.get(0)?,
+                row.get::<_, String>(1)?,
+                row.get(2)?,
+                row.get(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<(i64, String, String, String)>, _>>()?;
+
+    // Skip event types this build does not know about
+    let events = rows
+        .into_iter()
+        .filter_map(|(id, event_type, message, created_at)| {
+            Some(AppEvent {
+                id,
+                event_type: event_type.parse::<AppEventType>().ok()?,
+                message,
+                created_at,
+            })
+        })
+        .collect();
+
+    Ok(events)
+}
@@ -1,8 +1,15 @@
+use futures::StreamExt;
+use serde::Serialize;
 use std::sync::Arc;
 use tauri::State;
 
-use crate::db::{get_repository_by_id, DbPool, Issue, Platform};
+use super::repositories::list_repositories;
+
+use crate::db::events::redact_secrets;
+use crate::db::{get_repository_by_id, DbPool, Issue, Platform, Repository, TimelineEvent};
 use crate::error::AppError;
+use crate::grpc::payload::{normalize_mcp_payload, payload_items};
+use crate::grpc::tool_overrides::McpOperation;
 use crate::grpc::JobworkerpClient;
 
 /// Get the MCP tool name for listing issues based on platform
@@ -21,6 +28,76 @@ fn get_read_issue_tool(platform: Platform) -> &'static str {
     }
 }
 
+/// Get the MCP tool name for reading an issue's timeline based on platform
+fn get_issue_timeline_tool(platform: Platform) -> &'static str {
+    match platform {
+        Platform::GitHub => "list_issue_events",
+        Platform::Gitea => "get_issue_timeline",
+    }
+}
+
+/// Get the MCP tool name for listing an issue's comments based on platform
+fn get_issue_comments_tool(platform: Platform) -> &'static str {
+    match platform {
+        Platform::GitHub => "get_issue_comments",
+        Platform::Gitea => "get_issue_comments_by_index",
+    }
+}
+
+/// Keys holding the event list in object-shaped payloads
+const TIMELINE_LIST_KEYS: &[&str] = &["events", "timeline", "items"];
+
+/// Keys holding the comment list in object-shaped payloads
+const COMMENT_LIST_KEYS: &[&str] = &["comments", "items"];
+
+/// Maximum length, in characters, of the text built by `build_agent_context`
+const MAX_AGENT_CONTEXT_CHARS: usize = 8_000;
+
+/// Maximum number of issues returned by `search_issues`
+const MAX_SEARCH_RESULTS: usize = 50;
+
+/// Maximum number of assigned issues fetched per repository
+const MAX_ASSIGNED_ISSUES: usize = 100;
+
+/// Page size for listing Gitea issues, which are filtered by assignee locally
+const ASSIGNED_ISSUES_PAGE_SIZE: usize = 50;
+
+/// Repositories queried at once when listing assigned issues across all of them
+const MAX_CONCURRENT_INBOX_REPOSITORIES: usize = 4;
+
+/// Get the MCP tool name for searching issues based on platform
+fn get_search_issues_tool(platform: Platform) -> &'static str {
+    match platform {
+        Platform::GitHub => "search_issues",
+        Platform::Gitea => "search_issues",
+    }
+}
+
+/// Escape free text for the issue search syntax
+///
+/// Terms that would be read as qualifiers (`is:pr`), exclusions (`-label`) or
+/// boolean operators are quoted so user input cannot widen the search scope.
+fn escape_search_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .filter_map(|term| {
+            let term = term.replace(['"', '\\'], "");
+            if term.is_empty() {
+                return None;
+            }
+            let needs_quotes = term.contains(':')
+                || term.starts_with('-')
+                || matches!(term.as_str(), "AND" | "OR" | "NOT");
+            Some(if needs_quotes {
+                format!("\"{}\"", term)
+            } else {
+                term
+            })
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Convert issue state to platform-specific format
 /// GitHub MCP expects uppercase: "OPEN", "CLOSED", or omit for all
 /// Gitea MCP expects lowercase: "open", "closed", "all"
@@ -114,90 +191,110 @@ fn parse_issue(value: &serde_json::Value, repo_url: &str, platform: Platform) ->
     })
 }
 
-/// Extract issues from MCP result
-/// Handles multiple formats:
-/// 1. GitHub MCP: {"issues": [...], "pageInfo": {...}, "totalCount": N}
-/// 2. MCP content structure: {"content": [{"text": "..."}]}
-/// 3. Direct array: [...]
-/// 4. Single issue object: {"number": ...}
-fn extract_issues_from_result(
+/// Branch of [`extract_issues_from_result`] that matched a payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssuesPayloadShape {
+    /// Direct array: [...]
+    Array,
+    /// GitHub MCP: {"issues": [...], "pageInfo": {...}, "totalCount": N}
+    IssuesKey,
+    /// Truncated array: {"items": [...]}
+    ItemsKey,
+    /// Single issue object: {"number": ...}
+    SingleIssue,
+    /// Nothing recognizable; no issues parsed
+    Unrecognized,
+}
+
+/// Issues parsed from an MCP result, with the shape that was recognized
+#[derive(Debug, Serialize)]
+pub struct ParsedIssues {
+    pub issues: Vec<Issue>,
+    pub shape: IssuesPayloadShape,
+    /// Whether the payload was JSON text inside MCP `content`
+    pub from_text_content: bool,
+    /// List entries dropped because they lack a number or title
+    pub skipped: usize,
+}
+
+fn parse_issues_payload(
     result: &serde_json::Value,
     repo_url: &str,
     platform: Platform,
-) -> Result<Vec<Issue>, AppError> {
-    tracing::debug!("extract_issues_from_result: {:?}", result);
+) -> ParsedIssues {
+    let payload = normalize_mcp_payload(result);
+    let from_text_content = payload != *result;
 
-    // GitHub MCP format: {"issues": [...], "pageInfo": {...}}
-    if let Some(issues_arr) = result.get("issues").and_then(|i| i.as_array()) {
-        tracing::debug!("Found 'issues' field with {} items", issues_arr.len());
-        return Ok(issues_arr
+    if let Some(issues_arr) = payload_items(&payload, &["issues", "items"]) {
+        tracing::debug!("Found {} issue items", issues_arr.len());
+        let shape = if payload.is_array() {
+            IssuesPayloadShape::Array
+        } else if payload.get("issues").is_some_and(|v| v.is_array()) {
+            IssuesPayloadShape::IssuesKey
+        } else {
+            IssuesPayloadShape::ItemsKey
+        };
+        let issues: Vec<Issue> = issues_arr
             .iter()
             .filter_map(|v| parse_issue(v, repo_url, platform))
-            .collect());
-    }
-
-    // MCP content structure: {"content": [{"text": {"text": "..."}}]} or {"content": [{"text": "..."}]}
-    if let Some(content) = result.get("content").and_then(|c| c.as_array()) {
-        tracing::debug!("Found content array with {} items", content.len());
-        for item in content {
-            // Try nested text.text structure first (Protobuf decoded format)
-            let text_str = item.get("text").and_then(|t| {
-                // Try {"text": {"text": "..."}} format
-                t.get("text")
-                    .and_then(|inner| inner.as_str())
-                    // Fallback to {"text": "..."} format
-                    .or_else(|| t.as_str())
-            });
-
-            if let Some(text) = text_str {
-                tracing::debug!("Found text content: {}", &text[..text.len().min(500)]);
-                if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(text) {
-                    // Try GitHub format within text
-                    if let Some(issues_arr) = parsed.get("issues").and_then(|i| i.as_array()) {
-                        tracing::debug!(
-                            "Parsed text contains 'issues' field with {} items",
-                            issues_arr.len()
-                        );
-                        return Ok(issues_arr
-                            .iter()
-                            .filter_map(|v| parse_issue(v, repo_url, platform))
-                            .collect());
-                    }
-                    // Try direct array within text
-                    if let Some(arr) = parsed.as_array() {
-                        tracing::debug!("Parsed as array with {} items", arr.len());
-                        return Ok(arr
-                            .iter()
-                            .filter_map(|v| parse_issue(v, repo_url, platform))
-                            .collect());
-                    }
-                    tracing::debug!("Parsed JSON has neither 'issues' nor array: {:?}", parsed);
-                } else {
-                    tracing::debug!("Failed to parse text as JSON");
-                }
-            }
-        }
+            .collect();
+        return ParsedIssues {
+            skipped: issues_arr.len() - issues.len(),
+            issues,
+            shape,
+            from_text_content,
+        };
     }
 
-    // Direct array format
-    if let Some(arr) = result.as_array() {
-        tracing::debug!("Result is direct array with {} items", arr.len());
-        return Ok(arr
-            .iter()
-            .filter_map(|v| parse_issue(v, repo_url, platform))
-            .collect());
-    }
-
-    // Single issue
-    if result.get("number").is_some() {
+    if payload.get("number").is_some() {
         tracing::debug!("Result is single issue");
-        if let Some(issue) = parse_issue(result, repo_url, platform) {
-            return Ok(vec![issue]);
+        if let Some(issue) = parse_issue(&payload, repo_url, platform) {
+            return ParsedIssues {
+                issues: vec![issue],
+                shape: IssuesPayloadShape::SingleIssue,
+                from_text_content,
+                skipped: 0,
+            };
         }
     }
 
     tracing::debug!("No issues found in result");
-    Ok(vec![])
+    ParsedIssues {
+        issues: Vec::new(),
+        shape: IssuesPayloadShape::Unrecognized,
+        from_text_content,
+        skipped: 0,
+    }
+}
+
+/// Extract issues from MCP result
+/// Handles multiple formats:
+/// 1. GitHub MCP: {"issues": [...], "pageInfo": {...}, "totalCount": N}
+/// 2. MCP content structure: {"content": [{"text": "..."}]}
+/// 3. Direct array: [...]
+/// 4. Single issue object: {"number": ...}
+fn extract_issues_from_result(
+    result: &serde_json::Value,
+    repo_url: &str,
+    platform: Platform,
+) -> Result<Vec<Issue>, AppError> {
+    tracing::debug!("extract_issues_from_result: {:?}", result);
+    Ok(parse_issues_payload(result, repo_url, platform).issues)
+}
+
+/// Parse a pasted raw MCP response the way issue listing does
+///
+/// Helps diagnose "issues don't show up" reports without a backend.
+#[tauri::command]
+pub async fn test_parse_issues(
+    raw: String,
+    repo_url: String,
+    platform: Platform,
+) -> Result<ParsedIssues, AppError> {
+    let result: serde_json::Value = serde_json::from_str(&raw)
+        .map_err(|e| AppError::validation("raw", format!("Not valid JSON: {}", e)))?;
+    Ok(parse_issues_payload(&result, &repo_url, platform))
 }
 
 /// List issues for a repository via MCP server
@@ -209,7 +306,11 @@ pub async fn list_issues(
     state: Option<String>,
 ) -> Result<Vec<Issue>, AppError> {
     let repo = get_repository_by_id(&db, repository_id)?;
-    let tool_name = get_list_issues_tool(repo.platform);
+    let tool_name = grpc.tool_for(
+        &repo.mcp_server_name,
+        McpOperation::ListIssues,
+        get_list_issues_tool(repo.platform),
+    );
     let state_str = state.unwrap_or_else(|| "open".to_string());
     tracing::debug!("list_issues called with state: '{}'", state_str);
     let state_value = normalize_issue_state(&state_str, repo.platform);
@@ -229,12 +330,263 @@ pub async fn list_issues(
     tracing::debug!("list_issues args: {:?}", args);
 
     let result = grpc
-        .call_mcp_tool(&repo.mcp_server_name, tool_name, &args)
+        .call_mcp_tool(&repo.mcp_server_name, &tool_name, &args)
         .await?;
 
     extract_issues_from_result(&result, &repo.url, repo.platform)
 }
 
+/// Search issues in a repository via MCP server
+///
+/// Pull requests are excluded. At most `MAX_SEARCH_RESULTS` issues are returned.
+#[tauri::command]
+pub async fn search_issues(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    repository_id: i64,
+    query: String,
+    state: Option<String>,
+) -> Result<Vec<Issue>, AppError> {
+    let escaped = escape_search_query(&query);
+    if escaped.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Search query cannot be empty".to_string(),
+        ));
+    }
+
+    let repo = get_repository_by_id(&db, repository_id)?;
+    let tool_name = grpc.tool_for(
+        &repo.mcp_server_name,
+        McpOperation::SearchIssues,
+        get_search_issues_tool(repo.platform),
+    );
+    let state_str = state.unwrap_or_else(|| "open".to_string()).to_lowercase();
+    if !matches!(state_str.as_str(), "open" | "closed" | "all") {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid issue state: {}",
+            state_str
+        )));
+    }
+
+    let args = match repo.platform {
+        Platform::GitHub => {
+            let mut q = format!("repo:{}/{} is:issue", repo.owner, repo.repo_name);
+            if state_str != "all" {
+                q.push_str(&format!(" state:{}", state_str));
+            }
+            serde_json::json!({
+                "query": format!("{} {}", q, escaped),
+                "perPage": MAX_SEARCH_RESULTS,
+            })
+        }
+        Platform::Gitea => serde_json::json!({
+            "owner": repo.owner,
+            "repo": repo.repo_name,
+            "query": escaped,
+            "state": state_str,
+            "type": "issues",
+            "limit": MAX_SEARCH_RESULTS,
+        }),
+    };
+
+    tracing::debug!("search_issues args: {:?}", args);
+
+    let result = grpc
+        .call_mcp_tool(&repo.mcp_server_name, &tool_name, &args)
+        .await?;
+
+    let mut issues = extract_issues_from_result(&result, &repo.url, repo.platform)?;
+    issues.truncate(MAX_SEARCH_RESULTS);
+    Ok(issues)
+}
+
+/// Open issue assigned to the agent user, with the repository it belongs to
+#[derive(Debug, Serialize)]
+pub struct AgentInboxIssue {
+    pub repository_id: i64,
+    pub repository_name: String,
+    #[serde(flatten)]
+    pub issue: Issue,
+}
+
+/// Repository whose assigned issues could not be listed
+#[derive(Debug, Serialize)]
+pub struct InboxRepositoryError {
+    pub repository_id: i64,
+    pub error: String,
+}
+
+/// Issues assigned to the agent user across one or all repositories
+#[derive(Debug, Serialize)]
+pub struct AgentInbox {
+    pub issues: Vec<AgentInboxIssue>,
+    pub failed_repositories: Vec<InboxRepositoryError>,
+}
+
+/// Normalize an assignee login, accepting a leading `@`
+fn normalize_assignee(assignee: &str) -> Result<String, AppError> {
+    let login = assignee.trim().trim_start_matches('@');
+    if login.is_empty()
+        || !login
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(AppError::validation(
+            "assignee",
+            format!("Invalid user name: '{}'", assignee),
+        ));
+    }
+    Ok(login.to_string())
+}
+
+/// Check whether a raw issue lists `login` in `assignee` or `assignees`
+fn is_assigned_to(value: &serde_json::Value, login: &str) -> bool {
+    let matches = |user: &serde_json::Value| {
+        user_login(Some(user)).is_some_and(|name| name.eq_ignore_ascii_case(login))
+    };
+    value.get("assignee").is_some_and(matches)
+        || value
+            .get("assignees")
+            .and_then(|v| v.as_array())
+            .is_some_and(|users| users.iter().any(matches))
+}
+
+/// Fetch open issues of one repository assigned to `login`
+///
+/// GitHub filters through an `assignee:` search qualifier. Gitea's issue list
+/// has no assignee filter, so its pages are filtered locally.
+async fn fetch_assigned_issues(
+    grpc: &JobworkerpClient,
+    repo: &Repository,
+    login: &str,
+) -> Result<Vec<Issue>, AppError> {
+    match repo.platform {
+        Platform::GitHub => {
+            let tool_name = grpc.tool_for(
+                &repo.mcp_server_name,
+                McpOperation::SearchIssues,
+                get_search_issues_tool(repo.platform),
+            );
+            let args = serde_json::json!({
+                "query": format!(
+                    "repo:{}/{} is:issue state:open assignee:{}",
+                    repo.owner, repo.repo_name, login
+                ),
+                "perPage": MAX_ASSIGNED_ISSUES,
+            });
+            let result = grpc
+                .call_mcp_tool(&repo.mcp_server_name, &tool_name, &args)
+                .await?;
+            let mut issues = extract_issues_from_result(&result, &repo.url, repo.platform)?;
+            issues.truncate(MAX_ASSIGNED_ISSUES);
+            Ok(issues)
+        }
+        Platform::Gitea => {
+            let tool_name = grpc.tool_for(
+                &repo.mcp_server_name,
+                McpOperation::ListIssues,
+                get_list_issues_tool(repo.platform),
+            );
+            let mut issues = Vec::new();
+            for page in 1..=MAX_ASSIGNED_ISSUES.div_ceil(ASSIGNED_ISSUES_PAGE_SIZE) {
+                let args = serde_json::json!({
+                    "owner": repo.owner,
+                    "repo": repo.repo_name,
+                    "state": "open",
+                    "page": page,
+                    "pageSize": ASSIGNED_ISSUES_PAGE_SIZE,
+                });
+                let result = grpc
+                    .call_mcp_tool(&repo.mcp_server_name, &tool_name, &args)
+                    .await?;
+                let payload = normalize_mcp_payload(&result);
+                let Some(items) = payload_items(&payload, &["issues", "items"]) else {
+                    break;
+                };
+                issues.extend(
+                    items
+                        .iter()
+                        .filter(|v| is_assigned_to(v, login))
+                        .filter_map(|v| parse_issue(v, &repo.url, repo.platform)),
+                );
+                if items.len() < ASSIGNED_ISSUES_PAGE_SIZE {
+                    break;
+                }
+            }
+            Ok(issues)
+        }
+    }
+}
+
+/// List open issues assigned to the user the agent acts as
+///
+/// Queries one repository, or every registered repository when
+/// `repository_id` is omitted; then at most
+/// `MAX_CONCURRENT_INBOX_REPOSITORIES` are queried at once and failures are
+/// reported per repository instead of failing the whole list.
+#[tauri::command]
+pub async fn list_agent_assigned_issues(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    repository_id: Option<i64>,
+    assignee: String,
+) -> Result<AgentInbox, AppError> {
+    let login = normalize_assignee(&assignee)?;
+    let into_inbox = |repo: &Repository, issues: Vec<Issue>| -> Vec<AgentInboxIssue> {
+        issues
+            .into_iter()
+            .map(|issue| AgentInboxIssue {
+                repository_id: repo.id,
+                repository_name: repo.name.clone(),
+                issue,
+            })
+            .collect()
+    };
+
+    if let Some(id) = repository_id {
+        let repo = get_repository_by_id(&db, id)?;
+        let issues = fetch_assigned_issues(&grpc, &repo, &login).await?;
+        return Ok(AgentInbox {
+            issues: into_inbox(&repo, issues),
+            failed_repositories: Vec::new(),
+        });
+    }
+
+    let repos = list_repositories(db.clone()).await?;
+    let results: Vec<(Repository, Result<Vec<Issue>, AppError>)> = futures::stream::iter(repos)
+        .map(|repo| {
+            let (grpc, login) = (&grpc, &login);
+            async move {
+                let issues = fetch_assigned_issues(grpc, &repo, login).await;
+                (repo, issues)
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_INBOX_REPOSITORIES)
+        .collect()
+        .await;
+
+    let mut inbox = AgentInbox {
+        issues: Vec::new(),
+        failed_repositories: Vec::new(),
+    };
+    for (repo, issues) in results {
+        match issues {
+            Ok(issues) => inbox.issues.extend(into_inbox(&repo, issues)),
+            Err(e) => {
+                tracing::warn!("Failed to list assigned issues of {}: {}", repo.name, e);
+                inbox.failed_repositories.push(InboxRepositoryError {
+                    repository_id: repo.id,
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+    inbox
+        .issues
+        .sort_by(|a, b| b.issue.updated_at.cmp(&a.issue.updated_at));
+    Ok(inbox)
+}
+
 /// Get a single issue by number
 #[tauri::command]
 pub async fn get_issue(
@@ -244,7 +596,19 @@ pub async fn get_issue(
     issue_number: i32,
 ) -> Result<Issue, AppError> {
     let repo = get_repository_by_id(&db, repository_id)?;
-    let tool_name = get_read_issue_tool(repo.platform);
+    fetch_issue(&grpc, &repo, issue_number).await
+}
+
+async fn fetch_issue(
+    grpc: &JobworkerpClient,
+    repo: &Repository,
+    issue_number: i32,
+) -> Result<Issue, AppError> {
+    let tool_name = grpc.tool_for(
+        &repo.mcp_server_name,
+        McpOperation::ReadIssue,
+        get_read_issue_tool(repo.platform),
+    );
 
     let args = serde_json::json!({
         "owner": repo.owner,
@@ -253,29 +617,536 @@ pub async fn get_issue(
     });
 
     let result = grpc
-        .call_mcp_tool(&repo.mcp_server_name, tool_name, &args)
+        .call_mcp_tool(&repo.mcp_server_name, &tool_name, &args)
         .await?;
 
-    // Try to extract from MCP content structure first
-    if let Some(content) = result.get("content").and_then(|c| c.as_array()) {
-        for item in content {
-            // Handle nested text.text structure (Protobuf decoded format)
-            let text_str = item.get("text").and_then(|t| {
-                t.get("text")
-                    .and_then(|inner| inner.as_str())
-                    .or_else(|| t.as_str())
-            });
-            if let Some(text) = text_str {
-                if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(text) {
-                    if let Some(issue) = parse_issue(&parsed, &repo.url, repo.platform) {
-                        return Ok(issue);
-                    }
-                }
+    parse_issue(&normalize_mcp_payload(&result), &repo.url, repo.platform)
+        .ok_or_else(|| AppError::NotFound(format!("Issue #{} not found", issue_number)))
+}
+
+/// Reject issues an agent should not be started for
+fn ensure_issue_startable(issue: &Issue) -> Result<(), AppError> {
+    if !issue.state.eq_ignore_ascii_case("open") {
+        return Err(AppError::InvalidInput(format!(
+            "Issue #{} is {}, not open",
+            issue.number,
+            issue.state.to_lowercase()
+        )));
+    }
+    Ok(())
+}
+
+/// Check that an issue exists and is open before starting an agent on it
+///
+/// Returns the issue, or `None` when `skip_check` is set (e.g. offline or
+/// when the MCP server cannot read the issue) so the caller can force-start.
+#[tauri::command]
+pub async fn check_issue_for_agent(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    repository_id: i64,
+    issue_number: i32,
+    skip_check: Option<bool>,
+) -> Result<Option<Issue>, AppError> {
+    if issue_number <= 0 {
+        return Err(AppError::validation(
+            "issue_number",
+            "issue_number must be a positive number",
+        ));
+    }
+    let repo = get_repository_by_id(&db, repository_id)?;
+    if skip_check.unwrap_or(false) {
+        tracing::info!(
+            "Skipping issue check for {}/{}#{}",
+            repo.owner,
+            repo.repo_name,
+            issue_number
+        );
+        return Ok(None);
+    }
+
+    let issue = fetch_issue(&grpc, &repo, issue_number)
+        .await
+        .map_err(|e| match e {
+            // The platform's 404 surfaces as a tool error
+            AppError::Grpc(message) | AppError::Internal(message)
+                if message.contains("404") || message.to_lowercase().contains("not found") =>
+            {
+                AppError::NotFound(format!("Issue #{} not found", issue_number))
             }
+            other => other,
+        })?;
+    ensure_issue_startable(&issue)?;
+    Ok(Some(issue))
+}
+
+/// Whether an MCP call failed because the server does not provide the tool
+fn is_missing_tool_error(error: &AppError) -> bool {
+    let message = error.to_string().to_lowercase();
+    message.contains("tool")
+        && ["not found", "unknown", "no such", "not supported"]
+            .iter()
+            .any(|marker| message.contains(marker))
+}
+
+/// Login of a user object (GitHub: login, Gitea: login or username)
+fn user_login(value: Option<&serde_json::Value>) -> Option<String> {
+    let user = value?;
+    user.as_str()
+        .or_else(|| user.get("login").and_then(|v| v.as_str()))
+        .or_else(|| user.get("username").and_then(|v| v.as_str()))
+        .filter(|login| !login.is_empty())
+        .map(String::from)
+}
+
+/// Parse a timeline entry (handles both GitHub and Gitea formats)
+///
+/// GitHub events carry `event`, `actor`, `commit_id` and `source.issue`;
+/// Gitea timeline comments carry `type`, `user`, `ref_commit_sha` and `ref_issue`.
+fn parse_timeline_event(value: &serde_json::Value) -> Option<TimelineEvent> {
+    let event = value
+        .get("event")
+        .or_else(|| value.get("type"))
+        .and_then(|v| v.as_str())?
+        .to_string();
+    let actor = user_login(value.get("actor")).or_else(|| user_login(value.get("user")));
+    let created_at = value
+        .get("created_at")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+
+    let str_at = |path: &[&str]| {
+        path.iter()
+            .try_fold(value, |v, key| v.get(key))
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+    };
+    let number_at = |path: &[&str]| {
+        path.iter()
+            .try_fold(value, |v, key| v.get(key))
+            .and_then(|v| v.as_i64())
+            .map(|n| format!("#{}", n))
+    };
+    let detail = str_at(&["label", "name"])
+        .or_else(|| user_login(value.get("assignee")))
+        .or_else(|| str_at(&["commit_id"]))
+        .or_else(|| str_at(&["ref_commit_sha"]))
+        .or_else(|| number_at(&["source", "issue", "number"]))
+        .or_else(|| number_at(&["ref_issue", "number"]))
+        .or_else(|| {
+            let from = str_at(&["rename", "from"]).or_else(|| str_at(&["old_title"]))?;
+            let to = str_at(&["rename", "to"]).or_else(|| str_at(&["new_title"]))?;
+            Some(format!("{} -> {}", from, to))
+        })
+        .or_else(|| str_at(&["body"]));
+
+    Some(TimelineEvent {
+        event,
+        actor,
+        created_at,
+        detail,
+    })
+}
+
+/// Extract timeline events from MCP result
+fn extract_timeline_events(result: &serde_json::Value) -> Vec<TimelineEvent> {
+    let payload = normalize_mcp_payload(result);
+    payload_items(&payload, TIMELINE_LIST_KEYS)
+        .map(|arr| arr.iter().filter_map(parse_timeline_event).collect())
+        .unwrap_or_default()
+}
+
+/// Get an issue's history (labels, assignments, references, renames) via MCP server
+///
+/// Returns an empty list when the MCP server has no timeline tool.
+#[tauri::command]
+pub async fn get_issue_timeline(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    repository_id: i64,
+    issue_number: i32,
+) -> Result<Vec<TimelineEvent>, AppError> {
+    let repo = get_repository_by_id(&db, repository_id)?;
+    let tool_name = grpc.tool_for(
+        &repo.mcp_server_name,
+        McpOperation::IssueTimeline,
+        get_issue_timeline_tool(repo.platform),
+    );
+
+    let args = match repo.platform {
+        Platform::GitHub => serde_json::json!({
+            "owner": repo.owner,
+            "repo": repo.repo_name,
+            "issue_number": issue_number,
+        }),
+        Platform::Gitea => serde_json::json!({
+            "owner": repo.owner,
+            "repo": repo.repo_name,
+            "index": issue_number,
+        }),
+    };
+
+    match grpc
+        .call_mcp_tool(&repo.mcp_server_name, &tool_name, &args)
+        .await
+    {
+        Ok(result) => Ok(extract_timeline_events(&result)),
+        Err(e) if is_missing_tool_error(&e) => {
+            tracing::debug!(
+                "MCP server '{}' has no {} tool: {}",
+                repo.mcp_server_name,
+                tool_name,
+                e
+            );
+            Ok(Vec::new())
         }
+        Err(e) => Err(e),
     }
+}
 
-    // Direct format
-    parse_issue(&result, &repo.url, repo.platform)
-        .ok_or_else(|| AppError::NotFound(format!("Issue #{} not found", issue_number)))
+/// Issue comment as included in agent context
+#[derive(Debug, Clone, PartialEq)]
+struct IssueComment {
+    user: String,
+    created_at: String,
+    body: String,
+}
+
+/// Parse an issue comment (GitHub and Gitea share `body`, `user` and `created_at`)
+fn parse_issue_comment(value: &serde_json::Value) -> Option<IssueComment> {
+    let body = value.get("body")?.as_str()?.trim();
+    if body.is_empty() {
+        return None;
+    }
+    Some(IssueComment {
+        user: user_login(value.get("user")).unwrap_or_default(),
+        created_at: value
+            .get("created_at")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        body: body.to_string(),
+    })
+}
+
+/// Extract comments from MCP result, oldest first
+fn extract_issue_comments(result: &serde_json::Value) -> Vec<IssueComment> {
+    let payload = normalize_mcp_payload(result);
+    payload_items(&payload, COMMENT_LIST_KEYS)
+        .map(|arr| arr.iter().filter_map(parse_issue_comment).collect())
+        .unwrap_or_default()
+}
+
+/// Fetch an issue's comments; empty when the MCP server has no comments tool
+async fn fetch_issue_comments(
+    grpc: &JobworkerpClient,
+    repo: &Repository,
+    issue_number: i32,
+) -> Result<Vec<IssueComment>, AppError> {
+    let tool_name = grpc.tool_for(
+        &repo.mcp_server_name,
+        McpOperation::IssueComments,
+        get_issue_comments_tool(repo.platform),
+    );
+
+    let args = match repo.platform {
+        Platform::GitHub => serde_json::json!({
+            "owner": repo.owner,
+            "repo": repo.repo_name,
+            "issue_number": issue_number,
+        }),
+        Platform::Gitea => serde_json::json!({
+            "owner": repo.owner,
+            "repo": repo.repo_name,
+            "index": issue_number,
+        }),
+    };
+
+    match grpc
+        .call_mcp_tool(&repo.mcp_server_name, &tool_name, &args)
+        .await
+    {
+        Ok(result) => Ok(extract_issue_comments(&result)),
+        Err(e) if is_missing_tool_error(&e) => {
+            tracing::debug!(
+                "MCP server '{}' has no {} tool: {}",
+                repo.mcp_server_name,
+                tool_name,
+                e
+            );
+            Ok(Vec::new())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Issue discussion condensed for an agent prompt
+#[derive(Debug, Serialize)]
+pub struct AgentContext {
+    pub text: String,
+    pub included_comments: usize,
+    pub omitted_comments: usize,
+}
+
+/// Cut `text` to at most `max_chars` characters, marking the cut
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Format an issue and its comments into at most `max_chars` characters
+///
+/// The issue body takes at most half the budget. Comments fill the rest
+/// newest first, since the end of a thread usually holds the current state
+/// of the discussion, and are then listed in order with a note on how many
+/// older ones were left out. Secrets are redacted.
+fn format_agent_context(
+    issue: &Issue,
+    comments: &[IssueComment],
+    max_chars: usize,
+) -> AgentContext {
+    let body = issue.body.as_deref().unwrap_or("").trim();
+    let mut text = format!("Issue #{}: {}\n", issue.number, issue.title);
+    if !body.is_empty() {
+        text.push('\n');
+        text.push_str(&truncate_chars(body, max_chars / 2));
+        text.push('\n');
+    }
+    let text = truncate_chars(&redact_secrets(&text), max_chars);
+
+    // Room for the header and the omitted-comments note
+    let header = "\nComments:\n";
+    let omitted_note = |omitted: usize| {
+        format!(
+            "({} earlier comment{} omitted)\n",
+            omitted,
+            if omitted == 1 { "" } else { "s" }
+        )
+    };
+    let overhead = header.chars().count() + omitted_note(comments.len()).chars().count();
+    let mut remaining = max_chars.saturating_sub(text.chars().count() + overhead);
+    let mut included = Vec::new();
+    for comment in comments.iter().rev() {
+        let entry = redact_secrets(&format!(
+            "\n@{} ({}):\n{}\n",
+            comment.user, comment.created_at, comment.body
+        ));
+        let length = entry.chars().count();
+        if length > remaining {
+            break;
+        }
+        remaining -= length;
+        included.push(entry);
+    }
+    let omitted_comments = comments.len() - included.len();
+
+    let mut text = text;
+    if !comments.is_empty() {
+        text.push_str(header);
+        if omitted_comments > 0 {
+            text.push_str(&omitted_note(omitted_comments));
+        }
+        for entry in included.iter().rev() {
+            text.push_str(entry);
+        }
+    }
+
+    AgentContext {
+        text,
+        included_comments: included.len(),
+        omitted_comments,
+    }
+}
+
+/// Build prompt context from an issue and its comment thread
+///
+/// The text is capped at a few thousand characters, with secrets redacted,
+/// so it can be merged into an agent's `custom_prompt`.
+#[tauri::command]
+pub async fn build_agent_context(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    repository_id: i64,
+    issue_number: i32,
+) -> Result<AgentContext, AppError> {
+    let repo = get_repository_by_id(&db, repository_id)?;
+    let issue = fetch_issue(&grpc, &repo, issue_number).await?;
+    let comments = fetch_issue_comments(&grpc, &repo, issue_number).await?;
+    Ok(format_agent_context(
+        &issue,
+        &comments,
+        MAX_AGENT_CONTEXT_CHARS,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ensure_issue_startable() {
+        let mut issue = Issue {
+            number: 7,
+            title: "Crash on startup".to_string(),
+            body: None,
+            state: "OPEN".to_string(),
+            labels: Vec::new(),
+            user: "octocat".to_string(),
+            html_url: "https://github.com/o/r/issues/7".to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+        assert!(ensure_issue_startable(&issue).is_ok());
+
+        issue.state = "closed".to_string();
+        assert!(matches!(
+            ensure_issue_startable(&issue),
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_escape_search_query_quotes_qualifiers() {
+        assert_eq!(
+            escape_search_query("  crash on   startup "),
+            "crash on startup"
+        );
+        assert_eq!(
+            escape_search_query("repo:other/secret is:pr -bug"),
+            "\"repo:other/secret\" \"is:pr\" \"-bug\""
+        );
+        assert_eq!(escape_search_query("a OR b"), "a \"OR\" b");
+        assert_eq!(escape_search_query(r#"say "hi\" ""#), "say hi");
+        assert_eq!(escape_search_query(r#"  "" "#), "");
+    }
+
+    #[test]
+    fn test_extract_timeline_events_both_platforms() {
+        let github = serde_json::json!({
+            "content": [{"type": "text", "text": serde_json::json!([
+                {"event": "labeled", "actor": {"login": "octocat"},
+                 "created_at": "2024-01-01T00:00:00Z", "label": {"name": "bug"}},
+                {"event": "referenced", "actor": {"login": "octocat"},
+                 "created_at": "2024-01-02T00:00:00Z", "commit_id": "abc123"},
+                {"event": "renamed", "actor": {"login": "octocat"},
+                 "created_at": "2024-01-03T00:00:00Z", "rename": {"from": "Old", "to": "New"}}
+            ]).to_string()}]
+        });
+        let events = extract_timeline_events(&github);
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].detail.as_deref(), Some("bug"));
+        assert_eq!(events[1].detail.as_deref(), Some("abc123"));
+        assert_eq!(events[2].detail.as_deref(), Some("Old -> New"));
+
+        let gitea = serde_json::json!([
+            {"type": "assignees", "user": {"username": "alice"},
+             "created_at": "2024-01-01T00:00:00Z", "assignee": {"login": "bob"}},
+            {"type": "issue_ref", "user": {"login": "alice"},
+             "created_at": "2024-01-02T00:00:00Z", "ref_issue": {"number": 7}}
+        ]);
+        let events = extract_timeline_events(&gitea);
+        assert_eq!(events[0].actor.as_deref(), Some("alice"));
+        assert_eq!(events[0].detail.as_deref(), Some("bob"));
+        assert_eq!(events[1].detail.as_deref(), Some("#7"));
+    }
+
+    #[test]
+    fn test_parse_issues_payload_shapes() {
+        let url = "https://github.com/o/r";
+        let issue = serde_json::json!({"number": 7, "title": "Crash"});
+
+        let github = serde_json::json!({
+            "content": [{"type": "text", "text": serde_json::json!({
+                "issues": [issue, {"title": "no number"}], "totalCount": 2
+            }).to_string()}]
+        });
+        let parsed = parse_issues_payload(&github, url, Platform::GitHub);
+        assert_eq!(parsed.shape, IssuesPayloadShape::IssuesKey);
+        assert!(parsed.from_text_content);
+        assert_eq!(parsed.issues.len(), 1);
+        assert_eq!(parsed.skipped, 1);
+        assert_eq!(parsed.issues[0].html_url, "https://github.com/o/r/issues/7");
+
+        let array = serde_json::json!([issue]);
+        let parsed = parse_issues_payload(&array, url, Platform::Gitea);
+        assert_eq!(parsed.shape, IssuesPayloadShape::Array);
+        assert!(!parsed.from_text_content);
+
+        let items = serde_json::json!({"items": [issue]});
+        assert_eq!(
+            parse_issues_payload(&items, url, Platform::Gitea).shape,
+            IssuesPayloadShape::ItemsKey
+        );
+        assert_eq!(
+            parse_issues_payload(&issue, url, Platform::GitHub).shape,
+            IssuesPayloadShape::SingleIssue
+        );
+        let parsed = parse_issues_payload(&serde_json::json!({"data": []}), url, Platform::GitHub);
+        assert_eq!(parsed.shape, IssuesPayloadShape::Unrecognized);
+        assert!(parsed.issues.is_empty());
+    }
+
+    #[test]
+    fn test_format_agent_context_keeps_newest_comments() {
+        let issue = Issue {
+            number: 7,
+            title: "Crash on startup".to_string(),
+            body: Some("Stack trace with token=abc123".to_string()),
+            state: "open".to_string(),
+            labels: Vec::new(),
+            user: "octocat".to_string(),
+            html_url: String::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+        let result = serde_json::json!([
+            {"body": "First guess", "user": {"login": "alice"}, "created_at": "2024-01-01"},
+            {"body": "  ", "user": {"login": "bob"}},
+            {"body": "Second guess", "user": {"username": "bob"}, "created_at": "2024-01-02"},
+            {"body": "Found it", "user": {"login": "carol"}, "created_at": "2024-01-03"}
+        ]);
+        let comments = extract_issue_comments(&result);
+        assert_eq!(comments.len(), 3);
+
+        let full = format_agent_context(&issue, &comments, MAX_AGENT_CONTEXT_CHARS);
+        assert_eq!((full.included_comments, full.omitted_comments), (3, 0));
+        assert!(full.text.contains("token=[redacted]"));
+        assert!(!full.text.contains("abc123"));
+        let first = full.text.find("First guess").unwrap();
+        assert!(first < full.text.find("Found it").unwrap());
+
+        let bounded = format_agent_context(&issue, &comments, 160);
+        assert!(bounded.text.chars().count() <= 160);
+        assert_eq!(bounded.included_comments, 1);
+        assert!(bounded.text.contains("Found it"));
+        assert!(bounded.text.contains("(2 earlier comments omitted)"));
+    }
+
+    #[test]
+    fn test_is_assigned_to() {
+        let issue = serde_json::json!({
+            "number": 3,
+            "title": "Fix login",
+            "assignee": null,
+            "assignees": [{"login": "alice"}, {"username": "Agent-Bot"}],
+        });
+        assert!(is_assigned_to(&issue, "agent-bot"));
+        assert!(is_assigned_to(&issue, "alice"));
+        assert!(!is_assigned_to(&issue, "bob"));
+        assert!(!is_assigned_to(&serde_json::json!({"number": 4}), "alice"));
+        assert!(is_assigned_to(
+            &serde_json::json!({"assignee": {"login": "bob"}}),
+            "bob"
+        ));
+
+        assert_eq!(normalize_assignee(" @agent-bot ").unwrap(), "agent-bot");
+        assert!(normalize_assignee("@").is_err());
+        assert!(normalize_assignee("bob is:pr").is_err());
+    }
 }
@@ -0,0 +1,199 @@
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+use tokio::task::JoinSet;
+
+use crate::db::{AgentJobStatus, DbPool};
+use crate::error::AppError;
+use crate::grpc::data::result_output_item::Item;
+use crate::grpc::JobworkerpClient;
+
+/// Tauri event carrying the stream events of every active job
+pub const ALL_JOBS_STREAM_EVENT: &str = "all-jobs-stream";
+
+/// How often the feed looks for newly started jobs
+const FEED_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Stream event of one job in the merged feed
+#[derive(Debug, Clone, Serialize)]
+pub struct JobFeedEvent {
+    pub job_id: i64,
+    pub jobworkerp_job_id: String,
+    /// Same payload as the job's `job-stream-<id>` event
+    pub event: serde_json::Value,
+}
+
+/// Agent job the feed listens to
+#[derive(Debug, Clone, PartialEq)]
+struct ActiveJob {
+    id: i64,
+    jobworkerp_job_id: String,
+}
+
+/// Background task merging active jobs' streams into `all-jobs-stream`
+#[derive(Debug, Default)]
+pub struct JobFeed {
+    task: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+}
+
+/// Jobs whose agent may still be producing output
+fn active_jobs(conn: &Connection) -> Result<Vec<ActiveJob>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, jobworkerp_job_id FROM agent_jobs WHERE status IN {} ORDER BY id",
+        AgentJobStatus::active_sql_list()
+    ))?;
+    let jobs = stmt
+        .query_map([], |row| {
+            Ok(ActiveJob {
+                id: row.get(0)?,
+                jobworkerp_job_id: row.get(1)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(jobs)
+}
+
+/// Forward one job's stream to the merged feed until it ends
+async fn forward_job_stream(
+    app: &AppHandle,
+    grpc: &JobworkerpClient,
+    job: &ActiveJob,
+) -> Result<(), AppError> {
+    let mut stream = grpc.listen_stream(&job.jobworkerp_job_id).await?;
+    while let Some(item) = stream.message().await? {
+        let (event, end) = match item.item {
+            Some(Item::Data(data)) => (serde_json::json!({ "type": "Data", "data": data }), false),
+            Some(Item::FinalCollected(data)) => (
+                serde_json::json!({ "type": "FinalCollected", "data": data }),
+                false,
+            ),
+            Some(Item::End(_)) => (serde_json::json!({ "type": "End" }), true),
+            None => continue,
+        };
+        let _ = app.emit(
+            ALL_JOBS_STREAM_EVENT,
+            JobFeedEvent {
+                job_id: job.id,
+                jobworkerp_job_id: job.jobworkerp_job_id.clone(),
+                event,
+            },
+        );
+        if end {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Poll for active jobs and keep one stream forwarder per job
+///
+/// A job is listened to once; when its stream ends or fails it is not picked
+/// up again while it stays active. Dropping this future (on unsubscribe)
+/// aborts every forwarder.
+async fn run_job_feed(app: AppHandle, db: DbPool, grpc: Arc<JobworkerpClient>) {
+    let mut forwarders = JoinSet::new();
+    let mut seen: HashSet<i64> = HashSet::new();
+    loop {
+        let jobs = db
+            .get()
+            .map_err(|e| AppError::Internal(e.to_string()))
+            .and_then(|conn| active_jobs(&conn));
+        match jobs {
+            Ok(jobs) => {
+                let active: HashSet<i64> = jobs.iter().map(|job| job.id).collect();
+                seen.retain(|id| active.contains(id));
+                for job in jobs {
+                    if !seen.insert(job.id) {
+                        continue;
+                    }
+                    let (app, grpc) = (app.clone(), grpc.clone());
+                    forwarders.spawn(async move {
+                        if let Err(e) = forward_job_stream(&app, &grpc, &job).await {
+                            tracing::warn!("Job feed stopped listening to job {}: {}", job.id, e);
+                        }
+                    });
+                }
+            }
+            Err(e) => tracing::warn!("Job feed failed to load active jobs: {}", e),
+        }
+        // Reap finished forwarders so the set does not grow
+        while forwarders.try_join_next().is_some() {}
+        tokio::time::sleep(FEED_POLL_INTERVAL).await;
+    }
+}
+
+/// Start emitting every active job's stream events as `all-jobs-stream`
+///
+/// Each event is tagged with the local `job_id`. Jobs started later are
+/// picked up automatically. Calling this while subscribed does nothing.
+#[tauri::command]
+pub async fn subscribe_all_jobs(
+    app: AppHandle,
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    feed: State<'_, JobFeed>,
+) -> Result<(), AppError> {
+    let mut task = feed.task.lock().unwrap_or_else(|e| e.into_inner());
+    if task.is_some() {
+        return Ok(());
+    }
+    *task = Some(tauri::async_runtime::spawn(run_job_feed(
+        app,
+        db.inner().clone(),
+        grpc.inner().clone(),
+    )));
+    tracing::info!("Subscribed to the merged job feed");
+    Ok(())
+}
+
+/// Stop the merged job feed started by `subscribe_all_jobs`
+///
+/// Returns whether a feed was running.
+#[tauri::command]
+pub async fn unsubscribe_all_jobs(feed: State<'_, JobFeed>) -> Result<bool, AppError> {
+    let task = feed.task.lock().unwrap_or_else(|e| e.into_inner()).take();
+    match task {
+        Some(task) => {
+            task.abort();
+            tracing::info!("Unsubscribed from the merged job feed");
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_active_jobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let conn = pool.get().unwrap();
+        let repository_id = crate::db::test_support::insert_repository(&conn);
+        for (issue_number, status) in [(1, "RunningAgent"), (2, "Completed"), (3, "Pending")] {
+            conn.execute(
+                "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status)
+                 VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![
+                    repository_id,
+                    issue_number,
+                    format!("j{}", issue_number),
+                    status
+                ],
+            )
+            .unwrap();
+        }
+
+        let ids: Vec<String> = active_jobs(&conn)
+            .unwrap()
+            .into_iter()
+            .map(|job| job.jobworkerp_job_id)
+            .collect();
+        assert_eq!(ids, vec!["j1", "j3"]);
+    }
+}
@@ -1,7 +1,139 @@
-use tauri::State;
+use rusqlite::OptionalExtension;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
 
-use crate::db::{AgentJob, AgentJobStatus, DbPool};
+use super::pulls::build_pr_url;
+use super::worktree::remove_job_worktree;
+use crate::db::events::redact_secrets;
+use crate::db::{
+    job_logs_after, job_status_history, record_event, AgentJob, AgentJobStatus, AppEventType,
+    DbPool, JobLogEntry, JobStatusChange, Platform,
+};
 use crate::error::AppError;
+use crate::grpc::JobworkerpClient;
+use crate::hooks::spawn_post_job_hook;
+use crate::reattach::{reattach_stuck_job, ListenerRegistry};
+use crate::workflow_result::{reparse_all_stored_results, reparse_stored_result, WorkflowResult};
+
+/// Number of backend jobs listed when no limit is given
+const DEFAULT_BACKEND_JOB_LIMIT: i32 = 100;
+
+/// Job queued or running on the jobworkerp-rs backend
+#[derive(Debug, Serialize)]
+pub struct BackendJob {
+    pub jobworkerp_job_id: String,
+    pub worker_id: Option<i64>,
+    pub using: Option<String>,
+    pub enqueue_time: i64,
+    pub retried: u32,
+    /// Whether an agent_jobs row references this job
+    pub tracked: bool,
+}
+
+/// Latest agent job for an issue, with the number of attempts made
+#[derive(Debug, Serialize)]
+pub struct IssueJobSummary {
+    pub issue_number: i32,
+    /// Jobs run for this issue, including the latest
+    pub attempts: i64,
+    pub latest: AgentJob,
+}
+
+/// Agent job that opened a pull request, with a link to it
+#[derive(Debug, Serialize)]
+pub struct JobWithPr {
+    pub job_id: i64,
+    pub repository_id: i64,
+    pub issue_number: i32,
+    pub pr_number: i32,
+    pub pr_url: String,
+    pub status: AgentJobStatus,
+}
+
+/// Self-contained, redacted record of one agent job for bug reports
+///
+/// Paths are reduced to the worktree directory name (the absolute prefix and
+/// home directory are masked wherever they appear) and credentials are
+/// redacted from all free text.
+#[derive(Debug, Serialize)]
+pub struct JobReport {
+    pub app_version: String,
+    pub generated_at: String,
+    pub platform: Platform,
+    pub repository: String,
+    pub job: AgentJob,
+    pub logs: Vec<JobLogEntry>,
+}
+
+/// Value of one field in two compared jobs
+#[derive(Debug, Serialize)]
+pub struct FieldDiff<T> {
+    pub a: T,
+    pub b: T,
+    pub changed: bool,
+}
+
+impl<T: PartialEq> FieldDiff<T> {
+    fn new(a: T, b: T) -> Self {
+        let changed = a != b;
+        Self { a, b, changed }
+    }
+}
+
+/// Statuses each compared job went through
+#[derive(Debug, Serialize)]
+pub struct StatusTimelineDiff {
+    pub a: Vec<JobStatusChange>,
+    pub b: Vec<JobStatusChange>,
+    /// Number of leading statuses both jobs went through in the same order
+    pub common_prefix: usize,
+}
+
+/// Differences between two agent jobs run for the same issue
+#[derive(Debug, Serialize)]
+pub struct JobComparison {
+    pub repository_id: i64,
+    pub issue_number: i32,
+    pub job_a: AgentJob,
+    pub job_b: AgentJob,
+    pub timeline: StatusTimelineDiff,
+    pub status: FieldDiff<AgentJobStatus>,
+    /// Creation to last update of a finished job; None while it runs
+    pub duration_secs: FieldDiff<Option<i64>>,
+    pub error_message: FieldDiff<Option<String>>,
+    pub branch_name: FieldDiff<Option<String>>,
+    pub pr_number: FieldDiff<Option<i32>>,
+    pub pr_url: FieldDiff<Option<String>>,
+    pub commit_sha: FieldDiff<Option<String>>,
+    pub files_changed: FieldDiff<Option<i64>>,
+}
+
+/// Map a row selected with the standard agent_jobs column list
+fn agent_job_from_row(row: &rusqlite::Row) -> rusqlite::Result<AgentJob> {
+    let status_str: String = row.get(4)?;
+    Ok(AgentJob {
+        id: row.get(0)?,
+        repository_id: row.get(1)?,
+        issue_number: row.get(2)?,
+        jobworkerp_job_id: row.get(3)?,
+        status: status_str.parse().unwrap_or_else(|e| {
+            tracing::warn!("agent job {:?}: {}", row.get::<_, i64>(0), e);
+            AgentJobStatus::Pending
+        }),
+        worktree_path: row.get(5)?,
+        branch_name: row.get(6)?,
+        pr_number: row.get(7)?,
+        error_message: row.get(8)?,
+        commit_sha: row.get(9)?,
+        files_changed: row.get(10)?,
+        summary: row.get(11)?,
+        created_at: row.get(12)?,
+        updated_at: row.get(13)?,
+        pr_url: row.get(14)?,
+    })
+}
 
 #[tauri::command]
 pub async fn list_jobs(
@@ -13,7 +145,8 @@ pub async fn list_jobs(
 
     let mut sql = String::from(
         "SELECT id, repository_id, issue_number, jobworkerp_job_id, status,
-                worktree_path, branch_name, pr_number, error_message, created_at, updated_at
+                worktree_path, branch_name, pr_number, error_message, commit_sha,
+                files_changed, summary, created_at, updated_at, pr_url
          FROM agent_jobs WHERE 1=1",
     );
     let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
@@ -34,22 +167,7 @@ pub async fn list_jobs(
     let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
 
     let jobs = stmt
-        .query_map(params_ref.as_slice(), |row| {
-            let status_str: String = row.get(4)?;
-            Ok(AgentJob {
-                id: row.get(0)?,
-                repository_id: row.get(1)?,
-                issue_number: row.get(2)?,
-                jobworkerp_job_id: row.get(3)?,
-                status: status_str.parse().unwrap_or(AgentJobStatus::Pending),
-                worktree_path: row.get(5)?,
-                branch_name: row.get(6)?,
-                pr_number: row.get(7)?,
-                error_message: row.get(8)?,
-                created_at: row.get(9)?,
-                updated_at: row.get(10)?,
-            })
-        })?
+        .query_map(params_ref.as_slice(), agent_job_from_row)?
         .collect::<Result<Vec<_>, _>>()?;
 
     Ok(jobs)
@@ -58,29 +176,894 @@ pub async fn list_jobs(
 #[tauri::command]
 pub async fn get_job(db: State<'_, DbPool>, id: i64) -> Result<AgentJob, AppError> {
     let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    find_job(&conn, id)
+}
 
+/// Load one agent job by its local ID
+pub(crate) fn find_job(conn: &rusqlite::Connection, id: i64) -> Result<AgentJob, AppError> {
     let mut stmt = conn.prepare(
         "SELECT id, repository_id, issue_number, jobworkerp_job_id, status,
-                worktree_path, branch_name, pr_number, error_message, created_at, updated_at
+                worktree_path, branch_name, pr_number, error_message, commit_sha,
+                files_changed, summary, created_at, updated_at, pr_url
          FROM agent_jobs WHERE id = ?1",
     )?;
 
-    let job = stmt.query_row([id], |row| {
-        let status_str: String = row.get(4)?;
-        Ok(AgentJob {
-            id: row.get(0)?,
-            repository_id: row.get(1)?,
-            issue_number: row.get(2)?,
-            jobworkerp_job_id: row.get(3)?,
-            status: status_str.parse().unwrap_or(AgentJobStatus::Pending),
-            worktree_path: row.get(5)?,
-            branch_name: row.get(6)?,
-            pr_number: row.get(7)?,
-            error_message: row.get(8)?,
-            created_at: row.get(9)?,
-            updated_at: row.get(10)?,
-        })
-    })?;
+    let job = stmt.query_row([id], agent_job_from_row)?;
 
     Ok(job)
 }
+
+/// Export one agent job, with its log, as a shareable JSON report
+#[tauri::command]
+pub async fn export_job_report(db: State<'_, DbPool>, job_id: i64) -> Result<JobReport, AppError> {
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    let home = directories::UserDirs::new().map(|dirs| dirs.home_dir().display().to_string());
+    build_job_report(&conn, job_id, home.as_deref())
+}
+
+fn build_job_report(
+    conn: &rusqlite::Connection,
+    job_id: i64,
+    home_dir: Option<&str>,
+) -> Result<JobReport, AppError> {
+    let (job, platform, repository) = conn
+        .query_row(
+            "SELECT j.id, j.repository_id, j.issue_number, j.jobworkerp_job_id, j.status,
+                    j.worktree_path, j.branch_name, j.pr_number, j.error_message, j.commit_sha,
+                    j.files_changed, j.summary, j.created_at, j.updated_at, j.pr_url,
+                    r.platform, r.name
+             FROM agent_jobs j JOIN repositories r ON r.id = j.repository_id
+             WHERE j.id = ?1",
+            [job_id],
+            |row| {
+                let platform: String = row.get(15)?;
+                Ok((
+                    agent_job_from_row(row)?,
+                    platform.parse().unwrap_or(Platform::GitHub),
+                    row.get::<_, String>(16)?,
+                ))
+            },
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => {
+                AppError::NotFound(format!("Job with id {} not found", job_id))
+            }
+            _ => AppError::from(e),
+        })?;
+
+    // Longest prefixes first so the worktree path is masked before the home directory
+    let worktree_dir = job
+        .worktree_path
+        .as_deref()
+        .map(std::path::Path::new)
+        .and_then(|path| path.parent())
+        .map(|parent| parent.display().to_string());
+    let mut prefixes: Vec<(&str, &str)> = Vec::new();
+    if let Some(dir) = worktree_dir.as_deref().filter(|d| !d.is_empty()) {
+        prefixes.push((dir, "<worktrees>"));
+    }
+    if let Some(home) = home_dir.filter(|h| !h.is_empty()) {
+        prefixes.push((home, "~"));
+    }
+    let scrub = |text: &str| {
+        let masked = prefixes
+            .iter()
+            .fold(text.to_string(), |acc, (prefix, mask)| {
+                acc.replace(prefix, mask)
+            });
+        redact_secrets(&masked)
+    };
+
+    let logs = job_logs_after(conn, job_id, 0)?
+        .into_iter()
+        .map(|entry| JobLogEntry {
+            chunk: scrub(&entry.chunk),
+            ..entry
+        })
+        .collect();
+
+    Ok(JobReport {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        platform,
+        repository: redact_secrets(&repository),
+        job: AgentJob {
+            worktree_path: job
+                .worktree_path
+                .as_deref()
+                .map(std::path::Path::new)
+                .and_then(|path| path.file_name())
+                .map(|name| name.to_string_lossy().into_owned()),
+            error_message: job.error_message.as_deref().map(&scrub),
+            summary: job.summary.as_deref().map(&scrub),
+            ..job
+        },
+        logs,
+    })
+}
+
+/// Record the pull request opened by an agent job
+///
+/// When the workflow reported only the PR number, the link is built from the
+/// repository URL. Returns the stored link.
+pub(crate) fn update_job_with_pr(
+    conn: &rusqlite::Connection,
+    job_id: i64,
+    pr_number: i32,
+    pr_url: Option<&str>,
+) -> Result<String, AppError> {
+    let (repo_url, platform): (String, String) = conn
+        .query_row(
+            "SELECT r.url, r.platform
+             FROM agent_jobs j JOIN repositories r ON r.id = j.repository_id
+             WHERE j.id = ?1",
+            [job_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound(format!("Job with id {} not found", job_id)))?;
+    let pr_url = match pr_url.map(str::trim).filter(|url| !url.is_empty()) {
+        Some(url) => url.to_string(),
+        None => build_pr_url(
+            &repo_url,
+            pr_number,
+            platform.parse().unwrap_or(Platform::GitHub),
+        ),
+    };
+
+    conn.execute(
+        "UPDATE agent_jobs SET status = 'PrCreated', pr_number = ?2, pr_url = ?3,
+                               updated_at = datetime('now')
+         WHERE id = ?1",
+        rusqlite::params![job_id, pr_number, pr_url],
+    )?;
+    tracing::info!("Job {} opened PR #{}: {}", job_id, pr_number, pr_url);
+    Ok(pr_url)
+}
+
+/// List a repository's issues that have agent jobs, with each issue's latest job
+///
+/// Issues are ordered by their latest job, most recent first.
+#[tauri::command]
+pub async fn jobs_by_issue(
+    db: State<'_, DbPool>,
+    repository_id: i64,
+) -> Result<Vec<IssueJobSummary>, AppError> {
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    query_jobs_by_issue(&conn, repository_id)
+}
+
+fn query_jobs_by_issue(
+    conn: &rusqlite::Connection,
+    repository_id: i64,
+) -> Result<Vec<IssueJobSummary>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, repository_id, issue_number, jobworkerp_job_id, status,
+                worktree_path, branch_name, pr_number, error_message, commit_sha,
+                files_changed, summary, created_at, updated_at, pr_url, attempts
+         FROM (
+             SELECT *,
+                    COUNT(*) OVER (PARTITION BY issue_number) AS attempts,
+                    ROW_NUMBER() OVER (
+                        PARTITION BY issue_number ORDER BY created_at DESC, id DESC
+                    ) AS attempt_rank
+             FROM agent_jobs WHERE repository_id = ?1
+         )
+         WHERE attempt_rank = 1
+         ORDER BY created_at DESC, id DESC",
+    )?;
+
+    let summaries = stmt
+        .query_map([repository_id], |row| {
+            let latest = agent_job_from_row(row)?;
+            Ok(IssueJobSummary {
+                issue_number: latest.issue_number,
+                attempts: row.get(15)?,
+                latest,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(summaries)
+}
+
+/// List the job statuses present for a repository, for a status filter
+///
+/// Statuses are returned in `AgentJobStatus::ALL` order.
+#[tauri::command]
+pub async fn distinct_job_statuses(
+    db: State<'_, DbPool>,
+    repository_id: i64,
+) -> Result<Vec<AgentJobStatus>, AppError> {
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    query_distinct_job_statuses(&conn, repository_id)
+}
+
+fn query_distinct_job_statuses(
+    conn: &rusqlite::Connection,
+    repository_id: i64,
+) -> Result<Vec<AgentJobStatus>, AppError> {
+    let mut stmt =
+        conn.prepare("SELECT DISTINCT status FROM agent_jobs WHERE repository_id = ?1")?;
+    let present: Vec<AgentJobStatus> = stmt
+        .query_map([repository_id], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter_map(|status| match status.parse() {
+            Ok(status) => Some(status),
+            Err(_) => {
+                // The CHECK constraint should prevent this; see check_status_constraint
+                tracing::warn!(
+                    "Skipping unknown job status '{}' in repository {}",
+                    status,
+                    repository_id
+                );
+                None
+            }
+        })
+        .collect();
+
+    Ok(AgentJobStatus::ALL
+        .into_iter()
+        .filter(|status| present.contains(status))
+        .collect())
+}
+
+/// List agent jobs that produced a pull request, most recent first
+///
+/// `state` filters by job status (e.g. `PrCreated`, `Merged`).
+#[tauri::command]
+pub async fn list_jobs_with_prs(
+    db: State<'_, DbPool>,
+    state: Option<String>,
+    repository_id: Option<i64>,
+) -> Result<Vec<JobWithPr>, AppError> {
+    let status = state
+        .map(|s| {
+            s.parse::<AgentJobStatus>()
+                .map_err(|e| AppError::validation("state", e))
+        })
+        .transpose()?;
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    query_jobs_with_prs(&conn, status, repository_id)
+}
+
+fn query_jobs_with_prs(
+    conn: &rusqlite::Connection,
+    status: Option<AgentJobStatus>,
+    repository_id: Option<i64>,
+) -> Result<Vec<JobWithPr>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT j.id, j.repository_id, j.issue_number, j.pr_number, j.status, r.url, r.platform,
+                j.pr_url
+         FROM agent_jobs j JOIN repositories r ON r.id = j.repository_id
+         WHERE j.pr_number IS NOT NULL
+           AND (?1 IS NULL OR j.status = ?1)
+           AND (?2 IS NULL OR j.repository_id = ?2)
+         ORDER BY j.created_at DESC, j.id DESC",
+    )?;
+
+    let jobs = stmt
+        .query_map(
+            rusqlite::params![status.map(|s| s.to_string()), repository_id],
+            |row| {
+                let job_id: i64 = row.get(0)?;
+                let pr_number: i32 = row.get(3)?;
+                let status_str: String = row.get(4)?;
+                let repo_url: String = row.get(5)?;
+                let platform_str: String = row.get(6)?;
+                let platform = platform_str.parse().unwrap_or(Platform::GitHub);
+                let pr_url: Option<String> = row.get(7)?;
+                Ok(JobWithPr {
+                    job_id,
+                    repository_id: row.get(1)?,
+                    issue_number: row.get(2)?,
+                    pr_number,
+                    pr_url: pr_url.unwrap_or_else(|| build_pr_url(&repo_url, pr_number, platform)),
+                    status: status_str.parse().unwrap_or_else(|e| {
+                        tracing::warn!("agent job {}: {}", job_id, e);
+                        AgentJobStatus::Pending
+                    }),
+                })
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(jobs)
+}
+
+/// Compare two agent jobs run for the same issue, e.g. a failed attempt and its retry
+#[tauri::command]
+pub async fn compare_jobs(
+    db: State<'_, DbPool>,
+    job_id_a: i64,
+    job_id_b: i64,
+) -> Result<JobComparison, AppError> {
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    build_job_comparison(&conn, job_id_a, job_id_b)
+}
+
+/// Load a job with its run time, if it has finished
+fn job_with_duration(
+    conn: &rusqlite::Connection,
+    job_id: i64,
+) -> Result<(AgentJob, Option<i64>), AppError> {
+    conn.query_row(
+        &format!(
+            "SELECT id, repository_id, issue_number, jobworkerp_job_id, status,
+                    worktree_path, branch_name, pr_number, error_message, commit_sha,
+                    files_changed, summary, created_at, updated_at, pr_url,
+                    CASE WHEN status IN {}
+                         THEN CAST(ROUND((julianday(updated_at) - julianday(created_at)) * 86400) AS INTEGER)
+                    END
+             FROM agent_jobs WHERE id = ?1",
+            AgentJobStatus::terminal_sql_list()
+        ),
+        [job_id],
+        |row| Ok((agent_job_from_row(row)?, row.get(15)?)),
+    )
+    .optional()?
+    .ok_or_else(|| AppError::NotFound(format!("Job with id {} not found", job_id)))
+}
+
+fn build_job_comparison(
+    conn: &rusqlite::Connection,
+    job_id_a: i64,
+    job_id_b: i64,
+) -> Result<JobComparison, AppError> {
+    if job_id_a == job_id_b {
+        return Err(AppError::InvalidInput(
+            "Choose two different jobs to compare".into(),
+        ));
+    }
+    let (job_a, duration_a) = job_with_duration(conn, job_id_a)?;
+    let (job_b, duration_b) = job_with_duration(conn, job_id_b)?;
+    if (job_a.repository_id, job_a.issue_number) != (job_b.repository_id, job_b.issue_number) {
+        return Err(AppError::InvalidInput(format!(
+            "Job {} and job {} were not run for the same issue",
+            job_id_a, job_id_b
+        )));
+    }
+
+    let timeline_a = job_status_history(conn, job_id_a)?;
+    let timeline_b = job_status_history(conn, job_id_b)?;
+    let common_prefix = timeline_a
+        .iter()
+        .zip(&timeline_b)
+        .take_while(|(a, b)| a.status == b.status)
+        .count();
+
+    Ok(JobComparison {
+        repository_id: job_a.repository_id,
+        issue_number: job_a.issue_number,
+        timeline: StatusTimelineDiff {
+            a: timeline_a,
+            b: timeline_b,
+            common_prefix,
+        },
+        status: FieldDiff::new(job_a.status, job_b.status),
+        duration_secs: FieldDiff::new(duration_a, duration_b),
+        error_message: FieldDiff::new(job_a.error_message.clone(), job_b.error_message.clone()),
+        branch_name: FieldDiff::new(job_a.branch_name.clone(), job_b.branch_name.clone()),
+        pr_number: FieldDiff::new(job_a.pr_number, job_b.pr_number),
+        pr_url: FieldDiff::new(job_a.pr_url.clone(), job_b.pr_url.clone()),
+        commit_sha: FieldDiff::new(job_a.commit_sha.clone(), job_b.commit_sha.clone()),
+        files_changed: FieldDiff::new(job_a.files_changed, job_b.files_changed),
+        job_a,
+        job_b,
+    })
+}
+
+/// List jobs on the backend, flagging those without a local agent_jobs row
+///
+/// Orphans can come from a crashed session or another client.
+#[tauri::command]
+pub async fn list_backend_jobs(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    limit: Option<i32>,
+) -> Result<Vec<BackendJob>, AppError> {
+    let jobs = grpc
+        .list_jobs(Some(limit.unwrap_or(DEFAULT_BACKEND_JOB_LIMIT)))
+        .await?;
+
+    let tracked_ids: HashSet<String> = {
+        let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+        let mut stmt = conn.prepare("SELECT jobworkerp_job_id FROM agent_jobs")?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<HashSet<_>, _>>()?;
+        ids
+    };
+
+    Ok(jobs
+        .into_iter()
+        .filter_map(|job| {
+            let id = job.id?.value.to_string();
+            let data = job.data.unwrap_or_default();
+            Some(BackendJob {
+                tracked: tracked_ids.contains(&id),
+                jobworkerp_job_id: id,
+                worker_id: data.worker_id.map(|w| w.value),
+                using: data.using,
+                enqueue_time: data.enqueue_time,
+                retried: data.retried,
+            })
+        })
+        .collect())
+}
+
+/// Cancel an agent job: delete it on the backend and mark it Cancelled
+///
+/// With `cleanup_worktree`, the job's worktree is removed as well, but only
+/// after the backend has confirmed the deletion, so the agent no longer uses it.
+/// Cancelling a job that is already Cancelled only retries that cleanup;
+/// cancelling one that finished otherwise is rejected.
+#[tauri::command]
+pub async fn agent_cancel(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    jobworkerp_job_id: String,
+    cleanup_worktree: Option<bool>,
+) -> Result<(), AppError> {
+    cancel_agent_job(
+        &db,
+        &jobworkerp_job_id,
+        cleanup_worktree.unwrap_or(false),
+        || grpc.delete_job(&jobworkerp_job_id),
+    )
+    .await
+}
+
+/// Listen again to a running job whose result listener died
+///
+/// Unlike the reattach at startup, this is for one stuck job the user picks.
+/// Output is appended to the job log and emitted on `job-stream-<id>`, and the
+/// job gets its final status when the stream ends. Fails when the job is not
+/// running or is already listened to.
+#[tauri::command]
+pub async fn reattach_job(
+    app: AppHandle,
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    listeners: State<'_, ListenerRegistry>,
+    job_id: i64,
+) -> Result<(), AppError> {
+    reattach_stuck_job(&app, &db, &grpc, &listeners, job_id)
+}
+
+/// Parse a finished job's stored workflow output again
+///
+/// After a fix to the workflow's output format, this updates the job from the
+/// output it already produced, without running the agent again. Returns the
+/// new result; running, merged and cancelled jobs are rejected.
+#[tauri::command]
+pub async fn reparse_job_result(
+    db: State<'_, DbPool>,
+    job_id: i64,
+) -> Result<WorkflowResult, AppError> {
+    let mut conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    reparse_stored_result(&mut conn, job_id)
+}
+
+/// Parse every finished job's stored workflow output again
+///
+/// A migration path after a workflow output format change: jobs are updated
+/// from the output they already produced. Returns how many jobs changed.
+#[tauri::command]
+pub async fn reparse_all_job_results(db: State<'_, DbPool>) -> Result<usize, AppError> {
+    let mut conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    reparse_all_stored_results(&mut conn)
+}
+
+/// Cancel a job with `delete_backend_job` deleting it on the backend
+///
+/// Nothing is written until the backend deletion succeeds; the status update
+/// and its event are then committed together, and the post-job hook fires. A
+/// job already gone from the backend counts as deleted, so cancelling again
+/// after a crash between the two steps completes the cancellation.
+async fn cancel_agent_job<F, Fut>(
+    db: &DbPool,
+    jobworkerp_job_id: &str,
+    cleanup_worktree: bool,
+    delete_backend_job: F,
+) -> Result<(), AppError>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<(), AppError>>,
+{
+    let (job_id, status): (i64, String) = {
+        let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+        conn.query_row(
+            "SELECT id, status FROM agent_jobs WHERE jobworkerp_job_id = ?1",
+            [jobworkerp_job_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound(format!("Job {} not found", jobworkerp_job_id)))?
+    };
+    let status: AgentJobStatus = status.parse().map_err(AppError::Internal)?;
+    if status == AgentJobStatus::Cancelled {
+        // An earlier cancel may have stopped before removing the worktree
+        if cleanup_worktree {
+            remove_cancelled_worktree(db, job_id).await?;
+        }
+        return Ok(());
+    }
+    // A finished job's backend job is gone or reused; leave it alone
+    if !status.is_active() {
+        return Err(AppError::validation(
+            "jobworkerp_job_id",
+            format!("Job {} already finished ({})", jobworkerp_job_id, status),
+        ));
+    }
+
+    match delete_backend_job().await {
+        Ok(()) => {}
+        Err(AppError::NotFound(_)) => {
+            tracing::info!(
+                "Job {} is no longer on the backend; marking it cancelled",
+                jobworkerp_job_id
+            );
+        }
+        Err(e) => return Err(e),
+    }
+
+    let cancelled = {
+        let mut conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+        let tx = conn.transaction()?;
+        let updated = tx.execute(
+            &format!(
+                "UPDATE agent_jobs SET status = 'Cancelled', updated_at = datetime('now')
+                 WHERE id = ?1 AND status IN {}",
+                AgentJobStatus::active_sql_list()
+            ),
+            [job_id],
+        )?;
+        if updated > 0 {
+            record_event(
+                &tx,
+                AppEventType::JobFinished,
+                &format!("Job {} cancelled", job_id),
+            );
+        }
+        tx.commit()?;
+        if updated > 0 {
+            Some(find_job(&conn, job_id)?)
+        } else {
+            None
+        }
+    };
+    if let Some(job) = cancelled {
+        spawn_post_job_hook(db, &job);
+    }
+
+    if cleanup_worktree {
+        remove_cancelled_worktree(db, job_id).await?;
+    }
+    Ok(())
+}
+
+/// Remove a cancelled job's worktree; the error says the cancel itself went through
+async fn remove_cancelled_worktree(db: &DbPool, job_id: i64) -> Result<(), AppError> {
+    remove_job_worktree(db, job_id).await.map_err(|e| {
+        AppError::Internal(format!(
+            "Job cancelled, but removing its worktree failed: {}",
+            e
+        ))
+    })
+}
+
+/// Cancel a job on the backend
+#[tauri::command]
+pub async fn cancel_backend_job(
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    jobworkerp_job_id: String,
+) -> Result<(), AppError> {
+    grpc.delete_job(&jobworkerp_job_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jobs_by_issue_groups_attempts() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let conn = pool.get().unwrap();
+        let repository_id = crate::db::test_support::insert_repository(&conn);
+        for (issue, job_id, status, created_at) in [
+            (1, "j1", "Failed", "2024-01-01 00:00:00"),
+            (1, "j2", "PrCreated", "2024-01-02 00:00:00"),
+            (2, "j3", "Pending", "2024-01-03 00:00:00"),
+        ] {
+            conn.execute(
+                "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![repository_id, issue, job_id, status, created_at],
+            )
+            .unwrap();
+        }
+
+        let summaries = query_jobs_by_issue(&conn, repository_id).unwrap();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(
+            query_distinct_job_statuses(&conn, repository_id).unwrap(),
+            vec![
+                AgentJobStatus::Pending,
+                AgentJobStatus::PrCreated,
+                AgentJobStatus::Failed
+            ]
+        );
+        assert_eq!(summaries[0].issue_number, 2);
+        assert_eq!(summaries[0].attempts, 1);
+        assert_eq!(summaries[1].issue_number, 1);
+        assert_eq!(summaries[1].attempts, 2);
+        assert_eq!(summaries[1].latest.jobworkerp_job_id, "j2");
+        assert_eq!(summaries[1].latest.status, AgentJobStatus::PrCreated);
+    }
+
+    #[test]
+    fn test_query_jobs_with_prs() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let conn = pool.get().unwrap();
+        conn.execute(
+            "INSERT INTO repositories (mcp_server_name, platform, base_url, name, url, owner, repo_name)
+             VALUES ('gitea', 'Gitea', 'https://gitea.local/api/v1', 'octo/app',
+                     'https://gitea.local/octo/app', 'octo', 'app')",
+            [],
+        )
+        .unwrap();
+        let repository_id = conn.last_insert_rowid();
+        for (issue, job_id, status, pr_number) in [
+            (1, "j1", "Failed", None),
+            (2, "j2", "PrCreated", Some(10)),
+            (3, "j3", "Merged", Some(11)),
+        ] {
+            conn.execute(
+                "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status, pr_number)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![repository_id, issue, job_id, status, pr_number],
+            )
+            .unwrap();
+        }
+
+        let jobs = query_jobs_with_prs(&conn, None, Some(repository_id)).unwrap();
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[1].pr_url, "https://gitea.local/octo/app/pulls/10");
+        let merged = query_jobs_with_prs(&conn, Some(AgentJobStatus::Merged), None).unwrap();
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].issue_number, 3);
+        assert_eq!(merged[0].pr_url, "https://gitea.local/octo/app/pulls/11");
+    }
+
+    #[test]
+    fn test_update_job_with_pr() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let conn = pool.get().unwrap();
+        let repository_id = crate::db::test_support::insert_repository(&conn);
+        conn.execute(
+            "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status)
+             VALUES (?1, 7, 'j1', 'CreatingPR')",
+            [repository_id],
+        )
+        .unwrap();
+        let job_id = conn.last_insert_rowid();
+
+        assert_eq!(
+            update_job_with_pr(&conn, job_id, 12, None).unwrap(),
+            "https://github.com/octo/app/pull/12"
+        );
+        let reported = "https://github.com/octo/app/pull/13";
+        assert_eq!(
+            update_job_with_pr(&conn, job_id, 13, Some(reported)).unwrap(),
+            reported
+        );
+        let (status, pr_url): (String, String) = conn
+            .query_row(
+                "SELECT status, pr_url FROM agent_jobs WHERE id = ?1",
+                [job_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(status, "PrCreated");
+        assert_eq!(pr_url, reported);
+        assert!(matches!(
+            update_job_with_pr(&conn, job_id + 1, 1, None),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_job_report_redacts_paths_and_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let mut conn = pool.get().unwrap();
+        let repository_id = crate::db::test_support::insert_repository(&conn);
+        conn.execute(
+            "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status,
+                                     worktree_path, error_message)
+             VALUES (?1, 7, 'j1', 'Failed', '/home/dev/worktrees/octo__app-7',
+                     'git push failed in /home/dev/worktrees/octo__app-7: token=abc123')",
+            [repository_id],
+        )
+        .unwrap();
+        let job_id = conn.last_insert_rowid();
+        crate::db::append_job_log(&mut conn, job_id, "reading /home/dev/.gitconfig").unwrap();
+
+        let report = build_job_report(&conn, job_id, Some("/home/dev")).unwrap();
+        assert_eq!(report.repository, "octo/app");
+        assert_eq!(report.job.worktree_path.as_deref(), Some("octo__app-7"));
+        assert_eq!(
+            report.job.error_message.as_deref(),
+            Some("git push failed in <worktrees>/octo__app-7: token=[redacted]")
+        );
+        assert_eq!(report.logs[0].chunk, "reading ~/.gitconfig");
+        assert!(matches!(
+            build_job_report(&conn, job_id + 1, None),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_job_comparison() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let conn = pool.get().unwrap();
+        let repository_id = crate::db::test_support::insert_repository(&conn);
+        let insert = |issue: i32, created_at: &str| {
+            conn.execute(
+                "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status, created_at)
+                 VALUES (?1, ?2, 'j', 'Pending', ?3)",
+                rusqlite::params![repository_id, issue, created_at],
+            )
+            .unwrap();
+            conn.last_insert_rowid()
+        };
+        let failed = insert(7, "2024-01-01 00:00:00");
+        let retried = insert(7, "2024-01-02 00:00:00");
+        let other_issue = insert(8, "2024-01-02 00:00:00");
+        conn.execute(
+            "UPDATE agent_jobs SET status = 'RunningAgent' WHERE id IN (?1, ?2)",
+            [failed, retried],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE agent_jobs SET status = 'Failed', error_message = 'tests failed',
+                                   updated_at = '2024-01-01 00:10:00'
+             WHERE id = ?1",
+            [failed],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE agent_jobs SET status = 'PrCreated', branch_name = 'fix-7', pr_number = 12,
+                                   updated_at = '2024-01-02 00:04:00'
+             WHERE id = ?1",
+            [retried],
+        )
+        .unwrap();
+
+        let comparison = build_job_comparison(&conn, failed, retried).unwrap();
+        assert_eq!(comparison.issue_number, 7);
+        assert_eq!(comparison.timeline.common_prefix, 2);
+        assert_eq!(comparison.timeline.a.len(), 3);
+        assert_eq!(
+            (comparison.duration_secs.a, comparison.duration_secs.b),
+            (Some(600), Some(240))
+        );
+        assert!(comparison.status.changed);
+        assert!(comparison.error_message.changed);
+        assert!(comparison.pr_number.changed);
+        assert!(!comparison.commit_sha.changed);
+
+        assert!(matches!(
+            build_job_comparison(&conn, failed, other_issue),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            build_job_comparison(&conn, failed, failed),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            build_job_comparison(&conn, failed, other_issue + 1),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_agent_job_writes_only_after_backend_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let base = dir.path().join("worktrees");
+        let worktree = base.join("octo__app-7");
+        std::fs::create_dir_all(&worktree).unwrap();
+        {
+            let conn = pool.get().unwrap();
+            conn.execute(
+                "UPDATE app_settings SET worktree_base_path = ?1 WHERE id = 1",
+                [base.to_str().unwrap()],
+            )
+            .unwrap();
+            let repository_id = crate::db::test_support::insert_repository(&conn);
+            conn.execute(
+                "UPDATE repositories SET repo_slug = 'octo__app' WHERE id = ?1",
+                [repository_id],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status, worktree_path)
+                 VALUES (?1, 7, '42', 'RunningAgent', ?2)",
+                rusqlite::params![repository_id, worktree.to_str().unwrap()],
+            )
+            .unwrap();
+        }
+        let status = || -> String {
+            pool.get()
+                .unwrap()
+                .query_row(
+                    "SELECT status FROM agent_jobs WHERE jobworkerp_job_id = '42'",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap()
+        };
+        let events = || -> i64 {
+            pool.get()
+                .unwrap()
+                .query_row("SELECT COUNT(*) FROM app_events", [], |row| row.get(0))
+                .unwrap()
+        };
+
+        // Backend delete fails midway: nothing is written or removed
+        let result = cancel_agent_job(&pool, "42", true, || async {
+            Err(AppError::Grpc("connection reset".to_string()))
+        })
+        .await;
+        assert!(matches!(result, Err(AppError::Grpc(_))));
+        assert_eq!(status(), "RunningAgent");
+        assert_eq!(events(), 0);
+        assert!(worktree.exists());
+
+        // Retrying after the job vanished from the backend completes the cancel
+        cancel_agent_job(&pool, "42", false, || async {
+            Err(AppError::NotFound("Job 42".to_string()))
+        })
+        .await
+        .unwrap();
+        assert_eq!(status(), "Cancelled");
+        assert_eq!(events(), 1);
+
+        // Cancelling again does not reach the backend, but retries the cleanup
+        cancel_agent_job(&pool, "42", false, || async {
+            panic!("backend called for a cancelled job")
+        })
+        .await
+        .unwrap();
+        assert!(worktree.exists());
+        cancel_agent_job(&pool, "42", true, || async {
+            panic!("backend called for a cancelled job")
+        })
+        .await
+        .unwrap();
+        assert!(!worktree.exists());
+        assert_eq!(events(), 1);
+
+        // A job that finished otherwise is rejected before the backend is reached
+        pool.get()
+            .unwrap()
+            .execute(
+                "UPDATE agent_jobs SET status = 'Completed' WHERE jobworkerp_job_id = '42'",
+                [],
+            )
+            .unwrap();
+        let result = cancel_agent_job(&pool, "42", false, || async {
+            panic!("backend called for a completed job")
+        })
+        .await;
+        assert!(matches!(result, Err(AppError::Validation { .. })));
+        assert_eq!(status(), "Completed");
+    }
+}
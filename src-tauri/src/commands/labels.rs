@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::State;
+
+use crate::db::{get_repository_by_id, DbPool, Label, Platform};
+use crate::error::AppError;
+use crate::grpc::payload::{normalize_mcp_payload, payload_items};
+use crate::grpc::tool_overrides::McpOperation;
+use crate::grpc::JobworkerpClient;
+
+/// How long fetched labels are reused; labels change rarely
+const LABEL_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Keys holding the label list in object-shaped payloads
+const LABEL_LIST_KEYS: &[&str] = &["labels", "items"];
+
+/// Labels per repository, kept for `LABEL_CACHE_TTL`
+#[derive(Debug, Default)]
+pub struct LabelCache {
+    entries: Mutex<HashMap<i64, (Instant, Vec<Label>)>>,
+}
+
+impl LabelCache {
+    fn get(&self, repository_id: i64, now: Instant) -> Option<Vec<Label>> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(&repository_id)
+            .filter(|(fetched_at, _)| now.duration_since(*fetched_at) < LABEL_CACHE_TTL)
+            .map(|(_, labels)| labels.clone())
+    }
+
+    fn insert(&self, repository_id: i64, labels: Vec<Label>, now: Instant) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(repository_id, (now, labels));
+    }
+}
+
+/// Get the MCP tool name for listing repository labels based on platform
+fn get_list_labels_tool(platform: Platform) -> &'static str {
+    match platform {
+        Platform::GitHub => "list_label",
+        Platform::Gitea => "list_repo_labels",
+    }
+}
+
+/// Parse a label (GitHub and Gitea share the shape; Gitea prefixes colors with `#`)
+fn parse_label(value: &serde_json::Value) -> Option<Label> {
+    let name = value.get("name")?.as_str()?.to_string();
+    let color = value
+        .get("color")
+        .and_then(|v| v.as_str())
+        .map(|c| c.trim_start_matches('#').to_ascii_lowercase())
+        .filter(|c| !c.is_empty());
+    let description = value
+        .get("description")
+        .and_then(|v| v.as_str())
+        .filter(|d| !d.is_empty())
+        .map(String::from);
+    Some(Label {
+        name,
+        color,
+        description,
+    })
+}
+
+fn extract_labels(result: &serde_json::Value) -> Vec<Label> {
+    let payload = normalize_mcp_payload(result);
+    let mut labels: Vec<Label> = payload_items(&payload, LABEL_LIST_KEYS)
+        .map(|items| items.iter().filter_map(parse_label).collect())
+        .unwrap_or_default();
+    labels.sort_by_key(|label| label.name.to_lowercase());
+    labels
+}
+
+/// List a repository's labels, for label filters
+///
+/// Results are cached for ten minutes; pass `refresh` to fetch them again.
+#[tauri::command]
+pub async fn list_repository_labels(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    cache: State<'_, LabelCache>,
+    repository_id: i64,
+    refresh: Option<bool>,
+) -> Result<Vec<Label>, AppError> {
+    if !refresh.unwrap_or(false) {
+        if let Some(labels) = cache.get(repository_id, Instant::now()) {
+            return Ok(labels);
+        }
+    }
+
+    let repo = get_repository_by_id(&db, repository_id)?;
+    let args = serde_json::json!({
+        "owner": repo.owner,
+        "repo": repo.repo_name,
+    });
+    let result = grpc
+        .call_mcp_tool(
+            &repo.mcp_server_name,
+            &grpc.tool_for(
+                &repo.mcp_server_name,
+                McpOperation::ListLabels,
+                get_list_labels_tool(repo.platform),
+            ),
+            &args,
+        )
+        .await?;
+
+    let labels = extract_labels(&result);
+    cache.insert(repository_id, labels.clone(), Instant::now());
+    Ok(labels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_labels_both_platforms() {
+        let github = serde_json::json!({
+            "content": [{"type": "text", "text": serde_json::json!({
+                "labels": [
+                    {"name": "enhancement", "color": "A2EEEF", "description": "New feature"},
+                    {"name": "bug", "color": "d73a4a", "description": ""}
+                ],
+                "totalCount": 2
+            }).to_string()}]
+        });
+        let labels = extract_labels(&github);
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels[0].name, "bug");
+        assert_eq!(labels[0].description, None);
+        assert_eq!(labels[1].color.as_deref(), Some("a2eeef"));
+
+        let gitea = serde_json::json!([
+            {"id": 1, "name": "kind/bug", "color": "#ee0701", "description": "Something is broken"}
+        ]);
+        let labels = extract_labels(&gitea);
+        assert_eq!(labels[0].color.as_deref(), Some("ee0701"));
+        assert_eq!(
+            labels[0].description.as_deref(),
+            Some("Something is broken")
+        );
+    }
+
+    #[test]
+    fn test_label_cache_expires() {
+        let cache = LabelCache::default();
+        let now = Instant::now();
+        cache.insert(1, vec![], now);
+        assert!(cache.get(1, now + Duration::from_secs(60)).is_some());
+        assert!(cache.get(1, now + LABEL_CACHE_TTL).is_none());
+        assert!(cache.get(2, now).is_none());
+    }
+}
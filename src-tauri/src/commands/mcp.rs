@@ -1,9 +1,139 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tauri::State;
 use url::Url;
 
+use super::platforms::gitea_web_base;
+use super::worktree::expand_home;
+use crate::db::events::redact_secrets;
+use crate::db::{record_event, AppEventType, DbPool, Platform};
 use crate::error::AppError;
-use crate::grpc::{JobworkerpClient, McpServerInfo};
+use crate::grpc::breaker::ServerCircuitStatus;
+use crate::grpc::throttle::McpThrottleStatus;
+use crate::grpc::tool_overrides::McpOperation;
+use crate::grpc::{
+    data, JobworkerpClient, McpCallOptions, McpServerInfo, McpToolOutput, RunnerInfo,
+    ToolSchemaInfo,
+};
+
+/// MCP server usage by registered repositories
+#[derive(Debug, Serialize)]
+pub struct McpServerUsage {
+    pub name: String,
+    pub repository_count: i64,
+    pub worker_exists: bool,
+    /// No repository uses this server; candidate for deletion
+    pub unused: bool,
+}
+
+/// Repository whose MCP server runner no longer exists on the backend
+#[derive(Debug, PartialEq, Serialize)]
+pub struct MissingRunnerRepository {
+    pub repository_id: i64,
+    pub name: String,
+    pub mcp_server_name: String,
+}
+
+/// Tool name used for an operation on one MCP server instead of the default
+#[derive(Debug, Serialize)]
+pub struct ToolOverride {
+    pub mcp_server_name: String,
+    pub operation: McpOperation,
+    pub tool_name: String,
+}
+
+/// Availability of the docker image an MCP server runner starts
+#[derive(Debug, Serialize)]
+pub struct McpImageCheck {
+    pub image: String,
+    /// Image is present in the local docker image store
+    pub available: bool,
+    /// Registry has a manifest for the image, so `docker run` can pull it
+    pub pullable: bool,
+    /// Docker output explaining a failed check
+    pub detail: Option<String>,
+}
+
+/// Latency statistics of successful benchmark calls, in milliseconds
+#[derive(Debug, PartialEq, Serialize)]
+pub struct LatencyStats {
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub mean_ms: f64,
+    pub p95_ms: f64,
+}
+
+/// Result of calling one MCP tool repeatedly
+#[derive(Debug, Serialize)]
+pub struct McpToolBenchmark {
+    pub server_name: String,
+    pub tool_name: String,
+    pub iterations: u32,
+    pub succeeded: u32,
+    pub failed: u32,
+    /// Per-call latency in call order, including failed calls
+    pub latencies_ms: Vec<f64>,
+    /// `None` when no call succeeded
+    pub stats: Option<LatencyStats>,
+    pub first_error: Option<String>,
+    /// The overall timeout stopped the benchmark before all iterations ran
+    pub timed_out: bool,
+}
+
+const DEFAULT_WORKER_RESULT_LIMIT: i32 = 20;
+const MAX_WORKER_RESULT_LIMIT: i32 = 100;
+const MAX_BENCHMARK_ITERATIONS: u32 = 50;
+/// Upper bound for a whole benchmark run
+const BENCHMARK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300);
+/// Longest error output returned per result
+const MAX_RESULT_ERROR_CHARS: usize = 500;
+
+/// Summary of one stored MCP tool call result
+#[derive(Debug, Serialize)]
+pub struct McpCallSummary {
+    pub job_id: Option<i64>,
+    pub worker_name: String,
+    /// Tool name the job was enqueued with
+    pub using: Option<String>,
+    /// Backend result status (e.g. "SUCCESS", "FATAL_ERROR")
+    pub status: String,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub retried: u32,
+    /// Error output for failed calls, truncated
+    pub error: Option<String>,
+}
+
+impl From<data::JobResult> for McpCallSummary {
+    fn from(result: data::JobResult) -> Self {
+        let data = result.data.unwrap_or_default();
+        let status = data::ResultStatus::try_from(data.status)
+            .map(|s| s.as_str_name().to_string())
+            .unwrap_or_else(|_| format!("UNKNOWN({})", data.status));
+        let error = data
+            .output
+            .as_ref()
+            .filter(|_| data.status != data::ResultStatus::Success as i32)
+            .map(|output| {
+                String::from_utf8_lossy(&output.items)
+                    .chars()
+                    .take(MAX_RESULT_ERROR_CHARS)
+                    .collect()
+            });
+
+        McpCallSummary {
+            job_id: data.job_id.map(|id| id.value),
+            worker_name: data.worker_name,
+            using: data.using,
+            status,
+            start_time: data.start_time,
+            end_time: data.end_time,
+            retried: data.retried,
+            error,
+        }
+    }
+}
 
 /// Validate and escape a string for TOML value.
 /// Rejects strings containing characters that could break TOML parsing.
@@ -16,10 +146,13 @@ fn validate_toml_value(value: &str, field_name: &str) -> Result<(), AppError> {
         || value.contains('\t')
         || value.contains('\0')
     {
-        return Err(AppError::InvalidInput(format!(
-            "{} contains invalid characters (quotes, backslashes, or control characters are not allowed)",
-            field_name
-        )));
+        return Err(AppError::validation(
+            field_name.to_lowercase(),
+            format!(
+                "{} contains invalid characters (quotes, backslashes, or control characters are not allowed)",
+                field_name
+            ),
+        ));
     }
     Ok(())
 }
@@ -28,22 +161,21 @@ fn validate_toml_value(value: &str, field_name: &str) -> Result<(), AppError> {
 /// Only allows alphanumeric characters, hyphens, and underscores.
 fn validate_runner_name(name: &str) -> Result<(), AppError> {
     if name.is_empty() {
-        return Err(AppError::InvalidInput(
-            "Runner name cannot be empty".to_string(),
-        ));
+        return Err(AppError::validation("name", "Runner name cannot be empty"));
     }
     if name.len() > 64 {
-        return Err(AppError::InvalidInput(
-            "Runner name must be 64 characters or less".to_string(),
+        return Err(AppError::validation(
+            "name",
+            "Runner name must be 64 characters or less",
         ));
     }
     if !name
         .chars()
         .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
     {
-        return Err(AppError::InvalidInput(
-            "Runner name can only contain alphanumeric characters, hyphens, and underscores"
-                .to_string(),
+        return Err(AppError::validation(
+            "name",
+            "Runner name can only contain alphanumeric characters, hyphens, and underscores",
         ));
     }
     Ok(())
@@ -68,25 +200,674 @@ pub async fn mcp_check_connection(
     Ok(worker.is_some())
 }
 
+/// List MCP servers with the number of repositories using each
+///
+/// Cross-references backend runners with `repositories.mcp_server_name`.
+#[tauri::command]
+pub async fn mcp_server_usage(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+) -> Result<Vec<McpServerUsage>, AppError> {
+    let repository_counts: HashMap<String, i64> = {
+        let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+        let mut stmt = conn.prepare(
+            "SELECT mcp_server_name, COUNT(*) FROM repositories GROUP BY mcp_server_name",
+        )?;
+        let counts = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        counts
+    };
+
+    let servers = grpc.list_mcp_servers().await?;
+    let workers =
+        futures::future::join_all(servers.iter().map(|s| grpc.find_worker_by_name(&s.name))).await;
+
+    servers
+        .into_iter()
+        .zip(workers)
+        .map(|(server, worker)| {
+            let repository_count = repository_counts.get(&server.name).copied().unwrap_or(0);
+            Ok(McpServerUsage {
+                worker_exists: worker?.is_some(),
+                repository_count,
+                unused: repository_count == 0,
+                name: server.name,
+            })
+        })
+        .collect()
+}
+
+/// Runners whose name contains `name_substring`, ignoring case, sorted by name
+fn filter_runners(runners: Vec<RunnerInfo>, name_substring: &str) -> Vec<RunnerInfo> {
+    let needle = name_substring.trim().to_lowercase();
+    let mut matched: Vec<RunnerInfo> = runners
+        .into_iter()
+        .filter(|runner| runner.name.to_lowercase().contains(&needle))
+        .collect();
+    matched.sort_by(|a, b| a.name.cmp(&b.name));
+    matched
+}
+
+/// List backend runners whose name contains a substring, for runner cleanup
+///
+/// The backend's name filter may match exactly, so runners are filtered
+/// here. `types` takes runner type names (e.g. "MCP_SERVER"); omitted or
+/// empty, every type is listed.
+#[tauri::command]
+pub async fn search_runners(
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    name_substring: Option<String>,
+    types: Option<Vec<String>>,
+) -> Result<Vec<RunnerInfo>, AppError> {
+    let runner_types = types
+        .unwrap_or_default()
+        .iter()
+        .map(|name| {
+            data::RunnerType::from_str_name(name)
+                .map(i32::from)
+                .ok_or_else(|| {
+                    AppError::validation("types", format!("Unknown runner type: {}", name))
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let runners = grpc.list_runners(runner_types).await?;
+    Ok(filter_runners(
+        runners,
+        name_substring.as_deref().unwrap_or(""),
+    ))
+}
+
+/// Repositories whose `mcp_server_name` is not in `runners`
+fn missing_runner_repositories(
+    repositories: Vec<(i64, String, String)>,
+    runners: &HashSet<String>,
+) -> Vec<MissingRunnerRepository> {
+    repositories
+        .into_iter()
+        .filter(|(_, _, server)| !runners.contains(server))
+        .map(
+            |(repository_id, name, mcp_server_name)| MissingRunnerRepository {
+                repository_id,
+                name,
+                mcp_server_name,
+            },
+        )
+        .collect()
+}
+
+/// Find repositories pointing at MCP server runners missing from the backend
+///
+/// Used by `audit_mcp_servers` and the startup check.
+pub(crate) async fn find_missing_runner_repositories(
+    db: &DbPool,
+    grpc: &JobworkerpClient,
+) -> Result<Vec<MissingRunnerRepository>, AppError> {
+    let repositories: Vec<(i64, String, String)> = {
+        let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+        let mut stmt =
+            conn.prepare("SELECT id, name, mcp_server_name FROM repositories ORDER BY name")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows
+    };
+    if repositories.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let runners: HashSet<String> = grpc
+        .list_mcp_servers()
+        .await?
+        .into_iter()
+        .map(|server| server.name)
+        .collect();
+    Ok(missing_runner_repositories(repositories, &runners))
+}
+
+/// Check that every repository's MCP server runner exists on the backend
+///
+/// Returns the repositories pointing at missing runners, e.g. after the
+/// backend was reset. Fix them with `rename_mcp_server` or by recreating the
+/// runner.
+#[tauri::command]
+pub async fn audit_mcp_servers(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+) -> Result<Vec<MissingRunnerRepository>, AppError> {
+    find_missing_runner_repositories(&db, &grpc).await
+}
+
+/// Point repositories using `old_name` at the MCP server runner `new_name`
+///
+/// For use after a runner has been renamed or replaced on the backend.
+/// Returns the number of repositories updated.
+#[tauri::command]
+pub async fn rename_mcp_server(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    old_name: String,
+    new_name: String,
+) -> Result<usize, AppError> {
+    let new_name = new_name.trim();
+    if new_name == old_name {
+        return Err(AppError::validation(
+            "new_name",
+            "New MCP server name must differ from the old one",
+        ));
+    }
+    if grpc.find_runner_by_exact_name(new_name).await?.is_none() {
+        return Err(AppError::validation(
+            "new_name",
+            format!("MCP server '{}' not found", new_name),
+        ));
+    }
+
+    let mut conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    let tx = conn.transaction()?;
+    let updated = tx.execute(
+        "UPDATE repositories SET mcp_server_name = ?1, updated_at = datetime('now')
+         WHERE mcp_server_name = ?2",
+        rusqlite::params![new_name, old_name],
+    )?;
+    if updated == 0 {
+        return Err(AppError::NotFound(format!(
+            "No repositories use MCP server '{}'",
+            old_name
+        )));
+    }
+    tx.commit()?;
+
+    tracing::info!(
+        "Moved {} repositories from MCP server '{}' to '{}'",
+        updated,
+        old_name,
+        new_name
+    );
+    Ok(updated)
+}
+
+/// List recent stored results of an MCP server worker
+///
+/// Complements `agent_jobs`, which only tracks workflow jobs. MCP workers
+/// created by this app store failures only, so successful calls appear only
+/// for workers configured with `store_success`.
+#[tauri::command]
+pub async fn list_results_by_worker(
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    worker_id: i64,
+    limit: Option<i32>,
+) -> Result<Vec<McpCallSummary>, AppError> {
+    let limit = limit
+        .unwrap_or(DEFAULT_WORKER_RESULT_LIMIT)
+        .clamp(1, MAX_WORKER_RESULT_LIMIT);
+    let results = grpc.list_results_by_worker(worker_id, Some(limit)).await?;
+    Ok(results.into_iter().map(McpCallSummary::from).collect())
+}
+
+/// Call a single MCP tool and return the decoded result without parsing it
+///
+/// Intended for debugging: the response shows the raw result shape, which
+/// decoding path was used (protobuf or JSON fallback), and the byte length.
+/// With `force_json`, the result_proto schema is skipped and the raw bytes are
+/// parsed as JSON, or returned as a string if they are not JSON.
+#[tauri::command]
+pub async fn debug_mcp_call(
+    server_name: String,
+    tool_name: String,
+    args: serde_json::Value,
+    force_json: Option<bool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+) -> Result<McpToolOutput, AppError> {
+    let options = McpCallOptions {
+        force_json: force_json.unwrap_or(false),
+        ..Default::default()
+    };
+    grpc.call_mcp_tool_decoded(&server_name, &tool_name, &args, &options)
+        .await
+}
+
+/// Undecoded result of an MCP tool call
+#[derive(Debug, Serialize)]
+pub struct McpRawResult {
+    /// Result bytes, base64 encoded for the IPC bridge
+    pub data_base64: String,
+    pub byte_length: usize,
+}
+
+/// Call an MCP tool and return its result bytes without decoding them
+///
+/// Fallback for results the runner's result_proto schema cannot decode;
+/// the caller decodes the bytes itself.
+#[tauri::command]
+pub async fn call_mcp_tool_raw_bytes(
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    server_name: String,
+    tool_name: String,
+    args: serde_json::Value,
+) -> Result<McpRawResult, AppError> {
+    use base64::Engine;
+
+    let bytes = grpc
+        .call_mcp_tool_raw_bytes(&server_name, &tool_name, &args, &McpCallOptions::default())
+        .await?;
+    Ok(McpRawResult {
+        data_base64: base64::engine::general_purpose::STANDARD.encode(&bytes),
+        byte_length: bytes.len(),
+    })
+}
+
+/// Min/max/mean and nearest-rank p95 of a set of latencies
+fn latency_stats(latencies: &[std::time::Duration]) -> Option<LatencyStats> {
+    let mut ms: Vec<f64> = latencies.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+    if ms.is_empty() {
+        return None;
+    }
+    ms.sort_by(f64::total_cmp);
+    let p95_rank = (ms.len() * 95).div_ceil(100);
+    Some(LatencyStats {
+        min_ms: ms[0],
+        max_ms: ms[ms.len() - 1],
+        mean_ms: ms.iter().sum::<f64>() / ms.len() as f64,
+        p95_ms: ms[p95_rank - 1],
+    })
+}
+
+/// Call an MCP tool `iterations` times and report its latency
+///
+/// Calls run one after another through the normal throttle and circuit
+/// breaker, so the numbers match what workflows see. The whole run is bounded
+/// by `BENCHMARK_TIMEOUT`; stats cover the calls completed by then.
+#[tauri::command]
+pub async fn benchmark_mcp_tool(
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    server_name: String,
+    tool_name: String,
+    args: serde_json::Value,
+    iterations: u32,
+) -> Result<McpToolBenchmark, AppError> {
+    if iterations == 0 || iterations > MAX_BENCHMARK_ITERATIONS {
+        return Err(AppError::validation(
+            "iterations",
+            format!("Must be between 1 and {}", MAX_BENCHMARK_ITERATIONS),
+        ));
+    }
+
+    let deadline = tokio::time::Instant::now() + BENCHMARK_TIMEOUT;
+    let mut latencies_ms = Vec::new();
+    let mut successes = Vec::new();
+    let mut first_error = None;
+    let mut timed_out = false;
+    for _ in 0..iterations {
+        let started = std::time::Instant::now();
+        let call = grpc.call_mcp_tool(&server_name, &tool_name, &args);
+        let Ok(result) = tokio::time::timeout_at(deadline, call).await else {
+            timed_out = true;
+            break;
+        };
+        let elapsed = started.elapsed();
+        latencies_ms.push(elapsed.as_secs_f64() * 1000.0);
+        match result {
+            Ok(_) => successes.push(elapsed),
+            Err(e) => {
+                first_error.get_or_insert_with(|| e.to_string());
+            }
+        }
+    }
+
+    let completed = latencies_ms.len() as u32;
+    tracing::info!(
+        "Benchmarked '{}' on '{}': {}/{} calls succeeded",
+        tool_name,
+        server_name,
+        successes.len(),
+        completed
+    );
+    Ok(McpToolBenchmark {
+        server_name,
+        tool_name,
+        iterations,
+        succeeded: successes.len() as u32,
+        failed: completed - successes.len() as u32,
+        latencies_ms,
+        stats: latency_stats(&successes),
+        first_error,
+        timed_out,
+    })
+}
+
+/// Check that the docker image of an MCP server runner is available
+///
+/// The backend has no image check, so this asks the local docker daemon
+/// (`docker image inspect`, then `docker manifest inspect` for images that
+/// are not present). Only meaningful when jobworkerp-rs runs on this machine;
+/// requires the `docker-image-check` feature.
+#[tauri::command]
+pub async fn mcp_check_image(
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    server_name: String,
+) -> Result<McpImageCheck, AppError> {
+    let runner = grpc
+        .find_runner_by_exact_name(&server_name)
+        .await?
+        .and_then(|r| r.data)
+        .ok_or_else(|| AppError::NotFound(format!("MCP server '{}' not found", server_name)))?;
+    let image = docker_image(&runner.definition).ok_or_else(|| {
+        AppError::InvalidInput(format!(
+            "MCP server '{}' does not run a docker image",
+            server_name
+        ))
+    })?;
+    check_docker_image(image).await
+}
+
+/// Image a docker-based runner definition starts
+///
+/// Definitions from `github_mcp_toml` and `gitea_mcp_toml` pass the image
+/// as the last element of `args`.
+fn docker_image(definition: &str) -> Option<String> {
+    let command = regex::Regex::new(r#"(?m)^\s*command\s*=\s*"([^"]*)""#).expect("valid regex");
+    if command.captures(definition)?.get(1)?.as_str() != "docker" {
+        return None;
+    }
+    let args = regex::Regex::new(r"(?s)args\s*=\s*\[(.*?)\]").expect("valid regex");
+    let list = args.captures(definition)?.get(1)?.as_str().to_string();
+    regex::Regex::new(r#""([^"]*)""#)
+        .expect("valid regex")
+        .captures_iter(&list)
+        .map(|caps| caps[1].to_string())
+        .last()
+        .filter(|image| !image.starts_with('-'))
+}
+
+#[cfg(feature = "docker-image-check")]
+async fn check_docker_image(image: String) -> Result<McpImageCheck, AppError> {
+    async fn docker(args: &[&str]) -> Result<std::process::Output, AppError> {
+        tokio::process::Command::new("docker")
+            .args(args)
+            .stdin(std::process::Stdio::null())
+            .output()
+            .await
+            .map_err(|e| AppError::Config(format!("Could not run docker: {}", e)))
+    }
+
+    let inspect = docker(&["image", "inspect", "--format", "{{.Id}}", &image]).await?;
+    if inspect.status.success() {
+        return Ok(McpImageCheck {
+            image,
+            available: true,
+            pullable: true,
+            detail: None,
+        });
+    }
+
+    let manifest = tokio::time::timeout(
+        std::time::Duration::from_secs(30),
+        docker(&["manifest", "inspect", &image]),
+    )
+    .await
+    .map_err(|_| AppError::Internal("docker manifest inspect timed out".to_string()))??;
+    let detail = (!manifest.status.success()).then(|| {
+        String::from_utf8_lossy(&manifest.stderr)
+            .trim()
+            .chars()
+            .take(MAX_RESULT_ERROR_CHARS)
+            .collect()
+    });
+    Ok(McpImageCheck {
+        image,
+        available: false,
+        pullable: manifest.status.success(),
+        detail,
+    })
+}
+
+#[cfg(not(feature = "docker-image-check"))]
+async fn check_docker_image(image: String) -> Result<McpImageCheck, AppError> {
+    Err(AppError::Config(format!(
+        "Cannot check image {}: built without the docker-image-check feature",
+        image
+    )))
+}
+
+/// Get the JSON Schema of an MCP tool's arguments
+///
+/// Lets clients render a form for `debug_mcp_call` instead of free-form JSON.
+#[tauri::command]
+pub async fn get_tool_arg_schema(
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    server_name: String,
+    tool_name: String,
+) -> Result<serde_json::Value, AppError> {
+    grpc.get_tool_arg_schema(&server_name, &tool_name).await
+}
+
+/// List an MCP server's tools and whether each has args/result schemas
+///
+/// Explains decode differences: tools with a result schema are decoded via
+/// protobuf, the others are parsed as JSON.
+#[tauri::command]
+pub async fn inspect_runner_schema(
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    server_name: String,
+) -> Result<Vec<ToolSchemaInfo>, AppError> {
+    grpc.inspect_runner_schema(&server_name).await
+}
+
+/// List saved MCP tool overrides, by server and operation
+#[tauri::command]
+pub async fn list_tool_overrides(db: State<'_, DbPool>) -> Result<Vec<ToolOverride>, AppError> {
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    let mut stmt = conn.prepare(
+        "SELECT mcp_server_name, operation, tool_name FROM mcp_tool_overrides
+         ORDER BY mcp_server_name, operation",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|(mcp_server_name, operation, tool_name)| {
+            Some(ToolOverride {
+                mcp_server_name,
+                operation: operation.parse().ok()?,
+                tool_name,
+            })
+        })
+        .collect())
+}
+
+/// Call `tool_name` for an operation on an MCP server instead of the default tool
+///
+/// The tool must exist in the runner's tool list. Pass None or a blank name
+/// to go back to the default.
+#[tauri::command]
+pub async fn set_tool_override(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    mcp_server_name: String,
+    operation: McpOperation,
+    tool_name: Option<String>,
+) -> Result<(), AppError> {
+    let tool_name = tool_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty());
+
+    if let Some(tool_name) = tool_name {
+        let runner = grpc
+            .find_runner_by_exact_name(&mcp_server_name)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("MCP server '{}' not found", mcp_server_name))
+            })?;
+        let known = runner
+            .data
+            .and_then(|data| data.method_proto_map)
+            .is_some_and(|map| map.schemas.contains_key(tool_name));
+        if !known {
+            return Err(AppError::validation(
+                "tool_name",
+                format!(
+                    "MCP server '{}' has no tool named '{}'",
+                    mcp_server_name, tool_name
+                ),
+            ));
+        }
+    }
+
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    match tool_name {
+        Some(tool_name) => conn.execute(
+            "INSERT INTO mcp_tool_overrides (mcp_server_name, operation, tool_name)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT (mcp_server_name, operation) DO UPDATE SET tool_name = excluded.tool_name",
+            rusqlite::params![mcp_server_name, operation.to_string(), tool_name],
+        )?,
+        None => conn.execute(
+            "DELETE FROM mcp_tool_overrides WHERE mcp_server_name = ?1 AND operation = ?2",
+            rusqlite::params![mcp_server_name, operation.to_string()],
+        )?,
+    };
+    grpc.tool_overrides()
+        .set(&mcp_server_name, operation, tool_name);
+
+    record_event(
+        &conn,
+        AppEventType::SettingsChanged,
+        &format!(
+            "MCP server '{}' {} tool set to {}",
+            mcp_server_name,
+            operation,
+            tool_name.unwrap_or("the default")
+        ),
+    );
+    Ok(())
+}
+
+/// Get the MCP call throttle configuration and per-server state
+///
+/// The limit is set with `mcp_calls_per_minute` in the app settings.
+#[tauri::command]
+pub async fn mcp_throttle_status(
+    grpc: State<'_, Arc<JobworkerpClient>>,
+) -> Result<McpThrottleStatus, AppError> {
+    Ok(grpc.mcp_throttle_status())
+}
+
+/// Get the circuit breaker state of MCP servers with recent failures
+///
+/// A server whose circuit is open fails calls immediately until a probe
+/// call succeeds.
+#[tauri::command]
+pub async fn mcp_circuit_status(
+    grpc: State<'_, Arc<JobworkerpClient>>,
+) -> Result<Vec<ServerCircuitStatus>, AppError> {
+    Ok(grpc.mcp_circuit_status())
+}
+
+/// Close an MCP server's circuit so calls to it are attempted right away
+#[tauri::command]
+pub async fn reset_mcp_circuit(
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    server_name: String,
+) -> Result<(), AppError> {
+    grpc.reset_mcp_circuit(&server_name);
+    Ok(())
+}
+
+/// Get the TOML definition stored for an MCP server runner
+///
+/// Values of TOKEN/SECRET/PASSWORD/KEY variables and URL credentials are
+/// masked unless `reveal_secrets` is true; revealing is logged.
+#[tauri::command]
+pub async fn get_runner_definition(
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    server_name: String,
+    reveal_secrets: Option<bool>,
+) -> Result<String, AppError> {
+    let definition = grpc
+        .find_runner_by_exact_name(&server_name)
+        .await?
+        .and_then(|r| r.data)
+        .map(|data| data.definition)
+        .ok_or_else(|| AppError::NotFound(format!("MCP server '{}' not found", server_name)))?;
+
+    if reveal_secrets.unwrap_or(false) {
+        tracing::warn!(
+            "Revealing unmasked runner definition of MCP server '{}'",
+            server_name
+        );
+        return Ok(definition);
+    }
+    Ok(mask_definition_secrets(&definition))
+}
+
+/// Mask secret values in a runner definition
+fn mask_definition_secrets(definition: &str) -> String {
+    let secret_value = regex::Regex::new(
+        r#"(?i)(\b[A-Z0-9_]*(?:TOKEN|SECRET|PASSWORD|KEY)[A-Z0-9_]*\s*=\s*")[^"]*(")"#,
+    )
+    .expect("valid regex");
+    redact_secrets(&secret_value.replace_all(definition, "${1}[redacted]${2}"))
+}
+
 /// Create a new GitHub/Gitea MCP server (Runner) dynamically
 ///
 /// The TOML definition is auto-generated based on the platform.
 /// Docker execution format is used for MCP servers.
 #[tauri::command]
 pub async fn mcp_create_runner(
+    db: State<'_, DbPool>,
     grpc: State<'_, Arc<JobworkerpClient>>,
     platform: String,
     name: String,
     url: String,
     token: String,
 ) -> Result<McpServerInfo, AppError> {
+    let platform: Platform = platform.parse().map_err(|_| {
+        AppError::InvalidInput(format!(
+            "Unsupported platform: {}. Only 'GitHub' and 'Gitea' are supported.",
+            platform
+        ))
+    })?;
+
+    let (_, info) = create_mcp_runner(&grpc, platform, &name, &url, &token).await?;
+    // Failures recorded for an earlier runner of the same name no longer apply
+    grpc.reset_mcp_circuit(&info.name);
+
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    record_event(
+        &conn,
+        AppEventType::RunnerCreated,
+        &format!("MCP server runner '{}' created for {}", info.name, platform),
+    );
+    Ok(info)
+}
+
+/// Validate inputs, generate the TOML definition and create an MCP server runner
+///
+/// Returns the new runner ID along with the server info.
+pub(crate) async fn create_mcp_runner(
+    grpc: &JobworkerpClient,
+    platform: Platform,
+    name: &str,
+    url: &str,
+    token: &str,
+) -> Result<(i64, McpServerInfo), AppError> {
     // Validate inputs to prevent TOML injection
-    validate_runner_name(&name)?;
-    validate_toml_value(&token, "Token")?;
-    validate_toml_value(&url, "URL")?;
+    validate_runner_name(name)?;
+    validate_toml_value(token, "Token")?;
+    validate_toml_value(url, "URL")?;
 
     // Check if runner with this name already exists
-    if let Some(_existing) = grpc.find_runner_by_exact_name(&name).await? {
+    if let Some(_existing) = grpc.find_runner_by_exact_name(name).await? {
         return Err(AppError::InvalidInput(format!(
             "Runner with name '{}' already exists",
             name
@@ -94,26 +875,358 @@ pub async fn mcp_create_runner(
     }
 
     // Generate TOML definition based on platform
-    let definition = match platform.as_str() {
-        "GitHub" => github_mcp_toml(&name, &url, &token)?,
-        "Gitea" => gitea_mcp_toml(&name, &url, &token)?,
-        _ => {
-            return Err(AppError::InvalidInput(format!(
-                "Unsupported platform: {}. Only 'GitHub' and 'Gitea' are supported.",
-                platform
-            )))
-        }
+    let definition = match platform {
+        Platform::GitHub => github_mcp_toml(name, url, token)?,
+        Platform::Gitea => gitea_mcp_toml(name, url, token)?,
     };
 
     let description = format!("{} MCP Server", platform);
 
     // Create runner via gRPC
-    grpc.create_runner(&name, &description, &definition).await?;
+    let runner_id = grpc.create_runner(name, &description, &definition).await?;
+
+    Ok((
+        runner_id,
+        McpServerInfo {
+            name: name.to_string(),
+            description: Some(description),
+            runner_type: "MCP_SERVER".to_string(),
+        },
+    ))
+}
 
-    Ok(McpServerInfo {
-        name,
-        description: Some(description),
-        runner_type: "MCP_SERVER".to_string(),
+/// `[[server]]` entry of a jobworkerp-rs mcp-settings.toml
+///
+/// Fields this app does not know are kept so they reach the runner definition.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct McpSettingsServer {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    transport: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    args: Vec<String>,
+    #[serde(default, skip_serializing_if = "toml::Table::is_empty")]
+    envs: toml::Table,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(flatten)]
+    extra: toml::Table,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct McpSettingsFile {
+    #[serde(default)]
+    server: Vec<McpSettingsServer>,
+}
+
+/// Result of importing an mcp-settings.toml file
+#[derive(Debug, Serialize)]
+pub struct McpSettingsImport {
+    pub created: Vec<String>,
+    /// Servers whose runner name already exists in the backend
+    pub skipped: Vec<String>,
+    /// Servers whose runner could not be created; the others are still imported
+    pub failed: Vec<McpImportFailure>,
+    pub created_count: usize,
+    pub skipped_count: usize,
+}
+
+/// Server of an mcp-settings.toml file whose runner could not be created
+#[derive(Debug, Serialize)]
+pub struct McpImportFailure {
+    pub name: String,
+    pub error: String,
+}
+
+/// Parse and validate every `[[server]]` entry of an mcp-settings.toml file
+fn parse_mcp_settings(content: &str) -> Result<Vec<McpSettingsServer>, AppError> {
+    let settings: McpSettingsFile = toml::from_str(content)
+        .map_err(|e| AppError::validation("toml", format!("Invalid mcp-settings.toml: {}", e)))?;
+    if settings.server.is_empty() {
+        return Err(AppError::validation(
+            "toml",
+            "No [[server]] entries found in mcp-settings.toml",
+        ));
+    }
+
+    let mut names = HashSet::new();
+    for server in &settings.server {
+        validate_runner_name(&server.name)?;
+        if !names.insert(server.name.as_str()) {
+            return Err(AppError::validation(
+                "name",
+                format!("Server '{}' is defined more than once", server.name),
+            ));
+        }
+        let (field, value) = match server.transport.as_str() {
+            "stdio" => ("command", &server.command),
+            "sse" => ("url", &server.url),
+            other => {
+                return Err(AppError::validation(
+                    "transport",
+                    format!(
+                        "Server '{}' has unsupported transport '{}' (expected 'stdio' or 'sse')",
+                        server.name, other
+                    ),
+                ))
+            }
+        };
+        if value.as_deref().is_none_or(|v| v.trim().is_empty()) {
+            return Err(AppError::validation(
+                field,
+                format!(
+                    "Server '{}' with {} transport requires '{}'",
+                    server.name, server.transport, field
+                ),
+            ));
+        }
+        if let Some((key, _)) = server.envs.iter().find(|(_, v)| !v.is_str()) {
+            return Err(AppError::validation(
+                "envs",
+                format!("Server '{}' env '{}' must be a string", server.name, key),
+            ));
+        }
+    }
+    Ok(settings.server)
+}
+
+/// Runner definition for one server: a single-entry mcp-settings.toml
+fn mcp_settings_definition(server: &McpSettingsServer) -> Result<String, AppError> {
+    toml::to_string(&McpSettingsFile {
+        server: vec![server.clone()],
+    })
+    .map_err(|e| AppError::Internal(format!("Failed to serialize runner definition: {}", e)))
+}
+
+/// Create MCP server runners from a jobworkerp-rs mcp-settings.toml file
+///
+/// The whole file is validated before any runner is created. Servers whose
+/// name is already taken by a runner are skipped; a server whose runner
+/// cannot be created is reported in `failed` and the import continues.
+#[tauri::command]
+pub async fn import_mcp_settings(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    toml_path: String,
+) -> Result<McpSettingsImport, AppError> {
+    let path = expand_home(toml_path.trim());
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::InvalidInput(format!("Failed to read {}: {}", path.display(), e)))?;
+    let servers = parse_mcp_settings(&content)?;
+    let definitions = servers
+        .iter()
+        .map(mcp_settings_definition)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut created = Vec::new();
+    let mut skipped = Vec::new();
+    let mut failed = Vec::new();
+    for (server, definition) in servers.iter().zip(definitions) {
+        let description = server
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("Imported MCP Server ({})", server.transport));
+        let outcome = match grpc.find_runner_by_exact_name(&server.name).await {
+            Ok(Some(_)) => {
+                skipped.push(server.name.clone());
+                continue;
+            }
+            Ok(None) => {
+                grpc.create_runner(&server.name, &description, &definition)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        match outcome {
+            Ok(_) => {
+                grpc.reset_mcp_circuit(&server.name);
+                created.push(server.name.clone());
+            }
+            Err(e) => {
+                tracing::warn!("Failed to import MCP server '{}': {}", server.name, e);
+                failed.push(McpImportFailure {
+                    name: server.name.clone(),
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+
+    if !created.is_empty() {
+        let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+        record_event(
+            &conn,
+            AppEventType::RunnerCreated,
+            &format!(
+                "Imported MCP server runners from {}: {}",
+                path.display(),
+                created.join(", ")
+            ),
+        );
+    }
+    Ok(McpSettingsImport {
+        created_count: created.len(),
+        skipped_count: skipped.len(),
+        created,
+        skipped,
+        failed,
+    })
+}
+
+/// Result of exporting MCP runners to an mcp-settings.toml file
+#[derive(Debug, Serialize)]
+pub struct McpSettingsExport {
+    pub path: String,
+    pub exported: Vec<String>,
+    /// Runners whose definition is not an mcp-settings `[[server]]` entry
+    pub unparsed: Vec<String>,
+    pub secrets_included: bool,
+}
+
+/// Rebuild the `[[server]]` entry of a runner from its definition
+///
+/// The entry is named after the runner, which may differ from the name in
+/// the definition after a rename.
+fn server_from_definition(runner_name: &str, definition: &str) -> Option<McpSettingsServer> {
+    let settings: McpSettingsFile = toml::from_str(definition).ok()?;
+    let mut servers = settings.server.into_iter();
+    let mut server = servers.next()?;
+    if servers.next().is_some() {
+        return None;
+    }
+    server.name = runner_name.to_string();
+    Some(server)
+}
+
+/// Serialize servers as an mcp-settings.toml file, masking secrets unless asked
+fn mcp_settings_toml(
+    servers: Vec<McpSettingsServer>,
+    include_secrets: bool,
+) -> Result<String, AppError> {
+    let toml = toml::to_string(&McpSettingsFile { server: servers })
+        .map_err(|e| AppError::Internal(format!("Failed to serialize mcp-settings.toml: {}", e)))?;
+    Ok(if include_secrets {
+        toml
+    } else {
+        mask_definition_secrets(&toml)
+    })
+}
+
+/// Check that `dest` can be written as a file before doing any backend work
+fn validate_export_destination(dest: &std::path::Path) -> Result<(), AppError> {
+    if dest.is_dir() {
+        return Err(AppError::validation(
+            "dest_path",
+            format!("{} is a directory", dest.display()),
+        ));
+    }
+    let parent = match dest.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => std::path::Path::new("."),
+    };
+    let parent_metadata = std::fs::metadata(parent).map_err(|e| {
+        AppError::validation(
+            "dest_path",
+            format!("Directory {} is not accessible: {}", parent.display(), e),
+        )
+    })?;
+    if !parent_metadata.is_dir() {
+        return Err(AppError::validation(
+            "dest_path",
+            format!("{} is not a directory", parent.display()),
+        ));
+    }
+    let read_only = match std::fs::metadata(dest) {
+        Ok(metadata) => metadata.permissions().readonly(),
+        Err(_) => parent_metadata.permissions().readonly(),
+    };
+    if read_only {
+        return Err(AppError::validation(
+            "dest_path",
+            format!("{} is not writable", dest.display()),
+        ));
+    }
+    Ok(())
+}
+
+/// Write an export, readable only by the user when it holds secrets
+fn write_export_file(dest: &std::path::Path, content: &str, secret: bool) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    if secret {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+    }
+    let mut file = options.open(dest)?;
+    // The mode only applies to new files; restrict one being overwritten too
+    if secret {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    file.write_all(content.as_bytes())
+}
+
+/// Write the current MCP server runners to a jobworkerp-rs mcp-settings.toml file
+///
+/// Secret values are masked unless `include_secrets` is set, in which case the
+/// file can be imported again with `import_mcp_settings` and is written
+/// readable by the user only.
+#[tauri::command]
+pub async fn export_mcp_settings(
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    dest_path: String,
+    include_secrets: bool,
+) -> Result<McpSettingsExport, AppError> {
+    let dest = expand_home(dest_path.trim());
+    validate_export_destination(&dest)?;
+
+    let mut servers = Vec::new();
+    let mut unparsed = Vec::new();
+    let mut names: Vec<String> = grpc
+        .list_mcp_servers()
+        .await?
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    names.sort();
+    for name in names {
+        let definition = grpc
+            .find_runner_by_exact_name(&name)
+            .await?
+            .and_then(|r| r.data)
+            .map(|data| data.definition);
+        match definition.and_then(|d| server_from_definition(&name, &d)) {
+            Some(server) => servers.push(server),
+            None => unparsed.push(name),
+        }
+    }
+
+    let exported = servers.iter().map(|s| s.name.clone()).collect();
+    let content = mcp_settings_toml(servers, include_secrets)?;
+    write_export_file(&dest, &content, include_secrets)?;
+    if include_secrets {
+        tracing::warn!(
+            "Exported MCP runner definitions with secrets to {}",
+            dest.display()
+        );
+    }
+
+    Ok(McpSettingsExport {
+        path: dest.display().to_string(),
+        exported,
+        unparsed,
+        secrets_included: include_secrets,
     })
 }
 
@@ -142,7 +1255,7 @@ fn github_mcp_toml(name: &str, url: &str, token: &str) -> Result<String, AppErro
         "-i".to_string(),
         "--rm".to_string(),
         "-e".to_string(),
-        "GITHUB_PERSONAL_ACCESS_TOKEN".to_string(),
+        Platform::GitHub.token_env_key().to_string(),
     ];
 
     if is_ghes {
@@ -189,6 +1302,23 @@ envs = {envs}
     Ok(toml)
 }
 
+/// GitHub host a runner definition from `github_mcp_toml` targets
+///
+/// Definitions without `GITHUB_HOST` talk to github.com.
+pub(crate) fn github_runner_host(definition: &str) -> String {
+    let re = regex::Regex::new(r#"GITHUB_HOST\s*=\s*"([^"]+)""#).expect("valid regex");
+    re.captures(definition)
+        .map(|caps| caps[1].to_ascii_lowercase())
+        .unwrap_or_else(|| "github.com".to_string())
+}
+
+/// Access token stored in a runner definition from `github_mcp_toml` or `gitea_mcp_toml`
+pub(crate) fn runner_token(definition: &str, platform: Platform) -> Option<String> {
+    let re = regex::Regex::new(&format!(r#"{}\s*=\s*"([^"]+)""#, platform.token_env_key()))
+        .expect("valid regex");
+    re.captures(definition).map(|caps| caps[1].to_string())
+}
+
 /// Generate Gitea MCP Server TOML definition (Docker execution format)
 ///
 /// Reference: https://gitea.com/gitea/gitea-mcp
@@ -207,6 +1337,10 @@ envs = {envs}
 /// Note: GITEA_HOST is passed via environment variable for self-hosted Gitea instances.
 /// GITEA_INSECURE is set to "true" when using http:// URLs.
 fn gitea_mcp_toml(name: &str, url: &str, token: &str) -> Result<String, AppError> {
+    // GITEA_HOST must be the web base (sub-path included, no trailing slash);
+    // the server appends /api/v1 itself
+    let url = gitea_web_base(url)?;
+    let url = url.as_str();
     let parsed =
         Url::parse(url).map_err(|e| AppError::InvalidInput(format!("Invalid URL: {}", e)))?;
     let is_insecure = parsed.scheme() == "http";
@@ -217,7 +1351,7 @@ fn gitea_mcp_toml(name: &str, url: &str, token: &str) -> Result<String, AppError
         "-i".to_string(),
         "--rm".to_string(),
         "-e".to_string(),
-        "GITEA_ACCESS_TOKEN".to_string(),
+        Platform::Gitea.token_env_key().to_string(),
         "-e".to_string(),
         "GITEA_HOST".to_string(),
     ];
@@ -268,3 +1402,177 @@ envs = {envs}
 
     Ok(toml)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_runners_ignores_case() {
+        let runner = |id, name: &str| RunnerInfo {
+            id,
+            name: name.to_string(),
+            runner_type: "MCP_SERVER".to_string(),
+            description: String::new(),
+        };
+        let runners = vec![
+            runner(1, "gitea-work"),
+            runner(2, "GitHub-Personal"),
+            runner(3, "github"),
+            runner(4, "command"),
+        ];
+
+        let names: Vec<String> = filter_runners(runners.clone(), " GITHUB ")
+            .into_iter()
+            .map(|r| r.name)
+            .collect();
+        assert_eq!(names, vec!["GitHub-Personal", "github"]);
+        assert_eq!(filter_runners(runners, "").len(), 4);
+    }
+
+    #[test]
+    fn test_missing_runner_repositories() {
+        let runners: HashSet<String> = ["github".to_string()].into();
+        let missing = missing_runner_repositories(
+            vec![
+                (1, "octo/app".to_string(), "github".to_string()),
+                (2, "team/site".to_string(), "gitea-old".to_string()),
+            ],
+            &runners,
+        );
+        assert_eq!(
+            missing,
+            vec![MissingRunnerRepository {
+                repository_id: 2,
+                name: "team/site".to_string(),
+                mcp_server_name: "gitea-old".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_mask_definition_secrets() {
+        let definition =
+            github_mcp_toml("github", "https://ghe.example.com", "ghp_secret").unwrap();
+        let masked = mask_definition_secrets(&definition);
+        assert!(!masked.contains("ghp_secret"));
+        assert!(masked.contains(r#"GITHUB_PERSONAL_ACCESS_TOKEN = "[redacted]""#));
+        assert!(masked.contains(r#"GITHUB_HOST = "ghe.example.com""#));
+        assert_eq!(
+            runner_token(&definition, Platform::GitHub).as_deref(),
+            Some("ghp_secret")
+        );
+        assert_eq!(runner_token(&definition, Platform::Gitea), None);
+    }
+
+    #[test]
+    fn test_parse_mcp_settings() {
+        let content = r#"
+[[server]]
+name = "time"
+description = "Time server"
+transport = "stdio"
+command = "uvx"
+args = ["mcp-server-time"]
+envs = { TZ = "UTC" }
+timeout_sec = 30
+
+[[server]]
+name = "remote"
+transport = "sse"
+url = "http://localhost:8000/sse"
+"#;
+        let servers = parse_mcp_settings(content).unwrap();
+        assert_eq!(servers.len(), 2);
+
+        let definition = mcp_settings_definition(&servers[0]).unwrap();
+        let reparsed = parse_mcp_settings(&definition).unwrap();
+        assert_eq!(reparsed.len(), 1);
+        assert_eq!(reparsed[0].args, vec!["mcp-server-time"]);
+        assert_eq!(reparsed[0].extra.get("timeout_sec"), Some(&30.into()));
+        assert!(definition.contains("[[server]]"));
+
+        let invalid = [
+            "",
+            "[[server]]\nname = \"a\"\n",
+            "[[server]]\nname = \"a\"\ntransport = \"stdio\"\n",
+            "[[server]]\nname = \"a\"\ntransport = \"http\"\nurl = \"x\"\n",
+            "[[server]]\nname = \"a b\"\ntransport = \"sse\"\nurl = \"x\"\n",
+            "[[server]]\nname = \"a\"\ntransport = \"sse\"\nurl = \"x\"\n\
+             [[server]]\nname = \"a\"\ntransport = \"sse\"\nurl = \"y\"\n",
+        ];
+        for content in invalid {
+            assert!(
+                matches!(
+                    parse_mcp_settings(content),
+                    Err(AppError::Validation { .. })
+                ),
+                "accepted: {content}"
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_export_file_restricts_secrets() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mode =
+            |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        let secret = dir.path().join("secret.toml");
+        write_export_file(&secret, "token", true).unwrap();
+        assert_eq!(mode(&secret), 0o600);
+
+        // Overwriting a readable file with secrets restricts it as well
+        let existing = dir.path().join("existing.toml");
+        std::fs::write(&existing, "masked").unwrap();
+        std::fs::set_permissions(&existing, std::fs::Permissions::from_mode(0o644)).unwrap();
+        write_export_file(&existing, "token", true).unwrap();
+        assert_eq!(mode(&existing), 0o600);
+        assert_eq!(std::fs::read_to_string(&existing).unwrap(), "token");
+    }
+
+    #[test]
+    fn test_export_mcp_settings_roundtrip() {
+        let definition =
+            github_mcp_toml("github", "https://ghe.example.com", "ghp_secret").unwrap();
+        let server = server_from_definition("github-work", &definition).unwrap();
+        assert_eq!(server.name, "github-work");
+        assert!(server_from_definition("broken", "not toml").is_none());
+
+        let masked = mcp_settings_toml(vec![server.clone()], false).unwrap();
+        assert!(!masked.contains("ghp_secret"));
+        let full = mcp_settings_toml(vec![server], true).unwrap();
+        let imported = parse_mcp_settings(&full).unwrap();
+        assert_eq!(imported[0].name, "github-work");
+        assert_eq!(
+            imported[0]
+                .envs
+                .get("GITHUB_PERSONAL_ACCESS_TOKEN")
+                .and_then(|v| v.as_str()),
+            Some("ghp_secret")
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        assert!(validate_export_destination(&dir.path().join("mcp.toml")).is_ok());
+        assert!(validate_export_destination(dir.path()).is_err());
+        assert!(validate_export_destination(&dir.path().join("missing/mcp.toml")).is_err());
+    }
+
+    #[test]
+    fn test_latency_stats() {
+        use std::time::Duration;
+        assert_eq!(latency_stats(&[]), None);
+
+        let latencies: Vec<Duration> = (1..=20).rev().map(Duration::from_millis).collect();
+        let stats = latency_stats(&latencies).unwrap();
+        assert_eq!(stats.min_ms, 1.0);
+        assert_eq!(stats.max_ms, 20.0);
+        assert_eq!(stats.mean_ms, 10.5);
+        assert_eq!(stats.p95_ms, 19.0);
+
+        let single = latency_stats(&[Duration::from_millis(7)]).unwrap();
+        assert_eq!(single.p95_ms, 7.0);
+    }
+}
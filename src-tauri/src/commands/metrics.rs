@@ -0,0 +1,162 @@
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::{get_repository_by_id, AgentJobStatus, DbPool};
+use crate::error::AppError;
+
+/// Time bucket for agent metrics
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+pub enum MetricsBucket {
+    Day,
+    /// Weeks starting on Monday
+    Week,
+}
+
+impl MetricsBucket {
+    /// SQLite expression giving the bucket start date of `created_at`
+    ///
+    /// `created_at` is stored in UTC by `datetime('now')`, so buckets are UTC days.
+    fn start_expr(self) -> &'static str {
+        match self {
+            MetricsBucket::Day => "date(created_at)",
+            MetricsBucket::Week => "date(created_at, '-6 days', 'weekday 1')",
+        }
+    }
+}
+
+/// Agent job counts for one time bucket
+#[derive(Debug, PartialEq, Serialize)]
+pub struct AgentMetricsBucket {
+    /// First day of the bucket (UTC), `YYYY-MM-DD`
+    pub bucket_start: String,
+    pub started: i64,
+    /// Jobs that finished successfully, with or without a pull request
+    pub completed: i64,
+    pub failed: i64,
+    /// Mean time from creation to first finished status, in seconds
+    pub average_duration_secs: Option<f64>,
+}
+
+fn query_agent_metrics(
+    conn: &Connection,
+    repository_id: i64,
+    bucket: MetricsBucket,
+) -> Result<Vec<AgentMetricsBucket>, AppError> {
+    // The first finished status in the history ends the run; updated_at also
+    // moves on later changes such as a PR being merged
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {bucket} AS bucket_start,
+                COUNT(*),
+                SUM(status IN {success}),
+                SUM(status = 'Failed'),
+                AVG((julianday((SELECT MIN(changed_at) FROM job_status_history
+                                WHERE job_id = agent_jobs.id AND status IN {terminal}))
+                     - julianday(created_at)) * 86400)
+         FROM agent_jobs
+         WHERE repository_id = ?1
+         GROUP BY bucket_start
+         ORDER BY bucket_start",
+        bucket = bucket.start_expr(),
+        success = AgentJobStatus::success_sql_list(),
+        terminal = AgentJobStatus::terminal_sql_list(),
+    ))?;
+    let buckets = stmt
+        .query_map([repository_id], |row| {
+            Ok(AgentMetricsBucket {
+                bucket_start: row.get(0)?,
+                started: row.get(1)?,
+                completed: row.get(2)?,
+                failed: row.get(3)?,
+                average_duration_secs: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(buckets)
+}
+
+/// Count a repository's agent jobs per day or week, for a trends chart
+///
+/// Buckets without jobs are omitted.
+#[tauri::command]
+pub async fn agent_metrics(
+    db: State<'_, DbPool>,
+    repository_id: i64,
+    bucket: MetricsBucket,
+) -> Result<Vec<AgentMetricsBucket>, AppError> {
+    get_repository_by_id(&db, repository_id)?;
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    query_agent_metrics(&conn, repository_id, bucket)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_agent_metrics() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let conn = pool.get().unwrap();
+        let repository_id = crate::db::test_support::insert_repository(&conn);
+        // 2024-01-01 is a Monday
+        for (issue, status, created_at, finished_at) in [
+            (1, "PrCreated", "2024-01-01 10:00:00", "2024-01-01 10:10:00"),
+            (2, "Failed", "2024-01-01 23:00:00", "2024-01-01 23:20:00"),
+            (
+                3,
+                "RunningAgent",
+                "2024-01-07 12:00:00",
+                "2024-01-07 12:00:00",
+            ),
+            (4, "NoChanges", "2024-01-08 00:30:00", "2024-01-08 00:35:00"),
+        ] {
+            conn.execute(
+                "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, 'RunningAgent', ?4, ?4)",
+                rusqlite::params![repository_id, issue, issue.to_string(), created_at],
+            )
+            .unwrap();
+            let job_id = conn.last_insert_rowid();
+            conn.execute(
+                "UPDATE agent_jobs SET status = ?1 WHERE id = ?2",
+                rusqlite::params![status, job_id],
+            )
+            .unwrap();
+            conn.execute(
+                "UPDATE job_status_history SET changed_at = ?1 WHERE job_id = ?2 AND status = ?3",
+                rusqlite::params![finished_at, job_id, status],
+            )
+            .unwrap();
+        }
+        // A later merge does not stretch the run time
+        conn.execute(
+            "UPDATE agent_jobs SET status = 'Merged', updated_at = '2024-01-03 00:00:00'
+             WHERE jobworkerp_job_id = '1'",
+            [],
+        )
+        .unwrap();
+
+        let days = query_agent_metrics(&conn, repository_id, MetricsBucket::Day).unwrap();
+        assert_eq!(days.len(), 3);
+        assert_eq!(
+            days[0],
+            AgentMetricsBucket {
+                bucket_start: "2024-01-01".to_string(),
+                started: 2,
+                completed: 1,
+                failed: 1,
+                average_duration_secs: days[0].average_duration_secs,
+            }
+        );
+        assert!((days[0].average_duration_secs.unwrap() - 900.0).abs() < 1.0);
+        assert_eq!(days[1].average_duration_secs, None);
+
+        let weeks = query_agent_metrics(&conn, repository_id, MetricsBucket::Week).unwrap();
+        let starts: Vec<(&str, i64)> = weeks
+            .iter()
+            .map(|w| (w.bucket_start.as_str(), w.started))
+            .collect();
+        assert_eq!(starts, vec![("2024-01-01", 3), ("2024-01-08", 1)]);
+    }
+}
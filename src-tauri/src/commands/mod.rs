@@ -1,15 +1,39 @@
 mod connection;
+mod crypto;
+mod database;
+mod diagnostics;
+mod events;
 mod issues;
+mod job_feed;
 mod jobs;
+mod labels;
 mod mcp;
+mod metrics;
+mod opener;
+mod platforms;
 mod pulls;
 mod repositories;
 mod settings;
+mod tokens;
+mod workflows;
+mod worktree;
 
 pub use connection::*;
+pub use crypto::*;
+pub use database::*;
+pub use diagnostics::*;
+pub use events::*;
 pub use issues::*;
+pub use job_feed::*;
 pub use jobs::*;
+pub use labels::*;
 pub use mcp::*;
+pub use metrics::*;
+pub use opener::*;
+pub use platforms::*;
 pub use pulls::*;
 pub use repositories::*;
 pub use settings::*;
+pub use tokens::*;
+pub use workflows::*;
+pub use worktree::*;
@@ -0,0 +1,72 @@
+use tauri::{AppHandle, State};
+use tauri_plugin_opener::OpenerExt;
+use url::Url;
+
+use crate::db::DbPool;
+use crate::error::AppError;
+
+/// Hosts of registered repositories (from both `url` and `base_url`)
+fn repository_hosts(db: &DbPool) -> Result<Vec<String>, AppError> {
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    let mut stmt = conn.prepare("SELECT url, base_url FROM repositories")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(rows
+        .iter()
+        .flat_map(|(url, base_url)| [url, base_url])
+        .filter_map(|u| Url::parse(u).ok())
+        .filter_map(|u| u.host_str().map(|h| h.to_ascii_lowercase()))
+        .collect())
+}
+
+/// Validate a URL before handing it to the system opener.
+/// Allows `https`, and plain `http` only for hosts of registered repositories
+/// (e.g. a self-hosted Gitea). Other schemes such as `file:` or `javascript:`
+/// are rejected.
+fn validate_external_url(url: &str, allowed_http_hosts: &[String]) -> Result<Url, AppError> {
+    let parsed =
+        Url::parse(url).map_err(|e| AppError::InvalidInput(format!("Invalid URL: {}", e)))?;
+
+    let host = parsed
+        .host_str()
+        .map(|h| h.to_ascii_lowercase())
+        .ok_or_else(|| AppError::InvalidInput("URL must have a host".to_string()))?;
+
+    if !parsed.username().is_empty() || parsed.password().is_some() {
+        return Err(AppError::InvalidInput(
+            "URL must not contain credentials".to_string(),
+        ));
+    }
+
+    match parsed.scheme() {
+        "https" => Ok(parsed),
+        "http" if allowed_http_hosts.contains(&host) => Ok(parsed),
+        "http" => Err(AppError::InvalidInput(format!(
+            "Plain http is only allowed for registered repository hosts, got '{}'",
+            host
+        ))),
+        scheme => Err(AppError::InvalidInput(format!(
+            "URL scheme '{}' is not allowed",
+            scheme
+        ))),
+    }
+}
+
+/// Open an issue/PR URL in the system browser
+#[tauri::command]
+pub async fn open_external(
+    app: AppHandle,
+    db: State<'_, DbPool>,
+    url: String,
+) -> Result<(), AppError> {
+    let hosts = repository_hosts(&db)?;
+    let url = validate_external_url(&url, &hosts)?;
+
+    app.opener()
+        .open_url(url.as_str(), None::<&str>)
+        .map_err(|e| AppError::Internal(format!("Failed to open URL: {}", e)))
+}
@@ -0,0 +1,145 @@
+use serde::Serialize;
+use std::time::Duration;
+
+use super::repositories::normalize_base_url;
+use crate::db::Platform;
+use crate::error::AppError;
+
+/// Upper bound for the version request made by `verify_gitea_url`
+const GITEA_VERIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Features the app supports for a platform
+#[derive(Debug, Serialize)]
+pub struct PlatformCapabilities {
+    pub issues: bool,
+    pub pulls: bool,
+    pub comments: bool,
+    pub search: bool,
+}
+
+/// Platform description for the UI
+#[derive(Debug, Serialize)]
+pub struct PlatformInfo {
+    /// Value accepted wherever a `Platform` is expected (e.g. "GitHub")
+    pub id: Platform,
+    pub display_name: &'static str,
+    pub default_host: Option<&'static str>,
+    pub token_env_key: &'static str,
+    pub supports: PlatformCapabilities,
+}
+
+/// Base URLs of a Gitea instance, which may be served under a sub-path
+#[derive(Debug, PartialEq, Serialize)]
+pub struct GiteaUrlInfo {
+    /// Web base including any sub-path (e.g. `https://example.com/git`)
+    pub web_base: String,
+    /// REST API base (`{web_base}/api/v1`)
+    pub api_base: String,
+    /// Version reported by the instance
+    pub version: String,
+}
+
+/// Normalize a Gitea instance URL to its web base, keeping any sub-path
+///
+/// Accepts the web URL with or without a trailing slash, or the API URL
+/// (`.../api/v1`), so `https://example.com/git/` and
+/// `https://example.com/git/api/v1` both yield `https://example.com/git`.
+pub(crate) fn gitea_web_base(input: &str) -> Result<String, AppError> {
+    let normalized = normalize_base_url(input, "url")?;
+    let web_base = normalized
+        .strip_suffix("/api/v1")
+        .unwrap_or(&normalized)
+        .trim_end_matches('/');
+    Ok(web_base.to_string())
+}
+
+/// Check that a URL points at a Gitea instance and normalize it
+///
+/// Requests the public `/api/v1/version` endpoint, so no token is needed.
+#[tauri::command]
+pub async fn verify_gitea_url(url: String) -> Result<GiteaUrlInfo, AppError> {
+    let web_base = gitea_web_base(&url)?;
+    let api_base = format!("{}/api/v1", web_base);
+
+    let response = reqwest::Client::new()
+        .get(format!("{}/version", api_base))
+        .header(reqwest::header::ACCEPT, "application/json")
+        .timeout(GITEA_VERIFY_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| {
+            AppError::InvalidInput(format!("Cannot reach {}: {}", web_base, e.without_url()))
+        })?;
+    let body: serde_json::Value = response.json().await.unwrap_or(serde_json::Value::Null);
+    let version = body
+        .get("version")
+        .and_then(|v| v.as_str())
+        .map(String::from)
+        .ok_or_else(|| {
+            AppError::validation(
+                "url",
+                format!(
+                    "{} does not look like a Gitea instance (no version at {}/version)",
+                    web_base, api_base
+                ),
+            )
+        })?;
+
+    Ok(GiteaUrlInfo {
+        web_base,
+        api_base,
+        version,
+    })
+}
+
+/// Features backed by MCP tools in the issue, pull request and search commands
+fn capabilities(platform: Platform) -> PlatformCapabilities {
+    match platform {
+        Platform::GitHub | Platform::Gitea => PlatformCapabilities {
+            issues: true,
+            pulls: true,
+            comments: false,
+            search: true,
+        },
+    }
+}
+
+/// List supported platforms and their capabilities
+#[tauri::command]
+pub async fn supported_platforms() -> Result<Vec<PlatformInfo>, AppError> {
+    Ok(Platform::ALL
+        .into_iter()
+        .map(|platform| PlatformInfo {
+            id: platform,
+            display_name: platform.display_name(),
+            default_host: platform.default_host(),
+            token_env_key: platform.token_env_key(),
+            supports: capabilities(platform),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gitea_web_base_keeps_sub_path() {
+        for input in [
+            "https://example.com/git",
+            "https://example.com/git/",
+            "example.com/git/api/v1",
+            "https://example.com/git/api/v1/",
+        ] {
+            assert_eq!(gitea_web_base(input).unwrap(), "https://example.com/git");
+        }
+        assert_eq!(
+            gitea_web_base("http://localhost:3000").unwrap(),
+            "http://localhost:3000"
+        );
+        assert!(matches!(
+            gitea_web_base("https://user:pw@example.com/git"),
+            Err(AppError::Validation { .. })
+        ));
+    }
+}
@@ -2,10 +2,59 @@ use regex::Regex;
 use std::sync::Arc;
 use tauri::State;
 
-use crate::db::{get_repository_by_id, DbPool, Platform, PullRequest};
+use std::collections::HashSet;
+
+use crate::db::{
+    get_repository_by_id, DbPool, PaginatedPullRequests, Platform, PullRequest,
+    RelatedPullRequests, Repository, ReviewComment,
+};
 use crate::error::AppError;
+use crate::grpc::payload::{normalize_mcp_payload, page_info, payload_items};
+use crate::grpc::tool_overrides::McpOperation;
 use crate::grpc::JobworkerpClient;
 
+/// Page size used when the caller does not specify one
+const DEFAULT_PULLS_PER_PAGE: u32 = 30;
+
+/// Largest page size accepted by GitHub and Gitea
+const MAX_PULLS_PER_PAGE: u32 = 100;
+
+/// Pull requests scanned by `find_related_prs` when the caller does not specify a limit
+const DEFAULT_RELATED_PR_SCAN_LIMIT: u32 = 1000;
+
+/// Hard cap on pull requests scanned by `find_related_prs`
+const MAX_RELATED_PR_SCAN_LIMIT: u32 = 5000;
+
+/// Related pull requests returned when the caller does not specify a limit
+const DEFAULT_MAX_RELATED_PRS: u32 = 10;
+
+/// Keys holding the pull request list in object-shaped payloads
+const PULL_LIST_KEYS: &[&str] = &["pull_requests", "pullRequests", "items"];
+
+/// Keys holding the review comment list in object-shaped payloads
+const REVIEW_COMMENT_LIST_KEYS: &[&str] = &["comments", "items"];
+
+/// Keys holding the review list in object-shaped payloads
+const REVIEW_LIST_KEYS: &[&str] = &["reviews", "items"];
+
+/// Parameters for fetching one page of pull requests
+struct PullsQuery<'a> {
+    state: &'a str,
+    page: u32,
+    per_page: u32,
+    labels: &'a [String],
+    sort: Option<&'a str>,
+}
+
+/// Build pull request URL from repository URL and PR number
+pub(super) fn build_pr_url(repo_url: &str, pr_number: i32, platform: Platform) -> String {
+    let base = repo_url.trim_end_matches('/');
+    match platform {
+        Platform::GitHub => format!("{}/pull/{}", base, pr_number),
+        Platform::Gitea => format!("{}/pulls/{}", base, pr_number),
+    }
+}
+
 /// Get the MCP tool name for listing pull requests based on platform
 fn get_list_pulls_tool(platform: Platform) -> &'static str {
     match platform {
@@ -14,6 +63,55 @@ fn get_list_pulls_tool(platform: Platform) -> &'static str {
     }
 }
 
+/// Build list arguments for the platform's MCP tool
+/// GitHub MCP takes "perPage", Gitea MCP takes "pageSize"
+fn build_list_pulls_args(repo: &Repository, query: &PullsQuery) -> serde_json::Value {
+    let mut args = serde_json::json!({
+        "owner": repo.owner,
+        "repo": repo.repo_name,
+        "state": query.state,
+        "page": query.page,
+    });
+
+    let per_page_key = match repo.platform {
+        Platform::GitHub => "perPage",
+        Platform::Gitea => "pageSize",
+    };
+    args[per_page_key] = serde_json::json!(query.per_page);
+
+    if let Some(sort) = query.sort {
+        args["sort"] = serde_json::Value::String(sort.to_string());
+    }
+    if !query.labels.is_empty() {
+        args["labels"] = serde_json::Value::String(query.labels.join(","));
+    }
+    args
+}
+
+/// Check if a raw pull request carries every requested label
+/// Labels can be array of strings or array of objects with "name" field
+fn has_labels(value: &serde_json::Value, labels: &[String]) -> bool {
+    if labels.is_empty() {
+        return true;
+    }
+    let names: Vec<&str> = value
+        .get("labels")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|l| {
+                    l.as_str()
+                        .or_else(|| l.get("name").and_then(|n| n.as_str()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    labels
+        .iter()
+        .all(|wanted| names.iter().any(|name| name.eq_ignore_ascii_case(wanted)))
+}
+
 /// Parse pull request from MCP result JSON (handles both GitHub and Gitea formats)
 fn parse_pull_request(value: &serde_json::Value) -> Option<PullRequest> {
     let number_i64 = value.get("number")?.as_i64()?;
@@ -78,34 +176,186 @@ fn parse_pull_request(value: &serde_json::Value) -> Option<PullRequest> {
     })
 }
 
-/// Extract pull requests from MCP result
-fn extract_pulls_from_result(result: &serde_json::Value) -> Result<Vec<PullRequest>, AppError> {
-    // First, try to extract from MCP content structure
-    if let Some(content) = result.get("content").and_then(|c| c.as_array()) {
-        for item in content {
-            if let Some(text) = item.get("text").and_then(|t| t.as_str()) {
-                if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(text) {
-                    if let Some(arr) = parsed.as_array() {
-                        return Ok(arr.iter().filter_map(parse_pull_request).collect());
-                    }
-                }
-            }
-        }
-    }
+/// Extract one page of pull requests from MCP result
+/// Handles multiple formats:
+/// 1. MCP content structure: {"content": [{"text": "..."}]}
+/// 2. Object with list and pagination: {"pull_requests": [...], "pageInfo": {...}, "totalCount": N}
+/// 3. Direct array: [...]
+/// 4. Single PR object: {"number": ...}
+fn extract_pulls_from_result(
+    result: &serde_json::Value,
+    query: &PullsQuery,
+) -> PaginatedPullRequests {
+    let payload = normalize_mcp_payload(result);
+    let info = page_info(&payload);
 
-    // Direct array format
-    if let Some(arr) = result.as_array() {
-        return Ok(arr.iter().filter_map(parse_pull_request).collect());
+    let raw: Vec<&serde_json::Value> = match payload_items(&payload, PULL_LIST_KEYS) {
+        Some(arr) => arr.iter().collect(),
+        None if payload.get("number").is_some() => vec![&payload],
+        None => vec![],
+    };
+    let raw_count = raw.len();
+
+    let items = raw
+        .into_iter()
+        .filter(|v| has_labels(v, query.labels))
+        .filter_map(parse_pull_request)
+        .collect();
+
+    PaginatedPullRequests {
+        items,
+        page: query.page,
+        per_page: query.per_page,
+        total_count: info.total_count,
+        // Without pageInfo, a full page means there may be more
+        has_next_page: info
+            .has_next_page
+            .unwrap_or(raw_count >= query.per_page as usize),
     }
+}
 
-    // Single PR
-    if result.get("number").is_some() {
-        if let Some(pr) = parse_pull_request(result) {
-            return Ok(vec![pr]);
-        }
+/// Fetch one page of pull requests via MCP server
+async fn fetch_pulls_page(
+    grpc: &JobworkerpClient,
+    repo: &Repository,
+    query: &PullsQuery<'_>,
+) -> Result<PaginatedPullRequests, AppError> {
+    let tool_name = grpc.tool_for(
+        &repo.mcp_server_name,
+        McpOperation::ListPulls,
+        get_list_pulls_tool(repo.platform),
+    );
+    let args = build_list_pulls_args(repo, query);
+    tracing::debug!("list_pulls args: {:?}", args);
+
+    let result = grpc
+        .call_mcp_tool(&repo.mcp_server_name, &tool_name, &args)
+        .await?;
+    Ok(extract_pulls_from_result(&result, query))
+}
+
+/// Parse an inline review comment from MCP result JSON
+///
+/// GitHub reports `line`/`original_line` and `in_reply_to_id`; Gitea reports
+/// `position`/`original_position` and has no reply links.
+fn parse_review_comment(value: &serde_json::Value) -> Option<ReviewComment> {
+    let id = value.get("id")?.as_i64()?;
+    let path = value.get("path")?.as_str()?.to_string();
+    let str_field = |key: &str| value.get(key).and_then(|v| v.as_str());
+    let line = ["line", "original_line", "position", "original_position"]
+        .iter()
+        .filter_map(|key| value.get(*key).and_then(|v| v.as_i64()))
+        .find(|line| *line > 0)
+        .and_then(|line| i32::try_from(line).ok());
+
+    Some(ReviewComment {
+        id,
+        body: str_field("body").unwrap_or("").to_string(),
+        // GitHub: user.login, Gitea: user.login or user.username
+        user: value
+            .get("user")
+            .and_then(|u| {
+                u.get("login")
+                    .or_else(|| u.get("username"))
+                    .and_then(|v| v.as_str())
+            })
+            .unwrap_or("")
+            .to_string(),
+        path,
+        line,
+        diff_hunk: str_field("diff_hunk").map(String::from),
+        in_reply_to: value.get("in_reply_to_id").and_then(|v| v.as_i64()),
+        review_id: value.get("pull_request_review_id").and_then(|v| v.as_i64()),
+        html_url: str_field("html_url").unwrap_or("").to_string(),
+        created_at: str_field("created_at").unwrap_or("").to_string(),
+        updated_at: str_field("updated_at").unwrap_or("").to_string(),
+    })
+}
+
+/// Extract review comments from MCP result
+fn extract_review_comments(result: &serde_json::Value) -> Vec<ReviewComment> {
+    let payload = normalize_mcp_payload(result);
+    match payload_items(&payload, REVIEW_COMMENT_LIST_KEYS) {
+        Some(arr) => arr.iter().filter_map(parse_review_comment).collect(),
+        None => parse_review_comment(&payload).into_iter().collect(),
     }
+}
+
+/// Fetch inline review comments on a GitHub pull request
+async fn fetch_github_review_comments(
+    grpc: &JobworkerpClient,
+    repo: &Repository,
+    pr_number: i32,
+) -> Result<Vec<ReviewComment>, AppError> {
+    let args = serde_json::json!({
+        "owner": repo.owner,
+        "repo": repo.repo_name,
+        "pullNumber": pr_number,
+    });
+    let tool_name = grpc.tool_for(
+        &repo.mcp_server_name,
+        McpOperation::PrReviewComments,
+        "get_pull_request_review_comments",
+    );
+    let result = grpc
+        .call_mcp_tool(&repo.mcp_server_name, &tool_name, &args)
+        .await?;
+    Ok(extract_review_comments(&result))
+}
 
-    Ok(vec![])
+/// Fetch inline review comments on a Gitea pull request
+///
+/// Gitea only lists comments per review, so the reviews are listed first.
+async fn fetch_gitea_review_comments(
+    grpc: &JobworkerpClient,
+    repo: &Repository,
+    pr_number: i32,
+) -> Result<Vec<ReviewComment>, AppError> {
+    let args = serde_json::json!({
+        "owner": repo.owner,
+        "repo": repo.repo_name,
+        "index": pr_number,
+    });
+    let reviews_tool = grpc.tool_for(
+        &repo.mcp_server_name,
+        McpOperation::PrReviews,
+        "list_pull_request_reviews",
+    );
+    let comments_tool = grpc.tool_for(
+        &repo.mcp_server_name,
+        McpOperation::PrReviewComments,
+        "list_pull_request_review_comments",
+    );
+    let result = grpc
+        .call_mcp_tool(&repo.mcp_server_name, &reviews_tool, &args)
+        .await?;
+    let payload = normalize_mcp_payload(&result);
+    let review_ids: Vec<i64> = payload_items(&payload, REVIEW_LIST_KEYS)
+        .map(|arr| {
+            arr.iter()
+                .filter(|r| r.get("comments_count").and_then(|v| v.as_i64()) != Some(0))
+                .filter_map(|r| r.get("id").and_then(|v| v.as_i64()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut comments = Vec::new();
+    for review_id in review_ids {
+        let mut args = args.clone();
+        args["review_id"] = serde_json::json!(review_id);
+        let result = grpc
+            .call_mcp_tool(&repo.mcp_server_name, &comments_tool, &args)
+            .await?;
+        comments.extend(
+            extract_review_comments(&result)
+                .into_iter()
+                .map(|c| ReviewComment {
+                    review_id: c.review_id.or(Some(review_id)),
+                    ..c
+                }),
+        );
+    }
+    Ok(comments)
 }
 
 /// Check if a PR is related to a specific issue number
@@ -153,56 +403,194 @@ fn is_related_pr(pr: &PullRequest, issue_number: i32) -> bool {
 }
 
 /// List pull requests for a repository via MCP server
+///
+/// `labels` are forwarded to the MCP server and also applied to the returned
+/// page, since not every platform filters pull requests by label.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn list_pulls(
     db: State<'_, DbPool>,
     grpc: State<'_, Arc<JobworkerpClient>>,
     repository_id: i64,
     state: Option<String>,
-) -> Result<Vec<PullRequest>, AppError> {
-    let repo = get_repository_by_id(&db, repository_id)?;
-    let tool_name = get_list_pulls_tool(repo.platform);
+    page: Option<u32>,
+    per_page: Option<u32>,
+    labels: Option<Vec<String>>,
+    sort: Option<String>,
+) -> Result<PaginatedPullRequests, AppError> {
+    let page = page.unwrap_or(1);
+    let per_page = per_page.unwrap_or(DEFAULT_PULLS_PER_PAGE);
+    if page == 0 || per_page == 0 {
+        return Err(AppError::InvalidInput(
+            "page and per_page must be at least 1".to_string(),
+        ));
+    }
 
-    let args = serde_json::json!({
-        "owner": repo.owner,
-        "repo": repo.repo_name,
-        "state": state.unwrap_or_else(|| "open".to_string()),
-    });
+    let repo = get_repository_by_id(&db, repository_id)?;
+    let state = state.unwrap_or_else(|| "open".to_string());
+    let labels = labels.unwrap_or_default();
+    let query = PullsQuery {
+        state: &state,
+        page,
+        per_page: per_page.min(MAX_PULLS_PER_PAGE),
+        labels: &labels,
+        sort: sort.as_deref(),
+    };
 
-    let result = grpc
-        .call_mcp_tool(&repo.mcp_server_name, tool_name, &args)
-        .await?;
-    extract_pulls_from_result(&result)
+    fetch_pulls_page(&grpc, &repo, &query).await
 }
 
 /// Find pull requests related to a specific issue
+///
+/// Scans open and closed pull requests page by page, stopping once
+/// `max_results` related pull requests are found or `scan_limit` pull requests
+/// have been examined. Pages are scanned whole, so up to one page more than
+/// `scan_limit` may be examined. `truncated` is set when the scan stopped
+/// early or more than `max_results` related pull requests were found.
 #[tauri::command]
 pub async fn find_related_prs(
     db: State<'_, DbPool>,
     grpc: State<'_, Arc<JobworkerpClient>>,
     repository_id: i64,
     issue_number: i32,
-) -> Result<Vec<PullRequest>, AppError> {
+    max_results: Option<u32>,
+    scan_limit: Option<u32>,
+) -> Result<RelatedPullRequests, AppError> {
+    let max_results = max_results.unwrap_or(DEFAULT_MAX_RELATED_PRS);
+    let scan_limit = scan_limit
+        .unwrap_or(DEFAULT_RELATED_PR_SCAN_LIMIT)
+        .min(MAX_RELATED_PR_SCAN_LIMIT);
+    if max_results == 0 || scan_limit == 0 {
+        return Err(AppError::InvalidInput(
+            "max_results and scan_limit must be at least 1".to_string(),
+        ));
+    }
+
     let repo = get_repository_by_id(&db, repository_id)?;
-    let tool_name = get_list_pulls_tool(repo.platform);
+    let per_page = scan_limit.min(MAX_PULLS_PER_PAGE);
 
-    // Fetch all PRs (open and closed) to find related ones
-    let args = serde_json::json!({
-        "owner": repo.owner,
-        "repo": repo.repo_name,
-        "state": "all",
-    });
+    let mut seen = HashSet::new();
+    let mut related = Vec::new();
+    let mut truncated = false;
+    for page in 1.. {
+        let query = PullsQuery {
+            state: "all",
+            page,
+            per_page,
+            labels: &[],
+            sort: None,
+        };
+        let batch = fetch_pulls_page(&grpc, &repo, &query).await?;
 
-    let result = grpc
-        .call_mcp_tool(&repo.mcp_server_name, tool_name, &args)
-        .await?;
-    let all_prs = extract_pulls_from_result(&result)?;
+        // Stop if the server ignores paging and repeats the same results
+        let mut new_items = batch
+            .items
+            .into_iter()
+            .filter(|pr| seen.insert(pr.number))
+            .peekable();
+        if new_items.peek().is_none() {
+            break;
+        }
+        related.extend(new_items.filter(|pr| is_related_pr(pr, issue_number)));
 
-    // Filter to related PRs
-    let related: Vec<PullRequest> = all_prs
-        .into_iter()
-        .filter(|pr| is_related_pr(pr, issue_number))
-        .collect();
+        if !batch.has_next_page {
+            break;
+        }
+        if related.len() >= max_results as usize || seen.len() >= scan_limit as usize {
+            truncated = true;
+            break;
+        }
+    }
 
-    Ok(related)
+    if related.len() > max_results as usize {
+        truncated = true;
+        related.truncate(max_results as usize);
+    }
+    Ok(RelatedPullRequests {
+        items: related,
+        scanned: seen.len() as u32,
+        truncated,
+    })
+}
+
+/// Get inline review comments on a pull request via MCP server
+///
+/// Comments are returned oldest first. Replies carry `in_reply_to` on GitHub;
+/// on Gitea, comments are grouped into threads by `review_id`, `path` and `line`.
+#[tauri::command]
+pub async fn get_pr_review_comments(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    repository_id: i64,
+    pr_number: i32,
+) -> Result<Vec<ReviewComment>, AppError> {
+    if pr_number <= 0 {
+        return Err(AppError::validation(
+            "pr_number",
+            "Pull request number must be positive",
+        ));
+    }
+
+    let repo = get_repository_by_id(&db, repository_id)?;
+    let mut comments = match repo.platform {
+        Platform::GitHub => fetch_github_review_comments(&grpc, &repo, pr_number).await?,
+        Platform::Gitea => fetch_gitea_review_comments(&grpc, &repo, pr_number).await?,
+    };
+    comments.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+    Ok(comments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_pr_url_keeps_sub_path() {
+        assert_eq!(
+            build_pr_url("https://example.com/git/me/tool/", 3, Platform::Gitea),
+            "https://example.com/git/me/tool/pulls/3"
+        );
+        assert_eq!(
+            build_pr_url("https://github.com/octo/app", 3, Platform::GitHub),
+            "https://github.com/octo/app/pull/3"
+        );
+    }
+
+    #[test]
+    fn test_extract_review_comments_both_platforms() {
+        let github = serde_json::json!({
+            "content": [{"type": "text", "text": serde_json::json!([{
+                "id": 11,
+                "body": "nit",
+                "user": {"login": "octocat"},
+                "path": "src/lib.rs",
+                "line": 42,
+                "diff_hunk": "@@ -1,3 +1,4 @@",
+                "in_reply_to_id": 10,
+                "pull_request_review_id": 5,
+                "created_at": "2024-01-01T00:00:00Z"
+            }]).to_string()}]
+        });
+        let comments = extract_review_comments(&github);
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].user, "octocat");
+        assert_eq!(comments[0].line, Some(42));
+        assert_eq!(comments[0].in_reply_to, Some(10));
+        assert_eq!(comments[0].review_id, Some(5));
+
+        let gitea = serde_json::json!([{
+            "id": 3,
+            "body": "why?",
+            "user": {"username": "gitea-user"},
+            "path": "main.go",
+            "position": 0,
+            "original_position": 7,
+            "diff_hunk": "@@ -5 +5 @@"
+        }, {"id": 4, "body": "no path"}]);
+        let comments = extract_review_comments(&gitea);
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].user, "gitea-user");
+        assert_eq!(comments[0].line, Some(7));
+        assert_eq!(comments[0].in_reply_to, None);
+    }
 }
@@ -1,8 +1,14 @@
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tauri::State;
 
-use crate::db::DbPool;
+use crate::db::{record_event, AppEventType, DbPool};
 use crate::error::AppError;
+use crate::grpc::JobworkerpClient;
+use crate::hooks::{HookOutcome, PostJobHook, PostJobPayload};
+
+/// Shortest interval between background token checks, to spare API rate limits
+const MIN_TOKEN_CHECK_INTERVAL_MINUTES: i64 = 15;
 
 /// Application settings
 #[derive(Debug, Serialize, Deserialize)]
@@ -12,6 +18,16 @@ pub struct AppSettings {
     pub default_base_branch: String,
     pub agent_timeout_minutes: i64,
     pub sync_interval_minutes: i64,
+    /// Webhook URL or command run when an agent job finishes
+    pub post_job_hook: Option<String>,
+    /// MCP tool calls allowed per minute for each server; 0 means unlimited
+    pub mcp_calls_per_minute: i64,
+    /// Longest prompt (default and custom combined) accepted for an agent run
+    pub max_prompt_length: i64,
+    /// Running jobs updated within this many minutes are reattached at startup
+    pub reattach_window_minutes: i64,
+    /// Minutes between background MCP server token checks; 0 disables them
+    pub token_check_interval_minutes: i64,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -23,6 +39,12 @@ pub struct UpdateSettingsRequest {
     pub default_base_branch: Option<String>,
     pub agent_timeout_minutes: Option<i64>,
     pub sync_interval_minutes: Option<i64>,
+    /// An empty string removes the hook
+    pub post_job_hook: Option<String>,
+    pub mcp_calls_per_minute: Option<i64>,
+    pub max_prompt_length: Option<i64>,
+    pub reattach_window_minutes: Option<i64>,
+    pub token_check_interval_minutes: Option<i64>,
 }
 
 /// Get application settings
@@ -33,12 +55,12 @@ pub async fn get_app_settings(db: State<'_, DbPool>) -> Result<AppSettings, AppE
 }
 
 /// Fetch settings from connection (internal helper)
-fn fetch_settings(
-    conn: &r2d2::PooledConnection<r2d2_sqlite::SqliteConnectionManager>,
-) -> Result<AppSettings, AppError> {
+pub(crate) fn fetch_settings(conn: &rusqlite::Connection) -> Result<AppSettings, AppError> {
     conn.query_row(
         "SELECT id, worktree_base_path, default_base_branch, agent_timeout_minutes,
-                sync_interval_minutes, created_at, updated_at
+                sync_interval_minutes, post_job_hook,
+                mcp_calls_per_minute, max_prompt_length, reattach_window_minutes,
+                token_check_interval_minutes, created_at, updated_at
          FROM app_settings WHERE id = 1",
         [],
         |row| {
@@ -48,8 +70,13 @@ fn fetch_settings(
                 default_base_branch: row.get(2)?,
                 agent_timeout_minutes: row.get(3)?,
                 sync_interval_minutes: row.get(4)?,
-                created_at: row.get(5)?,
-                updated_at: row.get(6)?,
+                post_job_hook: row.get(5)?,
+                mcp_calls_per_minute: row.get(6)?,
+                max_prompt_length: row.get(7)?,
+                reattach_window_minutes: row.get(8)?,
+                token_check_interval_minutes: row.get(9)?,
+                created_at: row.get(10)?,
+                updated_at: row.get(11)?,
             })
         },
     )
@@ -64,8 +91,9 @@ fn validate_update_request(
         Some(path) => {
             let trimmed = path.trim();
             if trimmed.is_empty() {
-                return Err(AppError::InvalidInput(
-                    "worktree_base_path cannot be empty".into(),
+                return Err(AppError::validation(
+                    "worktree_base_path",
+                    "worktree_base_path cannot be empty",
                 ));
             }
             Some(trimmed.to_string())
@@ -77,8 +105,9 @@ fn validate_update_request(
         Some(branch) => {
             let trimmed = branch.trim();
             if trimmed.is_empty() {
-                return Err(AppError::InvalidInput(
-                    "default_base_branch cannot be empty".into(),
+                return Err(AppError::validation(
+                    "default_base_branch",
+                    "default_base_branch cannot be empty",
                 ));
             }
             Some(trimmed.to_string())
@@ -88,8 +117,9 @@ fn validate_update_request(
 
     let agent_timeout_minutes = match request.agent_timeout_minutes {
         Some(minutes) if minutes <= 0 => {
-            return Err(AppError::InvalidInput(
-                "agent_timeout_minutes must be a positive number".into(),
+            return Err(AppError::validation(
+                "agent_timeout_minutes",
+                "agent_timeout_minutes must be a positive number",
             ));
         }
         other => other,
@@ -97,18 +127,77 @@ fn validate_update_request(
 
     let sync_interval_minutes = match request.sync_interval_minutes {
         Some(minutes) if minutes <= 0 => {
-            return Err(AppError::InvalidInput(
-                "sync_interval_minutes must be a positive number".into(),
+            return Err(AppError::validation(
+                "sync_interval_minutes",
+                "sync_interval_minutes must be a positive number",
+            ));
+        }
+        other => other,
+    };
+
+    let mcp_calls_per_minute = match request.mcp_calls_per_minute {
+        Some(count) if !(0..=i64::from(u32::MAX)).contains(&count) => {
+            return Err(AppError::validation(
+                "mcp_calls_per_minute",
+                "mcp_calls_per_minute must be zero or a positive number",
+            ));
+        }
+        other => other,
+    };
+
+    let max_prompt_length = match request.max_prompt_length {
+        Some(length) if length <= 0 => {
+            return Err(AppError::validation(
+                "max_prompt_length",
+                "max_prompt_length must be a positive number",
+            ));
+        }
+        other => other,
+    };
+
+    let reattach_window_minutes = match request.reattach_window_minutes {
+        Some(minutes) if minutes <= 0 => {
+            return Err(AppError::validation(
+                "reattach_window_minutes",
+                "reattach_window_minutes must be a positive number",
             ));
         }
         other => other,
     };
 
+    let token_check_interval_minutes = match request.token_check_interval_minutes {
+        Some(minutes) if minutes != 0 && minutes < MIN_TOKEN_CHECK_INTERVAL_MINUTES => {
+            return Err(AppError::validation(
+                "token_check_interval_minutes",
+                format!(
+                    "token_check_interval_minutes must be 0 (disabled) or at least {}",
+                    MIN_TOKEN_CHECK_INTERVAL_MINUTES
+                ),
+            ));
+        }
+        other => other,
+    };
+
+    // Empty clears the hook; anything else must be a valid webhook or command
+    let post_job_hook = match &request.post_job_hook {
+        Some(spec) if spec.trim().is_empty() => Some(String::new()),
+        Some(spec) => {
+            PostJobHook::parse(spec)?;
+            Some(spec.trim().to_string())
+        }
+        None => None,
+    };
+
     Ok(UpdateSettingsRequest {
         worktree_base_path,
         default_base_branch,
         agent_timeout_minutes,
         sync_interval_minutes,
+        post_job_hook,
+        mcp_calls_per_minute,
+        max_prompt_length,
+        reattach_window_minutes,
+        token_check_interval_minutes,
     })
 }
 
@@ -117,6 +206,7 @@ fn validate_update_request(
 pub async fn update_app_settings(
     request: UpdateSettingsRequest,
     db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
 ) -> Result<AppSettings, AppError> {
     let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
 
@@ -125,6 +215,11 @@ pub async fn update_app_settings(
         && request.default_base_branch.is_none()
         && request.agent_timeout_minutes.is_none()
         && request.sync_interval_minutes.is_none()
+        && request.post_job_hook.is_none()
+        && request.mcp_calls_per_minute.is_none()
+        && request.max_prompt_length.is_none()
+        && request.reattach_window_minutes.is_none()
+        && request.token_check_interval_minutes.is_none()
     {
         return fetch_settings(&conn);
     }
@@ -138,6 +233,13 @@ pub async fn update_app_settings(
         default_base_branch = COALESCE(:default_base_branch, default_base_branch),
         agent_timeout_minutes = COALESCE(:agent_timeout_minutes, agent_timeout_minutes),
         sync_interval_minutes = COALESCE(:sync_interval_minutes, sync_interval_minutes),
+        post_job_hook = CASE WHEN :post_job_hook IS NULL THEN post_job_hook
+                             ELSE NULLIF(:post_job_hook, '') END,
+        mcp_calls_per_minute = COALESCE(:mcp_calls_per_minute, mcp_calls_per_minute),
+        max_prompt_length = COALESCE(:max_prompt_length, max_prompt_length),
+        reattach_window_minutes = COALESCE(:reattach_window_minutes, reattach_window_minutes),
+        token_check_interval_minutes =
+            COALESCE(:token_check_interval_minutes, token_check_interval_minutes),
         updated_at = datetime('now')
         WHERE id = 1";
 
@@ -148,7 +250,74 @@ pub async fn update_app_settings(
         ":default_base_branch": validated.default_base_branch,
         ":agent_timeout_minutes": validated.agent_timeout_minutes,
         ":sync_interval_minutes": validated.sync_interval_minutes,
+        ":post_job_hook": validated.post_job_hook,
+        ":mcp_calls_per_minute": validated.mcp_calls_per_minute,
+        ":max_prompt_length": validated.max_prompt_length,
+        ":reattach_window_minutes": validated.reattach_window_minutes,
+        ":token_check_interval_minutes": validated.token_check_interval_minutes,
     })?;
 
-    fetch_settings(&conn)
+    let changed: Vec<&str> = [
+        ("worktree_base_path", validated.worktree_base_path.is_some()),
+        (
+            "default_base_branch",
+            validated.default_base_branch.is_some(),
+        ),
+        (
+            "agent_timeout_minutes",
+            validated.agent_timeout_minutes.is_some(),
+        ),
+        (
+            "sync_interval_minutes",
+            validated.sync_interval_minutes.is_some(),
+        ),
+        ("post_job_hook", validated.post_job_hook.is_some()),
+        (
+            "mcp_calls_per_minute",
+            validated.mcp_calls_per_minute.is_some(),
+        ),
+        ("max_prompt_length", validated.max_prompt_length.is_some()),
+        (
+            "reattach_window_minutes",
+            validated.reattach_window_minutes.is_some(),
+        ),
+        (
+            "token_check_interval_minutes",
+            validated.token_check_interval_minutes.is_some(),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(field, set)| set.then_some(field))
+    .collect();
+    record_event(
+        &conn,
+        AppEventType::SettingsChanged,
+        &format!("Settings updated: {}", changed.join(", ")),
+    );
+
+    let settings = fetch_settings(&conn)?;
+    grpc.set_mcp_calls_per_minute(settings.mcp_calls_per_minute as u32);
+    Ok(settings)
+}
+
+/// Run the configured post-job hook with a sample payload
+///
+/// Uses the same timeout and payload shape as a real job, so users can check
+/// their webhook or command without running an agent.
+#[tauri::command]
+pub async fn test_post_job_hook(db: State<'_, DbPool>) -> Result<HookOutcome, AppError> {
+    let spec: Option<String> = {
+        let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+        conn.query_row(
+            "SELECT post_job_hook FROM app_settings WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )?
+    };
+    let spec = spec
+        .ok_or_else(|| AppError::validation("post_job_hook", "No post-job hook is configured"))?;
+
+    PostJobHook::parse(&spec)?
+        .run(&PostJobPayload::sample())
+        .await
 }
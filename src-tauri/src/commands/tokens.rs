@@ -0,0 +1,308 @@
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, State};
+
+use super::mcp::runner_token;
+use crate::db::{get_repository_by_id, DbPool, Platform, Repository};
+use crate::error::AppError;
+use crate::grpc::JobworkerpClient;
+use crate::token_monitor::{check_runner_tokens, RunnerTokenStatus};
+
+/// Upper bound for each platform API request made by the token check
+const TOKEN_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pause between two runners when the user asks for a check of all tokens
+const MANUAL_CHECK_SPACING: Duration = Duration::from_secs(1);
+
+/// Kind of access token found in a runner definition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenKind {
+    /// GitHub classic PAT; granted scopes are reported by the API
+    Classic,
+    /// GitHub fine-grained PAT; permissions are not reported, only probed
+    FineGrained,
+    /// Gitea access token; scopes are probed
+    Gitea,
+    /// Token rejected by the platform, so its kind cannot be told
+    Unknown,
+}
+
+/// Whether an MCP server's token can do what the agent workflow needs
+#[derive(Debug, Serialize)]
+pub struct TokenScopeReport {
+    pub token_kind: TokenKind,
+    /// Token accepted by the platform
+    pub valid: bool,
+    /// Scopes granted to the token, when the platform reports them
+    pub scopes: Option<Vec<String>>,
+    pub required_scopes: Vec<String>,
+    pub missing_scopes: Vec<String>,
+    /// Token can push to the repository, as reported for the repository
+    pub can_push: Option<bool>,
+    /// Checks that could not be performed
+    pub detail: Option<String>,
+}
+
+/// Scopes a classic PAT needs: pushing branches and opening pull requests
+fn missing_classic_scopes(granted: &[String], private: bool) -> Vec<String> {
+    let has = |scope: &str| granted.iter().any(|g| g == scope);
+    if has("repo") || (!private && has("public_repo")) {
+        Vec::new()
+    } else {
+        vec![if private { "repo" } else { "public_repo" }.to_string()]
+    }
+}
+
+/// Scope named in a Gitea 403 such as
+/// `token does not have at least one of required scope(s): [read:issue]`
+fn gitea_missing_scope(message: &str) -> Option<String> {
+    let (_, rest) = message.split_once("required scope(s)")?;
+    let start = rest.find('[')? + 1;
+    let end = rest[start..].find(']')? + start;
+    Some(rest[start..end].trim().to_string()).filter(|s| !s.is_empty())
+}
+
+struct ApiResponse {
+    status: reqwest::StatusCode,
+    oauth_scopes: Option<String>,
+    body: serde_json::Value,
+}
+
+async fn api_get(
+    client: &reqwest::Client,
+    url: &str,
+    authorization: &str,
+) -> Result<ApiResponse, AppError> {
+    let response = client
+        .get(url)
+        .header(reqwest::header::AUTHORIZATION, authorization)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .header(reqwest::header::USER_AGENT, "local-code-agent")
+        .timeout(TOKEN_CHECK_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| {
+            AppError::Internal(format!("Request to {} failed: {}", url, e.without_url()))
+        })?;
+    let status = response.status();
+    let oauth_scopes = response
+        .headers()
+        .get("x-oauth-scopes")
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+    let body = response.json().await.unwrap_or(serde_json::Value::Null);
+    Ok(ApiResponse {
+        status,
+        oauth_scopes,
+        body,
+    })
+}
+
+fn can_push(repository: &serde_json::Value) -> Option<bool> {
+    repository.get("permissions")?.get("push")?.as_bool()
+}
+
+async fn check_github_token(
+    client: &reqwest::Client,
+    repo: &Repository,
+    token: &str,
+) -> Result<TokenScopeReport, AppError> {
+    let authorization = format!("Bearer {}", token);
+    let user = api_get(client, &format!("{}/user", repo.base_url), &authorization).await?;
+    // A rejected token reports no scopes; diagnosing them would blame the wrong thing
+    if matches!(
+        user.status,
+        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN
+    ) {
+        return Ok(TokenScopeReport {
+            token_kind: TokenKind::Unknown,
+            valid: false,
+            scopes: None,
+            required_scopes: Vec::new(),
+            missing_scopes: Vec::new(),
+            can_push: None,
+            detail: Some(format!("Token rejected by the platform ({})", user.status)),
+        });
+    }
+    let repository = api_get(
+        client,
+        &format!("{}/repos/{}/{}", repo.base_url, repo.owner, repo.repo_name),
+        &authorization,
+    )
+    .await?;
+    let private = repository
+        .body
+        .get("private")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    let can_push = can_push(&repository.body);
+
+    // Only classic PATs (and OAuth tokens) report their scopes
+    match user.oauth_scopes {
+        Some(header) => {
+            let scopes: Vec<String> = header
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+            Ok(TokenScopeReport {
+                token_kind: TokenKind::Classic,
+                valid: user.status.is_success(),
+                missing_scopes: missing_classic_scopes(&scopes, private),
+                required_scopes: vec![if private { "repo" } else { "public_repo" }.to_string()],
+                scopes: Some(scopes),
+                can_push,
+                detail: None,
+            })
+        }
+        None => {
+            let mut missing_scopes = Vec::new();
+            if can_push == Some(false) || repository.status == reqwest::StatusCode::NOT_FOUND {
+                missing_scopes.push("contents:write".to_string());
+            }
+            Ok(TokenScopeReport {
+                token_kind: TokenKind::FineGrained,
+                valid: user.status.is_success(),
+                scopes: None,
+                required_scopes: vec![
+                    "contents:write".to_string(),
+                    "pull_requests:write".to_string(),
+                    "issues:read".to_string(),
+                ],
+                missing_scopes,
+                can_push,
+                detail: Some(
+                    "Fine-grained tokens do not report their permissions; only repository \
+                     access and push were checked"
+                        .to_string(),
+                ),
+            })
+        }
+    }
+}
+
+async fn check_gitea_token(
+    client: &reqwest::Client,
+    repo: &Repository,
+    token: &str,
+) -> Result<TokenScopeReport, AppError> {
+    let authorization = format!("token {}", token);
+    let repo_api = format!("{}/repos/{}/{}", repo.base_url, repo.owner, repo.repo_name);
+    let user = api_get(client, &format!("{}/user", repo.base_url), &authorization).await?;
+    let repository = api_get(client, &repo_api, &authorization).await?;
+    let issues = api_get(
+        client,
+        &format!("{}/issues?limit=1", repo_api),
+        &authorization,
+    )
+    .await?;
+
+    // Gitea names the scope a request lacked in its 403 message
+    let mut missing_scopes: Vec<String> = [&user, &repository, &issues]
+        .iter()
+        .filter(|r| r.status == reqwest::StatusCode::FORBIDDEN)
+        .filter_map(|r| gitea_missing_scope(r.body.get("message")?.as_str()?))
+        .collect();
+    let can_push = can_push(&repository.body);
+    if can_push == Some(false) {
+        missing_scopes.push("write:repository".to_string());
+    }
+    missing_scopes.sort();
+    missing_scopes.dedup();
+
+    Ok(TokenScopeReport {
+        token_kind: TokenKind::Gitea,
+        valid: user.status != reqwest::StatusCode::UNAUTHORIZED,
+        scopes: None,
+        required_scopes: vec!["write:repository".to_string(), "read:issue".to_string()],
+        missing_scopes,
+        can_push,
+        detail: None,
+    })
+}
+
+/// Check that the token of a repository's MCP server has the scopes the agent needs
+///
+/// The token is read from the runner definition and checked against the
+/// platform API directly, since the MCP servers do not expose token scopes.
+#[tauri::command]
+pub async fn check_runner_token(
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+    repository_id: i64,
+) -> Result<TokenScopeReport, AppError> {
+    let repo = get_repository_by_id(&db, repository_id)?;
+    runner_token_report(&grpc, &repo).await
+}
+
+/// Check the token in a repository's MCP server runner (see `check_runner_token`)
+pub(crate) async fn runner_token_report(
+    grpc: &JobworkerpClient,
+    repo: &Repository,
+) -> Result<TokenScopeReport, AppError> {
+    let definition = grpc
+        .find_runner_by_exact_name(&repo.mcp_server_name)
+        .await?
+        .and_then(|r| r.data)
+        .map(|data| data.definition)
+        .ok_or_else(|| {
+            AppError::NotFound(format!("MCP server '{}' not found", repo.mcp_server_name))
+        })?;
+    let token = runner_token(&definition, repo.platform).ok_or_else(|| {
+        AppError::Config(format!(
+            "MCP server '{}' has no {} in its definition",
+            repo.mcp_server_name,
+            repo.platform.token_env_key()
+        ))
+    })?;
+
+    let client = reqwest::Client::new();
+    match repo.platform {
+        Platform::GitHub => check_github_token(&client, repo, &token).await,
+        Platform::Gitea => check_gitea_token(&client, repo, &token).await,
+    }
+}
+
+/// Check the token of every MCP server runner used by a repository now
+///
+/// Runs the same check as the background token monitor and stores its
+/// results in `token_status`.
+#[tauri::command]
+pub async fn check_all_runner_tokens(
+    app: AppHandle,
+    db: State<'_, DbPool>,
+    grpc: State<'_, Arc<JobworkerpClient>>,
+) -> Result<Vec<RunnerTokenStatus>, AppError> {
+    check_runner_tokens(&app, &db, &grpc, MANUAL_CHECK_SPACING).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_classic_scopes() {
+        let scopes = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(missing_classic_scopes(&scopes(&["repo", "workflow"]), true).is_empty());
+        assert!(missing_classic_scopes(&scopes(&["public_repo"]), false).is_empty());
+        assert_eq!(
+            missing_classic_scopes(&scopes(&["public_repo"]), true),
+            vec!["repo"]
+        );
+        assert_eq!(missing_classic_scopes(&[], false), vec!["public_repo"]);
+    }
+
+    #[test]
+    fn test_gitea_missing_scope() {
+        assert_eq!(
+            gitea_missing_scope(
+                "token does not have at least one of required scope(s): [read:issue]"
+            )
+            .as_deref(),
+            Some("read:issue")
+        );
+        assert_eq!(gitea_missing_scope("Not Found"), None);
+    }
+}
@@ -0,0 +1,164 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::error::AppError;
+
+/// Directory name of bundled workflow definitions, under the resource dir
+const WORKFLOWS_DIR: &str = "workflows";
+
+/// Lines read from the top of a workflow file when looking for its header
+const MAX_HEADER_LINES: usize = 40;
+
+/// Workflow definition file available to agent jobs
+#[derive(Debug, PartialEq, Serialize)]
+pub struct WorkflowInfo {
+    /// File name, e.g. `code-agent-workflow.yaml`
+    pub name: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Directory holding workflow files
+///
+/// The bundled resource directory in release builds; in development, where
+/// resources are not copied, `workflows/` next to the crate manifest.
+fn workflows_dir(app: &AppHandle) -> Option<PathBuf> {
+    let bundled = app
+        .path()
+        .resource_dir()
+        .ok()
+        .map(|dir| dir.join(WORKFLOWS_DIR))
+        .filter(|dir| dir.is_dir());
+    bundled.or_else(|| {
+        Some(Path::new(env!("CARGO_MANIFEST_DIR")).join(WORKFLOWS_DIR)).filter(|dir| dir.is_dir())
+    })
+}
+
+/// Title and description from a workflow file's header
+///
+/// Uses `title:`/`summary:` from the `document:` block of a Serverless
+/// Workflow definition, falling back to the leading `#` comment lines (first
+/// line as title, the rest as description).
+fn parse_workflow_header(content: &str) -> (Option<String>, Option<String>) {
+    let lines: Vec<&str> = content.lines().take(MAX_HEADER_LINES).collect();
+
+    let comments: Vec<&str> = lines
+        .iter()
+        .map(|line| line.trim())
+        .take_while(|line| line.starts_with('#') || line.is_empty())
+        .filter_map(|line| line.strip_prefix('#').map(str::trim))
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    // Indented keys directly under `document:`
+    let document_field = |key: &str| {
+        lines
+            .iter()
+            .skip_while(|line| line.trim_end() != "document:")
+            .skip(1)
+            .take_while(|line| line.starts_with(' ') || line.trim().is_empty())
+            .find_map(|line| {
+                let value = line.trim().strip_prefix(key)?.strip_prefix(':')?.trim();
+                let value = value.trim_matches(|c| c == '"' || c == '\'').trim();
+                (!value.is_empty()).then(|| value.to_string())
+            })
+    };
+
+    let title = document_field("title").or_else(|| comments.first().map(|s| s.to_string()));
+    let description =
+        document_field("summary").or_else(|| (comments.len() > 1).then(|| comments[1..].join(" ")));
+    (title, description)
+}
+
+/// Workflow files in `dir`, sorted by name; an absent directory yields none
+fn read_workflows(dir: &Path) -> Result<Vec<WorkflowInfo>, AppError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut workflows = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let is_yaml = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"));
+        if !is_yaml || !path.is_file() {
+            continue;
+        }
+        let (title, description) = match std::fs::read_to_string(&path) {
+            Ok(content) => parse_workflow_header(&content),
+            Err(e) => {
+                tracing::warn!("Failed to read workflow {}: {}", path.display(), e);
+                (None, None)
+            }
+        };
+        workflows.push(WorkflowInfo {
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            title,
+            description,
+        });
+    }
+    workflows.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(workflows)
+}
+
+/// List the workflow definition files available to agent jobs
+#[tauri::command]
+pub async fn list_workflows(app: AppHandle) -> Result<Vec<WorkflowInfo>, AppError> {
+    match workflows_dir(&app) {
+        Some(dir) => read_workflows(&dir),
+        None => {
+            tracing::debug!("No workflows directory found");
+            Ok(Vec::new())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_workflows() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("code-agent.yaml"),
+            "document:\n  dsl: 1.0.0\n  name: code-agent\n  title: Code Agent\n  summary: \"Fix an issue and open a PR\"\ndo: []\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("review.yml"),
+            "# Review agent\n# Replies to PR review comments\n\ndocument:\n  name: review\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "# not a workflow").unwrap();
+
+        let workflows = read_workflows(dir.path()).unwrap();
+        assert_eq!(
+            workflows,
+            vec![
+                WorkflowInfo {
+                    name: "code-agent.yaml".to_string(),
+                    title: Some("Code Agent".to_string()),
+                    description: Some("Fix an issue and open a PR".to_string()),
+                },
+                WorkflowInfo {
+                    name: "review.yml".to_string(),
+                    title: Some("Review agent".to_string()),
+                    description: Some("Replies to PR review comments".to_string()),
+                },
+            ]
+        );
+
+        assert!(read_workflows(&dir.path().join("missing"))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,854 @@
+use rusqlite::OptionalExtension;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use super::repositories::effective_base_branch;
+use super::settings::fetch_settings;
+use crate::db::{get_repository_by_id, AgentJobStatus, DbPool};
+use crate::error::AppError;
+
+/// Git state of an agent job's worktree
+#[derive(Debug, Serialize)]
+pub struct WorktreeStatus {
+    /// Checked-out branch, or None for a detached HEAD
+    pub branch: Option<String>,
+    pub head_sha: String,
+    /// Whether there are staged, unstaged or untracked changes
+    pub dirty: bool,
+    /// Commits ahead of / behind the upstream branch (0 without an upstream)
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+/// Line changes to one file between two branches
+#[derive(Debug, Serialize)]
+pub struct FileDiffStat {
+    pub path: String,
+    /// None for binary files
+    pub additions: Option<u64>,
+    pub deletions: Option<u64>,
+}
+
+/// Changes on an agent job's branch relative to the base branch
+#[derive(Debug, Serialize)]
+pub struct BranchDiff {
+    pub base_branch: String,
+    pub branch: String,
+    pub files: Vec<FileDiffStat>,
+    pub insertions: u64,
+    pub deletions: u64,
+}
+
+/// Deepest directory level walked when measuring worktree size
+const MAX_USAGE_DEPTH: usize = 64;
+
+/// Disk usage of one agent job's worktree
+#[derive(Debug, Serialize)]
+pub struct JobWorktreeUsage {
+    pub job_id: i64,
+    pub worktree_path: String,
+    pub bytes: u64,
+}
+
+/// Disk usage of all agent job worktrees
+#[derive(Debug, Serialize)]
+pub struct WorktreeUsage {
+    pub total_bytes: u64,
+    pub jobs: Vec<JobWorktreeUsage>,
+}
+
+/// Directory under `worktree_base_path` not referenced by any agent job
+#[derive(Debug, Serialize)]
+pub struct OrphanWorktree {
+    pub path: String,
+    pub name: String,
+    pub size_bytes: u64,
+}
+
+/// Where an agent job for an issue will check out its worktree
+#[derive(Debug, Serialize)]
+pub struct WorktreePathPreview {
+    pub path: String,
+    /// A directory already exists at the path
+    pub exists: bool,
+    /// Active job of a different issue using the same path
+    pub conflicting_job_id: Option<i64>,
+}
+
+/// Expand a leading `~` to the user's home directory
+pub(super) fn expand_home(path: &str) -> PathBuf {
+    let home = directories::BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+    match (path.strip_prefix('~'), home) {
+        (Some(rest), Some(home)) => home.join(rest.trim_start_matches('/')),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Sum the raw size of files under a directory without following symlinks
+///
+/// Git objects shared between worktrees are counted in each one.
+pub(super) fn dir_size(path: &Path, depth: usize) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    if depth >= MAX_USAGE_DEPTH {
+        return 0;
+    }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| dir_size(&entry.path(), depth + 1))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Run a git command in the given directory and return its stdout
+async fn run_git(dir: &Path, args: &[&str]) -> Result<String, AppError> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(AppError::Internal(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parse `git status --porcelain=v2 --branch` output into (branch, dirty, ahead, behind)
+fn parse_status(output: &str) -> (Option<String>, bool, u32, u32) {
+    let mut branch = None;
+    let mut dirty = false;
+    let (mut ahead, mut behind) = (0, 0);
+
+    for line in output.lines() {
+        if let Some(head) = line.strip_prefix("# branch.head ") {
+            if head != "(detached)" {
+                branch = Some(head.to_string());
+            }
+        } else if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            // Format: "+<ahead> -<behind>"
+            for part in ab.split_whitespace() {
+                if let Some(n) = part.strip_prefix('+') {
+                    ahead = n.parse().unwrap_or(0);
+                } else if let Some(n) = part.strip_prefix('-') {
+                    behind = n.parse().unwrap_or(0);
+                }
+            }
+        } else if !line.starts_with('#') && !line.is_empty() {
+            dirty = true;
+        }
+    }
+
+    (branch, dirty, ahead, behind)
+}
+
+/// Agent job worktree resolved on disk
+struct JobWorktree {
+    path: PathBuf,
+    branch_name: Option<String>,
+    base_branch: String,
+    /// The path is the one rendered for the job's repository and issue
+    at_rendered_path: bool,
+}
+
+/// Canonical form of a job's `worktree_path`, strictly inside `base_path`
+///
+/// The base directory itself is rejected, so cleanup can never remove it.
+fn worktree_inside_base(
+    job_id: i64,
+    worktree_path: &str,
+    base_path: &str,
+) -> Result<PathBuf, AppError> {
+    let worktree = match expand_home(worktree_path).canonicalize() {
+        Ok(path) => path,
+        Err(_) => {
+            return Err(AppError::NotFound(format!(
+                "Worktree for job {} no longer exists",
+                job_id
+            )))
+        }
+    };
+    let base = expand_home(base_path).canonicalize().map_err(|e| {
+        AppError::Config(format!(
+            "worktree_base_path '{}' is not accessible: {}",
+            base_path, e
+        ))
+    })?;
+    let inside = worktree
+        .strip_prefix(&base)
+        .is_ok_and(|rest| rest.components().next().is_some());
+    if !inside {
+        return Err(AppError::InvalidInput(format!(
+            "Worktree path '{}' is not inside the worktree base path",
+            worktree_path
+        )));
+    }
+    Ok(worktree)
+}
+
+/// Look up a job's worktree and check it lies under `worktree_base_path`
+///
+/// Returns `NotFound` if the job has no worktree or it has been cleaned up.
+fn resolve_job_worktree(db: &DbPool, job_id: i64) -> Result<JobWorktree, AppError> {
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    let (repository_id, issue_number, repo_slug, worktree_path, branch_name): (
+        i64,
+        i32,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+    ) = conn
+        .query_row(
+            "SELECT j.repository_id, j.issue_number, r.repo_slug, j.worktree_path, j.branch_name
+             FROM agent_jobs j JOIN repositories r ON r.id = j.repository_id
+             WHERE j.id = ?1",
+            [job_id],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            },
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound(format!("Job {} not found", job_id)))?;
+    let worktree_path = worktree_path
+        .ok_or_else(|| AppError::NotFound(format!("Job {} has no worktree", job_id)))?;
+    let settings = fetch_settings(&conn)?;
+
+    let worktree = worktree_inside_base(job_id, &worktree_path, &settings.worktree_base_path)?;
+    let at_rendered_path = repo_slug.is_some_and(|slug| {
+        let rendered = render_worktree_path(&settings.worktree_base_path, &slug, issue_number);
+        expand_home(&rendered).canonicalize().ok().as_ref() == Some(&worktree)
+    });
+
+    Ok(JobWorktree {
+        path: worktree,
+        branch_name,
+        base_branch: effective_base_branch(&conn, repository_id)?.0,
+        at_rendered_path,
+    })
+}
+
+/// Common git dir of `dir`, if `dir` is the top of a linked worktree
+async fn linked_worktree_common_dir(dir: &Path) -> Option<PathBuf> {
+    let output = run_git(
+        dir,
+        &[
+            "rev-parse",
+            "--path-format=absolute",
+            "--show-toplevel",
+            "--git-dir",
+            "--git-common-dir",
+        ],
+    )
+    .await
+    .ok()?;
+    let mut paths = output.lines().map(|line| PathBuf::from(line.trim()));
+    let (toplevel, git_dir, common_dir) = (paths.next()?, paths.next()?, paths.next()?);
+    // A linked worktree has its own git dir under the repository's common dir
+    (toplevel == dir && git_dir != common_dir).then_some(common_dir)
+}
+
+/// Delete an agent job's worktree directory and unregister it from its repository
+///
+/// Only directories strictly under `worktree_base_path` that are a linked git
+/// worktree, or sit at the job's rendered worktree path (e.g. a failed
+/// checkout), are removed. A job without a worktree, or whose worktree is
+/// already gone, is left as is.
+pub(super) async fn remove_job_worktree(db: &DbPool, job_id: i64) -> Result<(), AppError> {
+    let worktree = match resolve_job_worktree(db, job_id) {
+        Ok(worktree) => worktree,
+        Err(AppError::NotFound(_)) => return Ok(()),
+        Err(e) => return Err(e),
+    };
+
+    let common_dir = linked_worktree_common_dir(&worktree.path).await;
+    if common_dir.is_none() && !worktree.at_rendered_path {
+        return Err(AppError::InvalidInput(format!(
+            "'{}' is not a git worktree of job {}; leaving it in place",
+            worktree.path.display(),
+            job_id
+        )));
+    }
+
+    tokio::fs::remove_dir_all(&worktree.path).await?;
+    if let Some(common_dir) = common_dir {
+        if let Err(e) = run_git(&common_dir, &["worktree", "prune"]).await {
+            tracing::warn!("git worktree prune failed for job {}: {}", job_id, e);
+        }
+    }
+    tracing::info!(
+        "Removed worktree for job {}: {}",
+        job_id,
+        worktree.path.display()
+    );
+    Ok(())
+}
+
+/// Inspect the git state of an agent job's worktree
+///
+/// The worktree must be located under the configured `worktree_base_path`.
+/// Returns `NotFound` if the job has no worktree or it has been cleaned up.
+#[tauri::command]
+pub async fn inspect_worktree(
+    db: State<'_, DbPool>,
+    job_id: i64,
+) -> Result<WorktreeStatus, AppError> {
+    let worktree = resolve_job_worktree(&db, job_id)?.path;
+
+    let status = run_git(&worktree, &["status", "--porcelain=v2", "--branch"]).await?;
+    let head_sha = run_git(&worktree, &["rev-parse", "HEAD"]).await?;
+    let (branch, dirty, ahead, behind) = parse_status(&status);
+
+    Ok(WorktreeStatus {
+        branch,
+        head_sha: head_sha.trim().to_string(),
+        dirty,
+        ahead,
+        behind,
+    })
+}
+
+/// Resolve a branch name to a commit-ish, preferring the local branch over `origin/`
+async fn resolve_branch(dir: &Path, branch: &str) -> Option<String> {
+    for candidate in [
+        format!("refs/heads/{}", branch),
+        format!("refs/remotes/origin/{}", branch),
+    ] {
+        let verify = format!("{}^{{commit}}", candidate);
+        if run_git(dir, &["rev-parse", "--verify", "--quiet", &verify])
+            .await
+            .is_ok()
+        {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+/// Parse `git diff --numstat` output into per-file stats
+fn parse_numstat(output: &str) -> Vec<FileDiffStat> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\t');
+            let additions = parts.next()?;
+            let deletions = parts.next()?;
+            let path = parts.next()?;
+            // Binary files report "-" instead of line counts
+            Some(FileDiffStat {
+                path: path.to_string(),
+                additions: additions.parse().ok(),
+                deletions: deletions.parse().ok(),
+            })
+        })
+        .collect()
+}
+
+/// Summarize changes on an agent job's branch relative to the base branch
+///
+/// Equivalent to `git diff <base>...<branch> --stat` in the job's worktree,
+/// previewing what a pull request would contain.
+#[tauri::command]
+pub async fn diff_job_branch(db: State<'_, DbPool>, job_id: i64) -> Result<BranchDiff, AppError> {
+    let worktree = resolve_job_worktree(&db, job_id)?;
+    let branch = worktree
+        .branch_name
+        .ok_or_else(|| AppError::NotFound(format!("Job {} has no branch", job_id)))?;
+    let base_branch = worktree.base_branch;
+
+    let branch_ref = resolve_branch(&worktree.path, &branch)
+        .await
+        .ok_or_else(|| AppError::NotFound(format!("Branch '{}' does not exist", branch)))?;
+    let base_ref = resolve_branch(&worktree.path, &base_branch)
+        .await
+        .ok_or_else(|| {
+            AppError::NotFound(format!("Base branch '{}' does not exist", base_branch))
+        })?;
+
+    let range = format!("{}...{}", base_ref, branch_ref);
+    let output = run_git(&worktree.path, &["diff", "--numstat", &range, "--"]).await?;
+    let files = parse_numstat(&output);
+
+    Ok(BranchDiff {
+        insertions: files.iter().filter_map(|f| f.additions).sum(),
+        deletions: files.iter().filter_map(|f| f.deletions).sum(),
+        base_branch,
+        branch,
+        files,
+    })
+}
+
+/// Report disk usage of agent job worktrees under `worktree_base_path`
+///
+/// Jobs whose worktree was removed or lies outside the base path are skipped.
+#[tauri::command]
+pub async fn worktree_usage(db: State<'_, DbPool>) -> Result<WorktreeUsage, AppError> {
+    let (worktrees, base_path) = {
+        let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+        let mut stmt = conn.prepare(
+            "SELECT id, worktree_path FROM agent_jobs
+             WHERE worktree_path IS NOT NULL ORDER BY id",
+        )?;
+        let worktrees = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        (worktrees, fetch_settings(&conn)?.worktree_base_path)
+    };
+
+    let base = match expand_home(&base_path).canonicalize() {
+        Ok(path) => path,
+        // No worktree has been created yet
+        Err(_) => {
+            return Ok(WorktreeUsage {
+                total_bytes: 0,
+                jobs: Vec::new(),
+            })
+        }
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let jobs: Vec<JobWorktreeUsage> = worktrees
+            .into_iter()
+            .filter_map(|(job_id, worktree_path)| {
+                let path = expand_home(&worktree_path).canonicalize().ok()?;
+                if !path.starts_with(&base) {
+                    tracing::warn!(
+                        "Skipping worktree outside base path for job {}: {}",
+                        job_id,
+                        worktree_path
+                    );
+                    return None;
+                }
+                Some(JobWorktreeUsage {
+                    job_id,
+                    bytes: dir_size(&path, 0),
+                    worktree_path,
+                })
+            })
+            .collect();
+
+        WorktreeUsage {
+            total_bytes: jobs.iter().map(|j| j.bytes).sum(),
+            jobs,
+        }
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// Canonical `worktree_base_path` and the canonical worktree paths of all jobs
+///
+/// Returns None for the base path when it does not exist yet.
+fn worktree_references(db: &DbPool) -> Result<(Option<PathBuf>, HashSet<PathBuf>), AppError> {
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    let mut stmt =
+        conn.prepare("SELECT worktree_path FROM agent_jobs WHERE worktree_path IS NOT NULL")?;
+    let referenced = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .map(|path| {
+            let path = expand_home(&path);
+            path.canonicalize().unwrap_or(path)
+        })
+        .collect();
+    let base = expand_home(&fetch_settings(&conn)?.worktree_base_path)
+        .canonicalize()
+        .ok();
+    Ok((base, referenced))
+}
+
+/// Directories directly under `base` that no job references
+fn scan_orphan_worktrees(base: &Path, referenced: &HashSet<PathBuf>) -> Vec<OrphanWorktree> {
+    let Ok(entries) = std::fs::read_dir(base) else {
+        return Vec::new();
+    };
+    let mut orphans: Vec<OrphanWorktree> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|entry| {
+            let path = entry.path().canonicalize().ok()?;
+            if referenced.contains(&path) {
+                return None;
+            }
+            Some(OrphanWorktree {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                size_bytes: dir_size(&path, 0),
+                path: path.to_string_lossy().into_owned(),
+            })
+        })
+        .collect();
+    orphans.sort_by(|a, b| a.name.cmp(&b.name));
+    orphans
+}
+
+/// Check that `path` is a directory directly under `base` that no job references
+fn verify_orphan(
+    base: &Path,
+    referenced: &HashSet<PathBuf>,
+    path: &str,
+) -> Result<PathBuf, AppError> {
+    let canonical = expand_home(path)
+        .canonicalize()
+        .map_err(|_| AppError::NotFound(format!("Worktree '{}' no longer exists", path)))?;
+    if canonical.parent() != Some(base) || !canonical.is_dir() {
+        return Err(AppError::InvalidInput(format!(
+            "'{}' is not a directory directly under the worktree base path",
+            path
+        )));
+    }
+    if referenced.contains(&canonical) {
+        return Err(AppError::InvalidInput(format!(
+            "'{}' is used by an agent job",
+            path
+        )));
+    }
+    Ok(canonical)
+}
+
+/// Find directories under `worktree_base_path` that no agent job refers to
+///
+/// These are left behind by deleted jobs or crashed runs.
+#[tauri::command]
+pub async fn find_orphan_worktrees(db: State<'_, DbPool>) -> Result<Vec<OrphanWorktree>, AppError> {
+    let (base, referenced) = worktree_references(&db)?;
+    let Some(base) = base else {
+        return Ok(Vec::new());
+    };
+    tokio::task::spawn_blocking(move || scan_orphan_worktrees(&base, &referenced))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// Delete orphan worktree directories found by `find_orphan_worktrees`
+///
+/// Each path is checked again before removal: it must still be a directory
+/// directly under `worktree_base_path` that no job refers to. Paths failing
+/// the check are skipped. Returns the paths that were removed.
+#[tauri::command]
+pub async fn delete_orphan_worktrees(
+    db: State<'_, DbPool>,
+    paths: Vec<String>,
+) -> Result<Vec<String>, AppError> {
+    let (base, referenced) = worktree_references(&db)?;
+    let Some(base) = base else {
+        return Ok(Vec::new());
+    };
+
+    let mut removed = Vec::new();
+    for path in paths {
+        let orphan = match verify_orphan(&base, &referenced, &path) {
+            Ok(orphan) => orphan,
+            Err(e) => {
+                tracing::warn!("Not removing orphan worktree: {}", e);
+                continue;
+            }
+        };
+        let common_dir = run_git(
+            &orphan,
+            &["rev-parse", "--path-format=absolute", "--git-common-dir"],
+        )
+        .await
+        .ok()
+        .map(|dir| PathBuf::from(dir.trim()));
+
+        if let Err(e) = tokio::fs::remove_dir_all(&orphan).await {
+            tracing::warn!("Failed to remove {}: {}", orphan.display(), e);
+            continue;
+        }
+        // Unregister it from its repository if it was a git worktree
+        if let Some(common_dir) = common_dir.filter(|dir| !dir.starts_with(&orphan)) {
+            if let Err(e) = run_git(&common_dir, &["worktree", "prune"]).await {
+                tracing::warn!("git worktree prune failed for {}: {}", orphan.display(), e);
+            }
+        }
+        tracing::info!("Removed orphan worktree: {}", orphan.display());
+        removed.push(path);
+    }
+    Ok(removed)
+}
+
+/// Worktree path of an issue: `<worktree_base_path>/<repo_slug>-<issue_number>`
+pub(crate) fn render_worktree_path(base_path: &str, repo_slug: &str, issue_number: i32) -> String {
+    format!(
+        "{}/{}-{}",
+        base_path.trim_end_matches('/'),
+        repo_slug,
+        issue_number
+    )
+}
+
+/// Active job of a different repository or issue whose worktree is at `path`
+pub(crate) fn find_worktree_collision(
+    conn: &rusqlite::Connection,
+    repository_id: i64,
+    issue_number: i32,
+    path: &str,
+) -> Result<Option<i64>, AppError> {
+    let target = expand_home(path);
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, worktree_path FROM agent_jobs
+         WHERE worktree_path IS NOT NULL
+           AND status IN {}
+           AND NOT (repository_id = ?1 AND issue_number = ?2)
+         ORDER BY id",
+        AgentJobStatus::active_sql_list()
+    ))?;
+    let jobs = stmt
+        .query_map(rusqlite::params![repository_id, issue_number], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(jobs
+        .into_iter()
+        .find(|(_, worktree_path)| expand_home(worktree_path) == target)
+        .map(|(job_id, _)| job_id))
+}
+
+/// Show where an agent job for an issue would check out its worktree
+#[tauri::command]
+pub async fn preview_worktree_path(
+    db: State<'_, DbPool>,
+    repository_id: i64,
+    issue_number: i32,
+) -> Result<WorktreePathPreview, AppError> {
+    let repo = get_repository_by_id(&db, repository_id)?;
+    let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    let settings = fetch_settings(&conn)?;
+    let path = render_worktree_path(&settings.worktree_base_path, &repo.repo_slug, issue_number);
+    Ok(WorktreePathPreview {
+        exists: expand_home(&path).exists(),
+        conflicting_job_id: find_worktree_collision(&conn, repository_id, issue_number, &path)?,
+        path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_numstat() {
+        let files = parse_numstat("3\t1\tsrc/lib.rs\n-\t-\tassets/logo.png\n10\t0\tdocs/a b.md\n");
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!((files[0].additions, files[0].deletions), (Some(3), Some(1)));
+        assert_eq!((files[1].additions, files[1].deletions), (None, None));
+        assert_eq!(files[2].path, "docs/a b.md");
+    }
+
+    #[test]
+    fn test_parse_status() {
+        let clean = "# branch.oid 0123abc\n# branch.head issue-7\n\
+                     # branch.upstream origin/issue-7\n# branch.ab +2 -1\n";
+        assert_eq!(
+            parse_status(clean),
+            (Some("issue-7".to_string()), false, 2, 1)
+        );
+
+        // Renames, untracked files and conflicts all make the worktree dirty
+        for entry in [
+            "2 R. N... 100644 100644 100644 0123 4567 R100 new.rs\told.rs",
+            "? notes.txt",
+            "u UU N... 100644 100644 100644 100644 0123 4567 89ab src/lib.rs",
+        ] {
+            let output = format!(
+                "# branch.oid 0123abc\n# branch.head (detached)\n{}\n",
+                entry
+            );
+            assert_eq!(parse_status(&output), (None, true, 0, 0), "{}", entry);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remove_job_worktree() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let repo = dir.path().join("repo");
+        let base = dir.path().join("worktrees");
+        let worktree = base.join("octo__app-1");
+        std::fs::create_dir_all(&repo).unwrap();
+        for args in [
+            vec!["init", "-q"],
+            vec![
+                "-c",
+                "user.name=t",
+                "-c",
+                "user.email=t@example.com",
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                "init",
+            ],
+            vec![
+                "worktree",
+                "add",
+                "-q",
+                "-b",
+                "issue-1",
+                worktree.to_str().unwrap(),
+            ],
+        ] {
+            run_git(&repo, &args).await.unwrap();
+        }
+
+        let job_id = {
+            let conn = pool.get().unwrap();
+            conn.execute(
+                "UPDATE app_settings SET worktree_base_path = ?1 WHERE id = 1",
+                [base.to_str().unwrap()],
+            )
+            .unwrap();
+            let repository_id = crate::db::test_support::insert_repository(&conn);
+            conn.execute(
+                "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status, worktree_path)
+                 VALUES (?1, 1, 'j1', 'Cancelled', ?2)",
+                rusqlite::params![repository_id, worktree.to_str().unwrap()],
+            )
+            .unwrap();
+            conn.last_insert_rowid()
+        };
+
+        remove_job_worktree(&pool, job_id).await.unwrap();
+        assert!(!worktree.exists());
+        let list = run_git(&repo, &["worktree", "list", "--porcelain"])
+            .await
+            .unwrap();
+        assert!(!list.contains("octo__app-1"));
+        // Already removed: nothing to do
+        remove_job_worktree(&pool, job_id).await.unwrap();
+
+        // A plain directory at some other path is not removed
+        let other = base.join("notes");
+        std::fs::create_dir_all(&other).unwrap();
+        pool.get()
+            .unwrap()
+            .execute(
+                "UPDATE agent_jobs SET worktree_path = ?1 WHERE id = ?2",
+                rusqlite::params![other.to_str().unwrap(), job_id],
+            )
+            .unwrap();
+        assert!(matches!(
+            remove_job_worktree(&pool, job_id).await,
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(other.exists());
+    }
+
+    #[test]
+    fn test_orphan_worktrees() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("worktrees");
+        for name in ["octo__app-1", "octo__app-2"] {
+            std::fs::create_dir_all(base.join(name)).unwrap();
+        }
+        std::fs::write(base.join("octo__app-2/file.txt"), "hello").unwrap();
+        std::fs::write(base.join("stray.txt"), "").unwrap();
+        let base = base.canonicalize().unwrap();
+        let referenced: HashSet<PathBuf> = [base.join("octo__app-1")].into();
+
+        let orphans = scan_orphan_worktrees(&base, &referenced);
+        assert_eq!(orphans.len(), 1);
+        assert_eq!(orphans[0].name, "octo__app-2");
+        assert_eq!(orphans[0].size_bytes, 5);
+
+        assert!(verify_orphan(&base, &referenced, &orphans[0].path).is_ok());
+        assert!(matches!(
+            verify_orphan(
+                &base,
+                &referenced,
+                base.join("octo__app-1").to_str().unwrap()
+            ),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            verify_orphan(
+                &base,
+                &referenced,
+                base.join("octo__app-2/..").to_str().unwrap()
+            ),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            verify_orphan(&base, &referenced, dir.path().to_str().unwrap()),
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_worktree_inside_base() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("worktrees");
+        std::fs::create_dir_all(base.join("octo__app-7")).unwrap();
+        let base_path = base.to_str().unwrap();
+
+        assert_eq!(
+            worktree_inside_base(1, &format!("{}/octo__app-7", base_path), base_path).unwrap(),
+            base.join("octo__app-7").canonicalize().unwrap()
+        );
+        // Never the base itself, nor anything reached by leaving it
+        assert!(matches!(
+            worktree_inside_base(1, base_path, base_path),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            worktree_inside_base(1, &format!("{}/..", base_path), base_path),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            worktree_inside_base(1, &format!("{}/octo__app-8", base_path), base_path),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_worktree_collision() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let conn = pool.get().unwrap();
+        let repository_id = crate::db::test_support::insert_repository(&conn);
+        let path = render_worktree_path("/tmp/worktrees/", "octo__app", 7);
+        assert_eq!(path, "/tmp/worktrees/octo__app-7");
+        for (issue_number, status) in [(7, "RunningAgent"), (8, "Failed")] {
+            conn.execute(
+                "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status, worktree_path)
+                 VALUES (?1, ?2, 'j', ?3, ?4)",
+                rusqlite::params![repository_id, issue_number, status, path],
+            )
+            .unwrap();
+        }
+
+        // The job of the same issue and finished jobs do not collide
+        assert_eq!(
+            find_worktree_collision(&conn, repository_id, 7, &path).unwrap(),
+            None
+        );
+        assert_eq!(
+            find_worktree_collision(&conn, repository_id, 9, "/tmp/worktrees/octo__app-7/")
+                .unwrap(),
+            Some(1)
+        );
+    }
+}
@@ -1,4 +1,4 @@
 // Token encryption with AES-256-GCM
 pub mod token;
 
-pub use token::TokenCrypto;
+pub use token::{CryptoStatus, KeyRotation, KeyStorage, TokenCrypto};
@@ -2,12 +2,28 @@ use aes_gcm::{
     aead::{rand_core::RngCore, Aead, KeyInit, OsRng},
     Aes256Gcm, Nonce,
 };
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Mutex, PoisonError, RwLock};
 use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::error::{AppError, AppResult};
 
 const NONCE_SIZE: usize = 12;
 const KEY_SIZE: usize = 32;
+/// Raw key bytes, wiped from memory when dropped
+type Key = Zeroizing<[u8; KEY_SIZE]>;
 const KEYRING_SERVICE: &str = "local-code-agent";
 const KEYRING_USER: &str = "encryption-key";
+/// Keyring entry holding the active key version after a rotation
+const KEYRING_VERSION_USER: &str = "encryption-key-version";
+/// Known plaintext used to verify a key after moving it between stores
+const VERIFY_SENTINEL: &str = "local-code-agent-key-check";
+/// HKDF info string for the SQLCipher database key
+#[cfg(feature = "sqlcipher")]
+const DB_KEY_INFO: &[u8] = b"local-code-agent sqlcipher database key";
 
 #[derive(Error, Debug)]
 pub enum CryptoError {
@@ -21,43 +37,403 @@ pub enum CryptoError {
     KeychainError(String),
 }
 
+/// Where the encryption key is stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyStorage {
+    /// OS keychain / secret service
+    Keyring,
+    /// Fallback key file in the application data directory
+    File,
+}
+
+/// Key storage status reported to the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct CryptoStatus {
+    pub backend: KeyStorage,
+    pub file_path: Option<String>,
+    /// False when the key is only protected by file permissions
+    pub secure: bool,
+}
+
+/// Ciphers by key version, and the version new data is encrypted with
+///
+/// Version 0 is the original key; rotation adds versions 1, 2, ... The
+/// ciphers wipe their key schedules when dropped (`zeroize` feature of
+/// `aes` and `ghash`).
+struct KeySet {
+    active: u8,
+    ciphers: BTreeMap<u8, Aes256Gcm>,
+}
+
 pub struct TokenCrypto {
-    cipher: Aes256Gcm,
+    keys: RwLock<KeySet>,
+    storage: Mutex<KeyStorage>,
+    /// SQLCipher key derived from the same secret
+    #[cfg(feature = "sqlcipher")]
+    db_key: Key,
+}
+
+/// Handle returned by [`TokenCrypto::rotate_key`]
+///
+/// Decrypts data sealed under any known key and re-encrypts it under the new one.
+pub struct KeyRotation<'a> {
+    crypto: &'a TokenCrypto,
+    pub previous_version: u8,
+    pub new_version: u8,
+}
+
+impl KeyRotation<'_> {
+    /// Re-encrypt data sealed under an older key with the new key
+    pub fn reencrypt(&self, encrypted: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let plaintext = self.crypto.decrypt(encrypted)?;
+        self.crypto.encrypt_with(self.new_version, &plaintext)
+    }
+}
+
+/// Keyring entry name of a rotated key
+fn versioned_key_user(version: u8) -> String {
+    format!("{}-v{}", KEYRING_USER, version)
 }
 
 impl TokenCrypto {
     /// Create TokenCrypto with key from keychain or generate new one
     pub fn new() -> Result<Self, CryptoError> {
-        let key = Self::get_or_generate_key()?;
-        let cipher = Aes256Gcm::new_from_slice(&key).map_err(|_| CryptoError::EncryptionFailed)?;
-        Ok(Self { cipher })
+        let (key, storage) = Self::get_or_generate_key()?;
+        let crypto = Self::with_key(&key, storage)?;
+        if storage == KeyStorage::Keyring {
+            crypto.load_rotated_keys(|user| {
+                keyring::Entry::new(KEYRING_SERVICE, user)
+                    .and_then(|entry| entry.get_password())
+                    .ok()
+            });
+        }
+        Ok(crypto)
+    }
+
+    /// Create TokenCrypto from raw key bytes
+    ///
+    /// The key becomes version 0. The SQLCipher key is always derived from
+    /// it, so rotating the token key never changes the database key.
+    fn with_key(key: &[u8; KEY_SIZE], storage: KeyStorage) -> Result<Self, CryptoError> {
+        let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| CryptoError::EncryptionFailed)?;
+
+        #[cfg(feature = "sqlcipher")]
+        let db_key = {
+            let mut okm = Key::default();
+            hkdf::Hkdf::<sha2::Sha256>::new(None, key)
+                .expand(DB_KEY_INFO, okm.as_mut())
+                .map_err(|_| CryptoError::EncryptionFailed)?;
+            okm
+        };
+
+        Ok(Self {
+            keys: RwLock::new(KeySet {
+                active: 0,
+                ciphers: BTreeMap::from([(0, cipher)]),
+            }),
+            storage: Mutex::new(storage),
+            #[cfg(feature = "sqlcipher")]
+            db_key,
+        })
+    }
+
+    /// Raw SQLCipher key (hex) for the application database
+    ///
+    /// Derived with HKDF-SHA256 from the token encryption key, so it follows
+    /// that key wherever it is stored.
+    #[cfg(feature = "sqlcipher")]
+    pub fn database_key_hex(&self) -> Zeroizing<String> {
+        Zeroizing::new(hex::encode(*self.db_key))
+    }
+
+    fn read_keys(&self) -> std::sync::RwLockReadGuard<'_, KeySet> {
+        self.keys.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Load keys added by earlier rotations
+    ///
+    /// `fetch` returns the keyring password stored under a user name. A
+    /// missing key leaves data sealed under it undecryptable, so it is only
+    /// logged; the newest loadable version becomes active.
+    fn load_rotated_keys(&self, fetch: impl Fn(&str) -> Option<String>) {
+        let Some(version) = fetch(KEYRING_VERSION_USER) else {
+            return;
+        };
+        let Ok(version) = version.trim().parse::<u8>() else {
+            tracing::warn!("Ignoring invalid encryption key version: {:?}", version);
+            return;
+        };
+
+        let mut keys = self.keys.write().unwrap_or_else(PoisonError::into_inner);
+        for v in 1..=version {
+            let cipher = fetch(&versioned_key_user(v))
+                .map(Zeroizing::new)
+                .ok_or(CryptoError::InvalidFormat)
+                .and_then(|key_hex| Self::decode_key(&key_hex))
+                .and_then(|key| {
+                    Aes256Gcm::new_from_slice(key.as_ref()).map_err(|_| CryptoError::InvalidFormat)
+                });
+            match cipher {
+                Ok(cipher) => {
+                    keys.ciphers.insert(v, cipher);
+                    keys.active = v;
+                }
+                Err(_) => tracing::warn!("Encryption key version {} could not be loaded", v),
+            }
+        }
+        if keys.active != version {
+            tracing::warn!(
+                "Active encryption key version {} is missing; using version {}",
+                version,
+                keys.active
+            );
+        }
+    }
+
+    /// Version of the key new data is encrypted with
+    pub fn key_version(&self) -> u8 {
+        self.read_keys().active
+    }
+
+    /// Generate a new key in the keyring and make it the active one
+    ///
+    /// Older keys stay loaded, so existing ciphertexts still decrypt; use the
+    /// returned handle to re-encrypt them. Requires keyring storage.
+    pub fn rotate_key(&self) -> Result<KeyRotation<'_>, CryptoError> {
+        if self.storage() != KeyStorage::Keyring {
+            return Err(CryptoError::KeychainError(
+                "Key rotation requires the key to be stored in the keychain".into(),
+            ));
+        }
+        let next_version = self
+            .key_version()
+            .checked_add(1)
+            .ok_or_else(|| CryptoError::KeychainError("No key versions left".into()))?;
+        let entry = |user: &str| {
+            keyring::Entry::new(KEYRING_SERVICE, user)
+                .map_err(|e| CryptoError::KeychainError(e.to_string()))
+        };
+        self.rotate_key_with(
+            next_version,
+            &entry(&versioned_key_user(next_version))?,
+            &entry(KEYRING_VERSION_USER)?,
+        )
+    }
+
+    /// Store a new key as `new_version` in `key_entry`, then point
+    /// `version_entry` at it
+    ///
+    /// The in-memory active key only changes once both writes succeeded; if
+    /// the version write fails, the new key entry is deleted again.
+    fn rotate_key_with(
+        &self,
+        new_version: u8,
+        key_entry: &keyring::Entry,
+        version_entry: &keyring::Entry,
+    ) -> Result<KeyRotation<'_>, CryptoError> {
+        let mut keys = self.keys.write().unwrap_or_else(PoisonError::into_inner);
+        let previous_version = keys.active;
+        if previous_version.checked_add(1) != Some(new_version) {
+            return Err(CryptoError::KeychainError(
+                "Key was rotated concurrently".into(),
+            ));
+        }
+
+        let key = Self::generate_key();
+        let cipher =
+            Aes256Gcm::new_from_slice(key.as_ref()).map_err(|_| CryptoError::EncryptionFailed)?;
+        let key_hex = Zeroizing::new(hex::encode(*key));
+
+        key_entry.set_password(&key_hex).map_err(|e| {
+            CryptoError::KeychainError(format!("Failed to store new key in keychain: {}", e))
+        })?;
+        let stored = key_entry.get_password().ok().map(Zeroizing::new);
+        let committed = if stored.as_deref() == Some(&*key_hex) {
+            version_entry
+                .set_password(&new_version.to_string())
+                .map_err(|e| format!("Failed to activate new key: {}", e))
+        } else {
+            Err("Key stored in keychain does not match the new key".to_string())
+        };
+        if let Err(message) = committed {
+            if let Err(e) = key_entry.delete_credential() {
+                tracing::warn!("Failed to roll back keychain entry: {:?}", e);
+            }
+            return Err(CryptoError::KeychainError(message));
+        }
+
+        keys.ciphers.insert(new_version, cipher);
+        keys.active = new_version;
+        tracing::info!(
+            "Rotated encryption key from version {} to {}",
+            previous_version,
+            new_version
+        );
+        Ok(KeyRotation {
+            crypto: self,
+            previous_version,
+            new_version,
+        })
+    }
+
+    /// Re-encrypt a batch of ciphertexts under the active key
+    ///
+    /// Each blob is decrypted with whichever known key sealed it. Blobs that
+    /// fail to decrypt are reported in place and do not stop the batch, so
+    /// the caller can tell which tokens are lost. Fails as a whole only if no
+    /// key rotation has happened, as there is no newer key to move to.
+    pub fn reencrypt_all(
+        &self,
+        encrypted: &[Vec<u8>],
+    ) -> AppResult<Vec<Result<Vec<u8>, CryptoError>>> {
+        let version = self.key_version();
+        if version == 0 {
+            return Err(AppError::Crypto(
+                "No rotated key to re-encrypt under; rotate the key first".into(),
+            ));
+        }
+        let results: Vec<_> = encrypted
+            .iter()
+            .map(|blob| {
+                self.decrypt(blob)
+                    .and_then(|plaintext| self.encrypt_with(version, &plaintext))
+            })
+            .collect();
+        let failed = results.iter().filter(|r| r.is_err()).count();
+        if failed > 0 {
+            tracing::warn!(
+                "{} of {} ciphertexts could not be decrypted for re-encryption",
+                failed,
+                results.len()
+            );
+        }
+        Ok(results)
+    }
+
+    /// Current key storage backend
+    fn storage(&self) -> KeyStorage {
+        *self.storage.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Report where the key in use is stored
+    ///
+    /// Reflects the backend chosen at construction time (or by a later
+    /// migration); the key is not re-read.
+    pub fn status(&self) -> CryptoStatus {
+        let storage = self.storage();
+        let file_path = match storage {
+            KeyStorage::Keyring => None,
+            KeyStorage::File => Self::key_file_path()
+                .ok()
+                .map(|p| p.to_string_lossy().into_owned()),
+        };
+
+        CryptoStatus {
+            backend: storage,
+            file_path,
+            secure: storage == KeyStorage::Keyring,
+        }
+    }
+
+    /// Move the fallback key file into the OS keyring
+    ///
+    /// The key is written to the keyring and verified against the active key
+    /// before the file is removed. On any failure the file is left in place.
+    /// No-op if the key already lives in the keyring.
+    pub fn migrate_key_to_keyring(&self) -> Result<CryptoStatus, CryptoError> {
+        if self.storage() == KeyStorage::Keyring {
+            return Ok(self.status());
+        }
+
+        let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+            .map_err(|e| CryptoError::KeychainError(e.to_string()))?;
+        self.migrate_file_key(&entry, &Self::key_file_path()?)?;
+
+        *self.storage.lock().unwrap_or_else(PoisonError::into_inner) = KeyStorage::Keyring;
+        tracing::info!("Migrated encryption key from file to keychain");
+        Ok(self.status())
+    }
+
+    /// Copy the key file into `entry`, verify it, then delete the file
+    fn migrate_file_key(&self, entry: &keyring::Entry, key_path: &Path) -> Result<(), CryptoError> {
+        let key = Self::read_key_file(key_path)?;
+
+        entry
+            .set_password(&Zeroizing::new(hex::encode(*key)))
+            .map_err(|e| {
+                CryptoError::KeychainError(format!("Failed to store key in keychain: {}", e))
+            })?;
+
+        // Read the key back and make sure it decrypts data sealed with the active key
+        let verified = entry
+            .get_password()
+            .map(Zeroizing::new)
+            .map_err(|e| CryptoError::KeychainError(e.to_string()))
+            .and_then(|key_hex| Self::decode_key(&key_hex))
+            .and_then(|stored| Self::with_key(&stored, KeyStorage::Keyring))
+            .and_then(|stored| stored.decrypt(&self.encrypt(VERIFY_SENTINEL)?))
+            .map(|plaintext| plaintext.as_str() == VERIFY_SENTINEL);
+
+        if !matches!(verified, Ok(true)) {
+            if let Err(e) = entry.delete_credential() {
+                tracing::warn!("Failed to roll back keychain entry: {:?}", e);
+            }
+            return Err(CryptoError::KeychainError(
+                "Key stored in keychain does not match the active key".into(),
+            ));
+        }
+
+        std::fs::remove_file(key_path).map_err(|e| {
+            CryptoError::KeychainError(format!(
+                "Key copied to keychain but the key file could not be removed: {}",
+                e
+            ))
+        })
+    }
+
+    /// Generate a random key
+    fn generate_key() -> Key {
+        let mut key = Key::default();
+        OsRng.fill_bytes(key.as_mut());
+        key
+    }
+
+    /// Decode a hex-encoded key
+    fn decode_key(key_hex: &str) -> Result<Key, CryptoError> {
+        let mut key = Key::default();
+        hex::decode_to_slice(key_hex.trim(), key.as_mut())
+            .map_err(|_| CryptoError::InvalidFormat)?;
+        Ok(key)
+    }
+
+    /// Read a hex-encoded key file
+    fn read_key_file(key_path: &Path) -> Result<Key, CryptoError> {
+        let key_hex = Zeroizing::new(
+            std::fs::read_to_string(key_path).map_err(|_| CryptoError::EncryptionFailed)?,
+        );
+        Self::decode_key(&key_hex)
     }
 
     /// Get key from keychain or generate and store new one
     /// Falls back to file-based storage if keychain is unavailable
-    fn get_or_generate_key() -> Result<[u8; KEY_SIZE], CryptoError> {
+    fn get_or_generate_key() -> Result<(Key, KeyStorage), CryptoError> {
         // Try keychain first
         match keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER) {
             Ok(entry) => {
                 match entry.get_password() {
                     Ok(key_hex) => {
                         // Decode existing key from hex
-                        let key = hex::decode(&key_hex).map_err(|_| CryptoError::InvalidFormat)?;
-                        if key.len() != KEY_SIZE {
-                            return Err(CryptoError::InvalidFormat);
-                        }
-                        let mut arr = [0u8; KEY_SIZE];
-                        arr.copy_from_slice(&key);
-                        return Ok(arr);
+                        let key = Self::decode_key(&Zeroizing::new(key_hex))?;
+                        return Ok((key, KeyStorage::Keyring));
                     }
                     Err(_) => {
                         // Generate and store new key
-                        let mut key = [0u8; KEY_SIZE];
-                        OsRng.fill_bytes(&mut key);
-                        let key_hex = hex::encode(key);
+                        let key = Self::generate_key();
+                        let key_hex = Zeroizing::new(hex::encode(*key));
                         if entry.set_password(&key_hex).is_ok() {
                             tracing::info!("Stored new encryption key in keychain");
-                            return Ok(key);
+                            return Ok((key, KeyStorage::Keyring));
                         }
                         // Fall through to file-based storage
                     }
@@ -74,7 +450,8 @@ impl TokenCrypto {
             "Keychain unavailable, falling back to file-based key storage. \
              This is less secure than keychain storage."
         );
-        Self::get_or_generate_key_from_file()
+        let key = Self::get_or_generate_key_from_file()?;
+        Ok((key, KeyStorage::File))
     }
 
     /// Set restrictive file permissions on Windows using ACL
@@ -116,32 +493,31 @@ impl TokenCrypto {
         }
     }
 
+    /// Path of the fallback key file in the application data directory
+    fn key_file_path() -> Result<std::path::PathBuf, CryptoError> {
+        let project_dirs =
+            directories::ProjectDirs::from("com", "local-code-agent", "LocalCodeAgent")
+                .ok_or_else(|| {
+                    CryptoError::KeychainError("Cannot determine data directory".into())
+                })?;
+        Ok(project_dirs.data_local_dir().join(".encryption_key"))
+    }
+
     /// Fallback: store encryption key in application data directory
-    fn get_or_generate_key_from_file() -> Result<[u8; KEY_SIZE], CryptoError> {
-        let key_path = directories::ProjectDirs::from("com", "local-code-agent", "LocalCodeAgent")
-            .ok_or_else(|| CryptoError::KeychainError("Cannot determine data directory".into()))?
-            .data_local_dir()
-            .join(".encryption_key");
+    fn get_or_generate_key_from_file() -> Result<Key, CryptoError> {
+        let key_path = Self::key_file_path()?;
 
         if key_path.exists() {
-            let key_hex =
-                std::fs::read_to_string(&key_path).map_err(|_| CryptoError::EncryptionFailed)?;
-            let key = hex::decode(key_hex.trim()).map_err(|_| CryptoError::InvalidFormat)?;
-            if key.len() != KEY_SIZE {
-                return Err(CryptoError::InvalidFormat);
-            }
-            let mut arr = [0u8; KEY_SIZE];
-            arr.copy_from_slice(&key);
-            Ok(arr)
+            Self::read_key_file(&key_path)
         } else {
             // Generate and store new key
             if let Some(parent) = key_path.parent() {
                 std::fs::create_dir_all(parent).map_err(|_| CryptoError::EncryptionFailed)?;
             }
-            let mut key = [0u8; KEY_SIZE];
-            OsRng.fill_bytes(&mut key);
-            let key_hex = hex::encode(key);
-            std::fs::write(&key_path, &key_hex).map_err(|_| CryptoError::EncryptionFailed)?;
+            let key = Self::generate_key();
+            let key_hex = Zeroizing::new(hex::encode(*key));
+            std::fs::write(&key_path, key_hex.as_bytes())
+                .map_err(|_| CryptoError::EncryptionFailed)?;
 
             // Set restrictive permissions
             #[cfg(unix)]
@@ -161,38 +537,70 @@ impl TokenCrypto {
         }
     }
 
-    /// Encrypt plaintext and return nonce + ciphertext
+    /// Encrypt plaintext with the active key and return version + nonce + ciphertext
     pub fn encrypt(&self, plaintext: &str) -> Result<Vec<u8>, CryptoError> {
+        self.encrypt_with(self.key_version(), plaintext)
+    }
+
+    /// Encrypt plaintext with the key of `version`
+    fn encrypt_with(&self, version: u8, plaintext: &str) -> Result<Vec<u8>, CryptoError> {
+        let keys = self.read_keys();
+        let cipher = keys
+            .ciphers
+            .get(&version)
+            .ok_or(CryptoError::EncryptionFailed)?;
+
         let mut nonce_bytes = [0u8; NONCE_SIZE];
         OsRng.fill_bytes(&mut nonce_bytes);
         let nonce = Nonce::from_slice(&nonce_bytes);
 
-        let ciphertext = self
-            .cipher
+        let ciphertext = cipher
             .encrypt(nonce, plaintext.as_bytes())
             .map_err(|_| CryptoError::EncryptionFailed)?;
 
-        // Prepend nonce to ciphertext
-        let mut result = nonce_bytes.to_vec();
+        // Prepend key version and nonce to ciphertext
+        let mut result = vec![version];
+        result.extend(nonce_bytes);
         result.extend(ciphertext);
         Ok(result)
     }
 
-    /// Decrypt ciphertext (with prepended nonce)
-    pub fn decrypt(&self, encrypted: &[u8]) -> Result<String, CryptoError> {
+    /// Decrypt a versioned ciphertext, or a legacy one (nonce + ciphertext
+    /// sealed with the version 0 key)
+    ///
+    /// A legacy ciphertext may start with a byte that looks like a key
+    /// version; AES-GCM authentication rejects the wrong reading, so both
+    /// are tried. The plaintext is wiped from memory when dropped; copy it
+    /// out only where an API needs an owned `String`.
+    pub fn decrypt(&self, encrypted: &[u8]) -> Result<Zeroizing<String>, CryptoError> {
         if encrypted.len() < NONCE_SIZE {
             return Err(CryptoError::InvalidFormat);
         }
 
-        let (nonce_bytes, ciphertext) = encrypted.split_at(NONCE_SIZE);
-        let nonce = Nonce::from_slice(nonce_bytes);
-
-        let plaintext = self
-            .cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|_| CryptoError::DecryptionFailed)?;
+        let keys = self.read_keys();
+        let open = |cipher: &Aes256Gcm, sealed: &[u8]| -> Option<Vec<u8>> {
+            if sealed.len() < NONCE_SIZE {
+                return None;
+            }
+            let (nonce_bytes, ciphertext) = sealed.split_at(NONCE_SIZE);
+            cipher
+                .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+                .ok()
+        };
+        let versioned = keys
+            .ciphers
+            .get(&encrypted[0])
+            .and_then(|cipher| open(cipher, &encrypted[1..]));
+        let plaintext = versioned
+            .or_else(|| open(&keys.ciphers[&0], encrypted))
+            .ok_or(CryptoError::DecryptionFailed)?;
 
-        String::from_utf8(plaintext).map_err(|_| CryptoError::DecryptionFailed)
+        String::from_utf8(plaintext)
+            .map(Zeroizing::new)
+            .map_err(|e| {
+                e.into_bytes().zeroize();
+                CryptoError::DecryptionFailed
+            })
     }
 }
 
@@ -210,7 +618,7 @@ mod tests {
         assert!(encrypted.len() > NONCE_SIZE);
 
         let decrypted = crypto.decrypt(&encrypted).unwrap();
-        assert_eq!(decrypted, plaintext);
+        assert_eq!(decrypted.as_str(), plaintext);
     }
 
     #[test]
@@ -221,11 +629,73 @@ mod tests {
         let encrypted1 = crypto.encrypt(plaintext).unwrap();
         let encrypted2 = crypto.encrypt(plaintext).unwrap();
 
-        // Nonces (first 12 bytes) should be different
-        assert_ne!(&encrypted1[..NONCE_SIZE], &encrypted2[..NONCE_SIZE]);
+        // Nonces (12 bytes after the key version) should be different
+        assert_ne!(&encrypted1[1..=NONCE_SIZE], &encrypted2[1..=NONCE_SIZE]);
         // Both should decrypt to same plaintext
-        assert_eq!(crypto.decrypt(&encrypted1).unwrap(), plaintext);
-        assert_eq!(crypto.decrypt(&encrypted2).unwrap(), plaintext);
+        assert_eq!(crypto.decrypt(&encrypted1).unwrap().as_str(), plaintext);
+        assert_eq!(crypto.decrypt(&encrypted2).unwrap().as_str(), plaintext);
+    }
+
+    #[test]
+    fn test_status_reports_file_path_only_for_file_storage() {
+        let crypto = TokenCrypto::new().unwrap();
+        let status = crypto.status();
+
+        assert_eq!(status.secure, status.backend == KeyStorage::Keyring);
+        assert_eq!(
+            status.file_path.is_some(),
+            status.backend == KeyStorage::File
+        );
+    }
+
+    fn mock_entry() -> keyring::Entry {
+        keyring::Entry::new_with_credential(Box::new(keyring::mock::MockCredential::default()))
+    }
+
+    fn write_key_file(dir: &Path, key: &[u8; KEY_SIZE]) -> std::path::PathBuf {
+        let path = dir.join(".encryption_key");
+        std::fs::write(&path, hex::encode(key)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_migrate_file_key_to_mock_keyring() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = [7u8; KEY_SIZE];
+        let key_path = write_key_file(dir.path(), &key);
+        let crypto = TokenCrypto::with_key(&key, KeyStorage::File).unwrap();
+        let entry = mock_entry();
+
+        crypto.migrate_file_key(&entry, &key_path).unwrap();
+
+        assert!(!key_path.exists());
+        assert_eq!(entry.get_password().unwrap(), hex::encode(key));
+    }
+
+    #[test]
+    fn test_migrate_file_key_keeps_file_when_keyring_write_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = [7u8; KEY_SIZE];
+        let key_path = write_key_file(dir.path(), &key);
+        let crypto = TokenCrypto::with_key(&key, KeyStorage::File).unwrap();
+        let entry = mock_entry();
+        let mock: &keyring::mock::MockCredential = entry.get_credential().downcast_ref().unwrap();
+        mock.set_error(keyring::Error::PlatformFailure("keyring locked".into()));
+
+        assert!(crypto.migrate_file_key(&entry, &key_path).is_err());
+        assert!(key_path.exists());
+    }
+
+    #[test]
+    fn test_migrate_file_key_rejects_mismatched_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = write_key_file(dir.path(), &[1u8; KEY_SIZE]);
+        let crypto = TokenCrypto::with_key(&[2u8; KEY_SIZE], KeyStorage::File).unwrap();
+        let entry = mock_entry();
+
+        assert!(crypto.migrate_file_key(&entry, &key_path).is_err());
+        assert!(key_path.exists());
+        assert!(entry.get_password().is_err());
     }
 
     #[test]
@@ -238,4 +708,98 @@ mod tests {
         // Invalid ciphertext
         assert!(crypto.decrypt(&[0u8; 32]).is_err());
     }
+
+    /// Nonce + ciphertext sealed with `key`, as written before key versions
+    fn legacy_encrypt(key: &[u8; KEY_SIZE], plaintext: &str) -> Vec<u8> {
+        let cipher = Aes256Gcm::new_from_slice(key).unwrap();
+        let nonce_bytes = [3u8; NONCE_SIZE];
+        let mut sealed = nonce_bytes.to_vec();
+        sealed.extend(
+            cipher
+                .encrypt(Nonce::from_slice(&nonce_bytes), plaintext.as_bytes())
+                .unwrap(),
+        );
+        sealed
+    }
+
+    #[test]
+    fn test_rotate_key() {
+        let key = [7u8; KEY_SIZE];
+        let crypto = TokenCrypto::with_key(&key, KeyStorage::Keyring).unwrap();
+        let legacy = legacy_encrypt(&key, "old_token");
+        let v0 = crypto.encrypt("v0_token").unwrap();
+        assert_eq!(v0[0], 0);
+
+        let (key_entry, version_entry) = (mock_entry(), mock_entry());
+        let rotation = crypto
+            .rotate_key_with(1, &key_entry, &version_entry)
+            .unwrap();
+        assert_eq!((rotation.previous_version, rotation.new_version), (0, 1));
+        assert_eq!(version_entry.get_password().unwrap(), "1");
+
+        let reencrypted = rotation.reencrypt(&legacy).unwrap();
+        assert_eq!(reencrypted[0], 1);
+        assert_eq!(crypto.decrypt(&reencrypted).unwrap().as_str(), "old_token");
+        assert_eq!(crypto.decrypt(&legacy).unwrap().as_str(), "old_token");
+        assert_eq!(crypto.decrypt(&v0).unwrap().as_str(), "v0_token");
+        assert_eq!(crypto.encrypt("new").unwrap()[0], 1);
+
+        // A restarted instance loads the rotated key from the keyring
+        let restarted = TokenCrypto::with_key(&key, KeyStorage::Keyring).unwrap();
+        restarted.load_rotated_keys(|user| match user {
+            KEYRING_VERSION_USER => version_entry.get_password().ok(),
+            "encryption-key-v1" => key_entry.get_password().ok(),
+            _ => None,
+        });
+        assert_eq!(restarted.key_version(), 1);
+        assert_eq!(
+            restarted.decrypt(&reencrypted).unwrap().as_str(),
+            "old_token"
+        );
+    }
+
+    #[test]
+    fn test_rotate_key_keeps_old_key_when_keyring_write_fails() {
+        let crypto = TokenCrypto::with_key(&[7u8; KEY_SIZE], KeyStorage::Keyring).unwrap();
+        let (key_entry, version_entry) = (mock_entry(), mock_entry());
+        let mock: &keyring::mock::MockCredential =
+            version_entry.get_credential().downcast_ref().unwrap();
+        mock.set_error(keyring::Error::PlatformFailure("keyring locked".into()));
+
+        assert!(crypto
+            .rotate_key_with(1, &key_entry, &version_entry)
+            .is_err());
+        assert_eq!(crypto.key_version(), 0);
+        assert!(key_entry.get_password().is_err());
+        assert_eq!(crypto.encrypt("token").unwrap()[0], 0);
+
+        // File storage cannot rotate at all
+        let file = TokenCrypto::with_key(&[7u8; KEY_SIZE], KeyStorage::File).unwrap();
+        assert!(file.rotate_key().is_err());
+    }
+
+    #[test]
+    fn test_reencrypt_all_reports_failures_per_item() {
+        let key = [7u8; KEY_SIZE];
+        let crypto = TokenCrypto::with_key(&key, KeyStorage::Keyring).unwrap();
+        let blobs = vec![
+            legacy_encrypt(&key, "legacy"),
+            crypto.encrypt("v0").unwrap(),
+            legacy_encrypt(&[9u8; KEY_SIZE], "unknown key"),
+        ];
+        assert!(crypto.reencrypt_all(&blobs).is_err());
+
+        let (key_entry, version_entry) = (mock_entry(), mock_entry());
+        crypto
+            .rotate_key_with(1, &key_entry, &version_entry)
+            .unwrap();
+        let results = crypto.reencrypt_all(&blobs).unwrap();
+        assert_eq!(results.len(), 3);
+        for (result, plaintext) in results[..2].iter().zip(["legacy", "v0"]) {
+            let reencrypted = result.as_ref().unwrap();
+            assert_eq!(reencrypted[0], 1);
+            assert_eq!(crypto.decrypt(reencrypted).unwrap().as_str(), plaintext);
+        }
+        assert!(matches!(results[2], Err(CryptoError::DecryptionFailed)));
+    }
 }
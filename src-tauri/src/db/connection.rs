@@ -2,6 +2,7 @@ use r2d2::{Pool, PooledConnection};
 use r2d2_sqlite::SqliteConnectionManager;
 use std::path::Path;
 
+use super::encryption;
 use crate::error::AppError;
 
 pub type DbPool = Pool<SqliteConnectionManager>;
@@ -15,12 +16,21 @@ mod embedded {
 
 /// Create a new database connection pool
 pub fn create_pool(db_path: &Path) -> Result<DbPool, AppError> {
+    create_pool_with_key(db_path, None)
+}
+
+/// Create a connection pool, keying each connection for SQLCipher if a key is given
+pub fn create_pool_with_key(db_path: &Path, key_hex: Option<String>) -> Result<DbPool, AppError> {
     // Ensure parent directory exists
     if let Some(parent) = db_path.parent() {
         std::fs::create_dir_all(parent)?;
     }
 
-    let manager = SqliteConnectionManager::file(db_path).with_init(|conn| {
+    let manager = SqliteConnectionManager::file(db_path).with_init(move |conn| {
+        // The key must be applied before any other statement
+        if let Some(ref key) = key_hex {
+            conn.execute_batch(&encryption::key_pragma(key))?;
+        }
         // Enable foreign key constraints
         conn.execute_batch(
             "PRAGMA foreign_keys = ON;
@@ -50,6 +60,26 @@ pub fn run_migrations(pool: &DbPool) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Get the latest applied migration version, or None if migrations never ran
+pub fn current_schema_version(pool: &DbPool) -> Result<Option<i64>, AppError> {
+    let conn = pool.get().map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let version = conn
+        .query_row(
+            "SELECT MAX(version) FROM refinery_schema_history",
+            [],
+            |row| row.get(0),
+        )
+        .or_else(|e| match e {
+            rusqlite::Error::SqliteFailure(_, Some(ref msg)) if msg.contains("no such table") => {
+                Ok(None)
+            }
+            _ => Err(e),
+        })?;
+
+    Ok(version)
+}
+
 /// Get default database path
 pub fn default_db_path() -> Result<std::path::PathBuf, AppError> {
     let project_dirs = directories::ProjectDirs::from("com", "local-code-agent", "LocalCodeAgent")
@@ -61,6 +91,17 @@ pub fn default_db_path() -> Result<std::path::PathBuf, AppError> {
 
 /// Initialize database: create pool and run migrations
 pub fn init_database(db_path: Option<&Path>) -> Result<DbPool, AppError> {
+    init_database_with_key(db_path, None)
+}
+
+/// Initialize database, opening it with a SQLCipher key if given
+///
+/// A pending `migrate_to_encrypted_db` request is applied first. A database
+/// that is still plaintext is opened without the key.
+pub fn init_database_with_key(
+    db_path: Option<&Path>,
+    key_hex: Option<&str>,
+) -> Result<DbPool, AppError> {
     let path = match db_path {
         Some(p) => p.to_path_buf(),
         None => default_db_path()?,
@@ -68,8 +109,24 @@ pub fn init_database(db_path: Option<&Path>) -> Result<DbPool, AppError> {
 
     tracing::info!("Initializing database at {:?}", path);
 
-    let pool = create_pool(&path)?;
+    let key = match key_hex {
+        Some(key) => {
+            encryption::apply_pending_encryption(&path, key)?;
+            if encryption::is_plaintext_db(&path)? {
+                tracing::warn!(
+                    "Database is not encrypted; run migrate_to_encrypted_db to encrypt it"
+                );
+                None
+            } else {
+                Some(key.to_string())
+            }
+        }
+        None => None,
+    };
+
+    let pool = create_pool_with_key(&path, key)?;
     run_migrations(&pool)?;
+    super::status_check::verify_status_constraint(&pool);
 
     Ok(pool)
 }
@@ -77,6 +134,7 @@ pub fn init_database(db_path: Option<&Path>) -> Result<DbPool, AppError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::db::AgentJobStatus;
     use tempfile::tempdir;
 
     #[test]
@@ -119,6 +177,50 @@ mod tests {
         assert_eq!(count, 1);
     }
 
+    #[test]
+    fn test_current_schema_version() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+
+        let pool = create_pool(&db_path).unwrap();
+        assert_eq!(current_schema_version(&pool).unwrap(), None);
+
+        run_migrations(&pool).unwrap();
+        assert_eq!(current_schema_version(&pool).unwrap(), Some(20));
+    }
+
+    #[test]
+    fn test_agent_jobs_accepts_no_changes_status() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+
+        let pool = init_database(Some(&db_path)).unwrap();
+        let conn = pool.get().unwrap();
+
+        conn.execute(
+            "INSERT INTO repositories (mcp_server_name, platform, base_url, name, url, owner, repo_name)
+             VALUES ('github', 'GitHub', 'https://api.github.com', 'repo', 'https://github.com/o/r', 'o', 'r')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status)
+             VALUES (1, 1, 'job-1', 'NoChanges')",
+            [],
+        )
+        .unwrap();
+
+        let status: String = conn
+            .query_row("SELECT status FROM agent_jobs WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(
+            status.parse::<AgentJobStatus>(),
+            Ok(AgentJobStatus::NoChanges)
+        );
+    }
+
     #[test]
     fn test_foreign_keys_enabled() {
         let dir = tempdir().unwrap();
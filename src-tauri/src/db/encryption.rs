@@ -0,0 +1,190 @@
+use serde::Serialize;
+use std::ffi::OsString;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use super::connection::DbPool;
+use crate::error::AppError;
+
+/// Header of an unencrypted SQLite database file
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Database encryption status reported to the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct DbEncryptionStatus {
+    /// Whether the app was built with the `sqlcipher` feature
+    pub supported: bool,
+    pub encrypted: bool,
+    /// Encryption was requested and will be applied on next start
+    pub migration_pending: bool,
+    pub path: String,
+}
+
+/// Statement applying a raw SQLCipher key; must be the first statement on a connection
+pub(crate) fn key_pragma(key_hex: &str) -> String {
+    format!("PRAGMA key = \"x'{}'\";", key_hex)
+}
+
+/// Append a suffix to the database file name (e.g. `app.db` -> `app.db.encrypted`)
+fn sibling_path(db_path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(db_path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Marker file recording a pending `migrate_to_encrypted_db` request
+fn pending_marker(db_path: &Path) -> PathBuf {
+    sibling_path(db_path, ".encrypt-pending")
+}
+
+/// Check whether a database file exists and is stored as plaintext SQLite
+pub fn is_plaintext_db(path: &Path) -> Result<bool, AppError> {
+    let mut file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut header = [0u8; 16];
+    match file.read_exact(&mut header) {
+        Ok(()) => Ok(&header == SQLITE_HEADER),
+        // Empty or truncated file: SQLite treats it as a new database
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Path of the main database file behind a pool
+fn main_db_path(pool: &DbPool) -> Result<PathBuf, AppError> {
+    let conn = pool.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    let file: String = conn.query_row(
+        "SELECT file FROM pragma_database_list WHERE name = 'main'",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(PathBuf::from(file))
+}
+
+/// Report whether the database file is encrypted
+pub fn encryption_status(pool: &DbPool) -> Result<DbEncryptionStatus, AppError> {
+    let path = main_db_path(pool)?;
+    Ok(DbEncryptionStatus {
+        supported: cfg!(feature = "sqlcipher"),
+        encrypted: path.exists() && !is_plaintext_db(&path)?,
+        migration_pending: pending_marker(&path).exists(),
+        path: path.to_string_lossy().into_owned(),
+    })
+}
+
+/// Request encryption of a plaintext database on next start
+///
+/// Open pool connections keep using the current file, so the database is
+/// re-keyed by `apply_pending_encryption` before the pool is created.
+pub fn request_encryption(pool: &DbPool) -> Result<DbEncryptionStatus, AppError> {
+    let path = main_db_path(pool)?;
+    if is_plaintext_db(&path)? {
+        std::fs::write(pending_marker(&path), b"")?;
+        tracing::info!("Database encryption scheduled for next start: {:?}", path);
+    }
+    encryption_status(pool)
+}
+
+/// Encrypt a plaintext database if encryption was requested
+///
+/// Exports the database into an encrypted copy with `sqlcipher_export`, then
+/// replaces the original. Requires a SQLCipher build. Returns true if the
+/// database was encrypted.
+pub(crate) fn apply_pending_encryption(db_path: &Path, key_hex: &str) -> Result<bool, AppError> {
+    let marker = pending_marker(db_path);
+    if !marker.exists() {
+        return Ok(false);
+    }
+    if !is_plaintext_db(db_path)? {
+        std::fs::remove_file(&marker)?;
+        return Ok(false);
+    }
+
+    let encrypted_path = sibling_path(db_path, ".encrypted");
+    if encrypted_path.exists() {
+        std::fs::remove_file(&encrypted_path)?;
+    }
+
+    {
+        let conn = rusqlite::Connection::open(db_path)?;
+        conn.execute(
+            "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+            rusqlite::params![encrypted_path.to_string_lossy(), format!("x'{}'", key_hex)],
+        )?;
+        conn.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))?;
+        conn.execute("DETACH DATABASE encrypted", [])?;
+        // Closing the last connection checkpoints and removes the WAL file
+    }
+
+    for suffix in ["-wal", "-shm"] {
+        let leftover = sibling_path(db_path, suffix);
+        if leftover.exists() {
+            std::fs::remove_file(leftover)?;
+        }
+    }
+    std::fs::rename(&encrypted_path, db_path)?;
+    std::fs::remove_file(&marker)?;
+
+    tracing::info!("Encrypted database at {:?}", db_path);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_request_encryption_marks_plaintext_db() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let pool = crate::db::init_database(Some(&db_path)).unwrap();
+
+        let status = encryption_status(&pool).unwrap();
+        assert!(!status.encrypted);
+        assert!(!status.migration_pending);
+        assert!(is_plaintext_db(&db_path).unwrap());
+
+        let status = request_encryption(&pool).unwrap();
+        assert!(status.migration_pending);
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    #[test]
+    fn test_apply_pending_encryption_rekeys_database() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let key = "ab".repeat(32);
+
+        let pool = crate::db::init_database(Some(&db_path)).unwrap();
+        request_encryption(&pool).unwrap();
+        drop(pool);
+
+        let pool = crate::db::init_database_with_key(Some(&db_path), Some(&key)).unwrap();
+        let status = encryption_status(&pool).unwrap();
+        assert!(status.encrypted);
+        assert!(!status.migration_pending);
+
+        // Data survives the export
+        let count: i64 = pool
+            .get()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM app_settings", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_is_plaintext_db_missing_or_empty() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("missing.db");
+        assert!(!is_plaintext_db(&db_path).unwrap());
+
+        std::fs::write(&db_path, b"").unwrap();
+        assert!(!is_plaintext_db(&db_path).unwrap());
+    }
+}
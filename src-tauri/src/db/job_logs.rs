@@ -0,0 +1,106 @@
+// Per-job log chunks with gapless sequence numbers
+use rusqlite::{Connection, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+/// One chunk of a job's streamed output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobLogEntry {
+    pub job_id: i64,
+    /// 1-based position of the chunk within the job, without gaps
+    pub seq: i64,
+    pub chunk: String,
+    pub created_at: String,
+}
+
+/// Append a chunk to a job's log and return its sequence number
+///
+/// The next `seq` is read and written under an immediate (write-locked)
+/// transaction, so concurrent writers for the same job (e.g. the stream
+/// task and a reattach task) are serialized and never skip or reuse a number.
+pub fn append_job_log(conn: &mut Connection, job_id: i64, chunk: &str) -> Result<i64, AppError> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let seq = tx.query_row(
+        "INSERT INTO job_logs (job_id, seq, chunk)
+         SELECT ?1, COALESCE(MAX(seq), 0) + 1, ?2 FROM job_logs WHERE job_id = ?1
+         RETURNING seq",
+        rusqlite::params![job_id, chunk],
+        |row| row.get(0),
+    )?;
+    tx.commit()?;
+    Ok(seq)
+}
+
+/// Chunks of a job's log with `seq` greater than `after_seq`, in order
+pub fn job_logs_after(
+    conn: &Connection,
+    job_id: i64,
+    after_seq: i64,
+) -> Result<Vec<JobLogEntry>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT job_id, seq, chunk, created_at FROM job_logs
+         WHERE job_id = ?1 AND seq > ?2 ORDER BY seq",
+    )?;
+    let entries = stmt
+        .query_map(rusqlite::params![job_id, after_seq], |row| {
+            Ok(JobLogEntry {
+                job_id: row.get(0)?,
+                seq: row.get(1)?,
+                chunk: row.get(2)?,
+                created_at: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concurrent_appends_are_gapless_and_ordered() {
+        const WRITERS: i64 = 4;
+        const CHUNKS: i64 = 25;
+
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let job_id = {
+            let conn = pool.get().unwrap();
+            let repository_id = crate::db::test_support::insert_repository(&conn);
+            conn.execute(
+                "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status)
+                 VALUES (?1, 1, '1', 'RunningAgent')",
+                [repository_id],
+            )
+            .unwrap();
+            conn.last_insert_rowid()
+        };
+
+        let handles: Vec<_> = (0..WRITERS)
+            .map(|writer| {
+                let pool = pool.clone();
+                std::thread::spawn(move || {
+                    let mut conn = pool.get().unwrap();
+                    (0..CHUNKS)
+                        .map(|i| {
+                            append_job_log(&mut conn, job_id, &format!("{}:{}", writer, i)).unwrap()
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        for handle in handles {
+            let seqs = handle.join().unwrap();
+            // Each writer sees its own chunks in increasing order
+            assert!(seqs.windows(2).all(|w| w[0] < w[1]));
+        }
+
+        let conn = pool.get().unwrap();
+        let entries = job_logs_after(&conn, job_id, 0).unwrap();
+        let seqs: Vec<i64> = entries.iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, (1..=WRITERS * CHUNKS).collect::<Vec<_>>());
+        assert_eq!(job_logs_after(&conn, job_id, 98).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,91 @@
+// Status transitions of agent jobs, written by triggers on agent_jobs
+use rusqlite::types::Type;
+use rusqlite::Connection;
+use serde::Serialize;
+
+use super::AgentJobStatus;
+use crate::error::AppError;
+
+/// One status an agent job went through
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct JobStatusChange {
+    pub status: AgentJobStatus,
+    pub changed_at: String,
+    /// Time until the next status; None for the current one
+    pub seconds_in_status: Option<i64>,
+}
+
+/// Statuses of a job in the order it went through them
+///
+/// Jobs created before the history was recorded only have their status at
+/// that time.
+pub fn job_status_history(
+    conn: &Connection,
+    job_id: i64,
+) -> Result<Vec<JobStatusChange>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT status, changed_at,
+                CAST(ROUND((julianday(LEAD(changed_at) OVER (ORDER BY id))
+                            - julianday(changed_at)) * 86400) AS INTEGER)
+         FROM job_status_history WHERE job_id = ?1 ORDER BY id",
+    )?;
+    let changes = stmt
+        .query_map([job_id], |row| {
+            let status: String = row.get(0)?;
+            Ok(JobStatusChange {
+                status: status.parse().map_err(|e: String| {
+                    rusqlite::Error::FromSqlConversionFailure(0, Type::Text, e.into())
+                })?,
+                changed_at: row.get(1)?,
+                seconds_in_status: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_changes_are_recorded_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let conn = pool.get().unwrap();
+        let repository_id = crate::db::test_support::insert_repository(&conn);
+        conn.execute(
+            "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status)
+             VALUES (?1, 1, '1', 'Pending')",
+            [repository_id],
+        )
+        .unwrap();
+        let job_id = conn.last_insert_rowid();
+        for status in ["RunningAgent", "RunningAgent", "Failed"] {
+            conn.execute(
+                "UPDATE agent_jobs SET status = ?2, updated_at = datetime('now') WHERE id = ?1",
+                rusqlite::params![job_id, status],
+            )
+            .unwrap();
+        }
+        // Updates that leave the status alone are not transitions
+        conn.execute(
+            "UPDATE agent_jobs SET error_message = 'boom' WHERE id = ?1",
+            [job_id],
+        )
+        .unwrap();
+
+        let history = job_status_history(&conn, job_id).unwrap();
+        let statuses: Vec<AgentJobStatus> = history.iter().map(|c| c.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                AgentJobStatus::Pending,
+                AgentJobStatus::RunningAgent,
+                AgentJobStatus::Failed
+            ]
+        );
+        assert!(history[..2].iter().all(|c| c.seconds_in_status.is_some()));
+        assert_eq!(history[2].seconds_in_status, None);
+    }
+}
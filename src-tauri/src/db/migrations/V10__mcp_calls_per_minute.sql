@@ -0,0 +1,3 @@
+-- Per-server limit on MCP tool calls; 0 disables throttling
+
+ALTER TABLE app_settings ADD COLUMN mcp_calls_per_minute INTEGER NOT NULL DEFAULT 0 CHECK (mcp_calls_per_minute >= 0);
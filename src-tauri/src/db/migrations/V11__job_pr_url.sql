@@ -0,0 +1,3 @@
+-- Link to the pull request opened by an agent job
+
+ALTER TABLE agent_jobs ADD COLUMN pr_url TEXT;
@@ -0,0 +1,3 @@
+-- Upper bound on the prompt passed to an agent run (default + custom), in characters
+
+ALTER TABLE app_settings ADD COLUMN max_prompt_length INTEGER NOT NULL DEFAULT 20000 CHECK (max_prompt_length > 0);
@@ -0,0 +1,3 @@
+-- Running jobs updated within this many minutes are reattached at startup; older ones are marked Failed
+
+ALTER TABLE app_settings ADD COLUMN reattach_window_minutes INTEGER NOT NULL DEFAULT 60 CHECK (reattach_window_minutes > 0);
@@ -0,0 +1,3 @@
+-- PEM file of extra CA certificates trusted for an https backend (self-signed or private CA)
+
+ALTER TABLE app_settings ADD COLUMN grpc_ca_cert_path TEXT;
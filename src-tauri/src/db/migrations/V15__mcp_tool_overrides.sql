@@ -0,0 +1,9 @@
+-- Tool names overriding the built-in defaults for an MCP server (tools renamed across server versions)
+
+CREATE TABLE IF NOT EXISTS mcp_tool_overrides (
+  mcp_server_name TEXT NOT NULL,
+  operation TEXT NOT NULL,
+  tool_name TEXT NOT NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  PRIMARY KEY (mcp_server_name, operation)
+);
@@ -0,0 +1,3 @@
+-- Backend auth token set from the UI, encrypted with the token encryption key; JOBWORKERP_AUTH_TOKEN takes precedence
+
+ALTER TABLE app_settings ADD COLUMN grpc_auth_token_encrypted BLOB;
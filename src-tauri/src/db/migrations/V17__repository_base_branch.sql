@@ -0,0 +1,3 @@
+-- Per-repository base branch for agent runs (NULL = app_settings.default_base_branch)
+
+ALTER TABLE repositories ADD COLUMN base_branch TEXT;
@@ -0,0 +1,6 @@
+-- Result of the periodic MCP server token check: 'valid' or 'invalid' (NULL = not checked yet)
+ALTER TABLE repositories ADD COLUMN token_status TEXT;
+ALTER TABLE repositories ADD COLUMN token_checked_at TEXT;
+
+-- Minutes between background token checks; 0 disables them
+ALTER TABLE app_settings ADD COLUMN token_check_interval_minutes INTEGER NOT NULL DEFAULT 360;
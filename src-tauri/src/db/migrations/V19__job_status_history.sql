@@ -0,0 +1,26 @@
+-- Status changes of agent jobs, recorded by triggers so every writer is covered
+
+CREATE TABLE job_status_history (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  job_id INTEGER NOT NULL REFERENCES agent_jobs(id) ON DELETE CASCADE,
+  status TEXT NOT NULL,
+  changed_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_job_status_history_job ON job_status_history(job_id);
+
+CREATE TRIGGER agent_jobs_status_inserted AFTER INSERT ON agent_jobs
+BEGIN
+  INSERT INTO job_status_history (job_id, status, changed_at)
+  VALUES (NEW.id, NEW.status, NEW.created_at);
+END;
+
+CREATE TRIGGER agent_jobs_status_updated AFTER UPDATE OF status ON agent_jobs
+WHEN NEW.status IS NOT OLD.status
+BEGIN
+  INSERT INTO job_status_history (job_id, status) VALUES (NEW.id, NEW.status);
+END;
+
+-- Earlier transitions of existing jobs are unknown; keep their current status
+INSERT INTO job_status_history (job_id, status, changed_at)
+SELECT id, status, updated_at FROM agent_jobs;
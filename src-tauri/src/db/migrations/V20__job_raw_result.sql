@@ -0,0 +1,3 @@
+-- Raw FinalCollected output of agent jobs, kept so results can be parsed again
+
+ALTER TABLE agent_jobs ADD COLUMN raw_result BLOB;
@@ -0,0 +1,36 @@
+-- Add 'NoChanges' terminal status for agent runs that found nothing to change.
+-- SQLite cannot alter a CHECK constraint, so agent_jobs is rebuilt.
+
+CREATE TABLE agent_jobs_new (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  repository_id INTEGER NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+  issue_number INTEGER NOT NULL,
+  jobworkerp_job_id TEXT NOT NULL,
+  status TEXT NOT NULL CHECK (status IN (
+    'Pending', 'PreparingWorkspace', 'FetchingIssue',
+    'RunningAgent', 'CreatingPR', 'PrCreated',
+    'Merged', 'Completed', 'NoChanges', 'Failed', 'Cancelled'
+  )),
+  worktree_path TEXT,
+  branch_name TEXT,
+  pr_number INTEGER,
+  error_message TEXT,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT INTO agent_jobs_new (
+  id, repository_id, issue_number, jobworkerp_job_id, status,
+  worktree_path, branch_name, pr_number, error_message, created_at, updated_at
+)
+SELECT
+  id, repository_id, issue_number, jobworkerp_job_id, status,
+  worktree_path, branch_name, pr_number, error_message, created_at, updated_at
+FROM agent_jobs;
+
+DROP TABLE agent_jobs;
+ALTER TABLE agent_jobs_new RENAME TO agent_jobs;
+
+CREATE INDEX idx_agent_jobs_repository ON agent_jobs(repository_id);
+CREATE INDEX idx_agent_jobs_status ON agent_jobs(status);
+CREATE INDEX idx_agent_jobs_jobworkerp_id ON agent_jobs(jobworkerp_job_id);
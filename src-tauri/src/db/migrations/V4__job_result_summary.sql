@@ -0,0 +1,5 @@
+-- Optional completion details reported by the agent workflow
+
+ALTER TABLE agent_jobs ADD COLUMN commit_sha TEXT;
+ALTER TABLE agent_jobs ADD COLUMN files_changed INTEGER;
+ALTER TABLE agent_jobs ADD COLUMN summary TEXT;
@@ -0,0 +1,3 @@
+-- Webhook URL or command template run when an agent job finishes (NULL disables)
+
+ALTER TABLE app_settings ADD COLUMN post_job_hook TEXT;
@@ -0,0 +1,8 @@
+-- App-wide activity log (runners, repositories, jobs, settings)
+
+CREATE TABLE app_events (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  event_type TEXT NOT NULL,
+  message TEXT NOT NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,3 @@
+-- Standing per-repository instructions merged into every agent run (NULL = none)
+
+ALTER TABLE repositories ADD COLUMN default_prompt TEXT;
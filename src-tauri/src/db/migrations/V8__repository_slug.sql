@@ -0,0 +1,16 @@
+-- Stable per-repository directory name for agent worktrees, fixed at creation
+-- so later edits to owner/repo_name/local_path do not move worktrees
+
+ALTER TABLE repositories ADD COLUMN repo_slug TEXT;
+
+-- Backfill: lowercase owner__repo_name with path separators and spaces replaced
+UPDATE repositories SET repo_slug =
+  lower(replace(replace(replace(owner, '/', '-'), '\', '-'), ' ', '-'))
+  || '__' ||
+  lower(replace(replace(replace(repo_name, '/', '-'), '\', '-'), ' ', '-'));
+
+-- The same owner/repo may be registered under several MCP servers
+UPDATE repositories SET repo_slug = repo_slug || '-' || id
+WHERE id NOT IN (SELECT MIN(id) FROM repositories GROUP BY repo_slug);
+
+CREATE UNIQUE INDEX idx_repositories_repo_slug ON repositories(repo_slug);
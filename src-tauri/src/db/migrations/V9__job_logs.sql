@@ -0,0 +1,10 @@
+-- Streamed agent output, one row per chunk; seq orders chunks within a job
+
+CREATE TABLE job_logs (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  job_id INTEGER NOT NULL REFERENCES agent_jobs(id) ON DELETE CASCADE,
+  seq INTEGER NOT NULL,
+  chunk TEXT NOT NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  UNIQUE (job_id, seq)
+);
@@ -1,10 +1,24 @@
 // SQLite database connection and migrations
 pub mod connection;
+pub mod encryption;
+pub mod events;
+pub mod job_logs;
+pub mod job_status_history;
 pub mod models;
 mod queries;
+pub mod status_check;
+#[cfg(test)]
+pub(crate) mod test_support;
 
-pub use connection::{init_database, DbPool};
+pub use connection::{current_schema_version, init_database, init_database_with_key, DbPool};
+pub use encryption::DbEncryptionStatus;
+pub use events::{record_event, AppEvent, AppEventType};
+pub use job_logs::{append_job_log, job_logs_after, JobLogEntry};
+pub use job_status_history::{job_status_history, JobStatusChange};
 pub use models::{
-    AgentJob, AgentJobStatus, CreateRepository, Issue, Platform, PullRequest, Repository,
+    AgentJob, AgentJobStatus, CreateRepository, Issue, Label, PaginatedPullRequests,
+    PaginatedRemoteRepositories, Platform, PullRequest, RelatedPullRequests, RemoteRepository,
+    Repository, ReviewComment, TimelineEvent,
 };
 pub use queries::get_repository_by_id;
+pub use status_check::StatusConstraintReport;
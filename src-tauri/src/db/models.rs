@@ -20,6 +20,34 @@ pub enum Platform {
     Gitea,
 }
 
+impl Platform {
+    /// Every supported platform, in display order
+    pub const ALL: [Platform; 2] = [Platform::GitHub, Platform::Gitea];
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Platform::GitHub => "GitHub",
+            Platform::Gitea => "Gitea",
+        }
+    }
+
+    /// Public host used when none is configured (self-hosted platforms have none)
+    pub fn default_host(self) -> Option<&'static str> {
+        match self {
+            Platform::GitHub => Some("github.com"),
+            Platform::Gitea => None,
+        }
+    }
+
+    /// Environment variable the MCP server container reads its token from
+    pub fn token_env_key(self) -> &'static str {
+        match self {
+            Platform::GitHub => "GITHUB_PERSONAL_ACCESS_TOKEN",
+            Platform::Gitea => "GITEA_ACCESS_TOKEN",
+        }
+    }
+}
+
 impl std::fmt::Display for Platform {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -52,6 +80,10 @@ pub struct Repository {
     pub owner: String,
     pub repo_name: String,
     pub local_path: Option<String>,
+    /// Stable directory name for agent worktrees, fixed when the repository is added
+    pub repo_slug: String,
+    /// Result of the last background token check: "valid" or "invalid"
+    pub token_status: Option<String>,
     pub last_synced_at: Option<String>,
     pub created_at: String,
     pub updated_at: String,
@@ -79,10 +111,81 @@ pub enum AgentJobStatus {
     PrCreated,
     Merged,
     Completed,
+    /// The agent ran but found nothing to change
+    NoChanges,
     Failed,
     Cancelled,
 }
 
+impl AgentJobStatus {
+    /// Every status, matching the agent_jobs CHECK constraint
+    pub const ALL: [AgentJobStatus; 11] = [
+        AgentJobStatus::Pending,
+        AgentJobStatus::PreparingWorkspace,
+        AgentJobStatus::FetchingIssue,
+        AgentJobStatus::RunningAgent,
+        AgentJobStatus::CreatingPR,
+        AgentJobStatus::PrCreated,
+        AgentJobStatus::Merged,
+        AgentJobStatus::Completed,
+        AgentJobStatus::NoChanges,
+        AgentJobStatus::Failed,
+        AgentJobStatus::Cancelled,
+    ];
+
+    /// Statuses of a job whose agent may still be running
+    pub const ACTIVE: [AgentJobStatus; 5] = [
+        AgentJobStatus::Pending,
+        AgentJobStatus::PreparingWorkspace,
+        AgentJobStatus::FetchingIssue,
+        AgentJobStatus::RunningAgent,
+        AgentJobStatus::CreatingPR,
+    ];
+
+    /// Statuses of a job that finished with what it set out to do
+    pub const SUCCESS: [AgentJobStatus; 4] = [
+        AgentJobStatus::PrCreated,
+        AgentJobStatus::Merged,
+        AgentJobStatus::Completed,
+        AgentJobStatus::NoChanges,
+    ];
+
+    /// Statuses of a job that has finished, successfully or not
+    pub const TERMINAL: [AgentJobStatus; 6] = [
+        AgentJobStatus::PrCreated,
+        AgentJobStatus::Merged,
+        AgentJobStatus::Completed,
+        AgentJobStatus::NoChanges,
+        AgentJobStatus::Failed,
+        AgentJobStatus::Cancelled,
+    ];
+
+    /// Whether the job's agent may still be running
+    pub fn is_active(self) -> bool {
+        Self::ACTIVE.contains(&self)
+    }
+
+    /// `ACTIVE` as an SQL list, for `status IN {}`
+    pub fn active_sql_list() -> String {
+        Self::sql_list(&Self::ACTIVE)
+    }
+
+    /// `SUCCESS` as an SQL list, for `status IN {}`
+    pub fn success_sql_list() -> String {
+        Self::sql_list(&Self::SUCCESS)
+    }
+
+    /// `TERMINAL` as an SQL list, for `status IN {}`
+    pub fn terminal_sql_list() -> String {
+        Self::sql_list(&Self::TERMINAL)
+    }
+
+    fn sql_list(statuses: &[AgentJobStatus]) -> String {
+        let quoted: Vec<String> = statuses.iter().map(|s| format!("'{}'", s)).collect();
+        format!("({})", quoted.join(", "))
+    }
+}
+
 impl std::fmt::Display for AgentJobStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -94,6 +197,7 @@ impl std::fmt::Display for AgentJobStatus {
             AgentJobStatus::PrCreated => write!(f, "PrCreated"),
             AgentJobStatus::Merged => write!(f, "Merged"),
             AgentJobStatus::Completed => write!(f, "Completed"),
+            AgentJobStatus::NoChanges => write!(f, "NoChanges"),
             AgentJobStatus::Failed => write!(f, "Failed"),
             AgentJobStatus::Cancelled => write!(f, "Cancelled"),
         }
@@ -113,6 +217,7 @@ impl std::str::FromStr for AgentJobStatus {
             "PrCreated" => Ok(AgentJobStatus::PrCreated),
             "Merged" => Ok(AgentJobStatus::Merged),
             "Completed" => Ok(AgentJobStatus::Completed),
+            "NoChanges" => Ok(AgentJobStatus::NoChanges),
             "Failed" => Ok(AgentJobStatus::Failed),
             "Cancelled" => Ok(AgentJobStatus::Cancelled),
             _ => Err(format!("Unknown status: {}", s)),
@@ -130,7 +235,13 @@ pub struct AgentJob {
     pub worktree_path: Option<String>,
     pub branch_name: Option<String>,
     pub pr_number: Option<i32>,
+    /// Pull request link as reported when the PR was opened
+    pub pr_url: Option<String>,
     pub error_message: Option<String>,
+    /// Completion details from the workflow result, if reported
+    pub commit_sha: Option<String>,
+    pub files_changed: Option<i64>,
+    pub summary: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -156,6 +267,26 @@ pub struct Issue {
     pub updated_at: String,
 }
 
+/// Repository label from GitHub/Gitea (not persisted to DB)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Label {
+    pub name: String,
+    /// Hex color without `#`, lowercase (e.g. `d73a4a`)
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Entry in an issue's history from GitHub/Gitea (not persisted to DB)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    /// Event kind as reported by the platform (e.g. `labeled`, `assigned`, `label`)
+    pub event: String,
+    pub actor: Option<String>,
+    pub created_at: String,
+    /// Label, assignee, commit, referenced issue or comment text, depending on the event
+    pub detail: Option<String>,
+}
+
 /// Pull Request from GitHub/Gitea (not persisted to DB)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PullRequest {
@@ -170,3 +301,63 @@ pub struct PullRequest {
     pub created_at: String,
     pub updated_at: String,
 }
+
+/// Inline review comment on a pull request diff (not persisted to DB)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewComment {
+    pub id: i64,
+    pub body: String,
+    pub user: String,
+    /// File the comment is attached to
+    pub path: String,
+    /// Line in the diff, if the comment is still anchored to one
+    pub line: Option<i32>,
+    pub diff_hunk: Option<String>,
+    /// Comment this one replies to (GitHub only)
+    pub in_reply_to: Option<i64>,
+    /// Review the comment belongs to
+    pub review_id: Option<i64>,
+    pub html_url: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Pull requests related to an issue, from a bounded scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedPullRequests {
+    pub items: Vec<PullRequest>,
+    /// Number of pull requests examined
+    pub scanned: u32,
+    /// The scan stopped before examining every pull request
+    pub truncated: bool,
+}
+
+/// Repository visible to the authenticated platform user (not persisted to DB)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteRepository {
+    pub owner: String,
+    pub repo_name: String,
+    pub url: String,
+    pub description: Option<String>,
+    pub private: bool,
+}
+
+/// One page of remote repositories from GitHub/Gitea
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaginatedRemoteRepositories {
+    pub items: Vec<RemoteRepository>,
+    pub page: u32,
+    pub per_page: u32,
+    pub has_next_page: bool,
+}
+
+/// One page of pull requests from GitHub/Gitea
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaginatedPullRequests {
+    pub items: Vec<PullRequest>,
+    pub page: u32,
+    pub per_page: u32,
+    /// Total number of matching pull requests, if the platform reports it
+    pub total_count: Option<i64>,
+    pub has_next_page: bool,
+}
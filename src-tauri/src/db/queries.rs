@@ -8,7 +8,7 @@ pub fn get_repository_by_id(db: &DbPool, id: i64) -> Result<Repository, AppError
 
     let mut stmt = conn.prepare(
         "SELECT id, mcp_server_name, platform, base_url, name, url, owner, repo_name,
-                local_path, last_synced_at, created_at, updated_at
+                local_path, last_synced_at, created_at, updated_at, repo_slug, token_status
          FROM repositories WHERE id = ?1",
     )?;
 
@@ -25,6 +25,8 @@ pub fn get_repository_by_id(db: &DbPool, id: i64) -> Result<Repository, AppError
         Option<String>,
         String,
         String,
+        String,
+        Option<String>,
     ) = stmt
         .query_row([id], |row| {
             Ok((
@@ -40,6 +42,8 @@ pub fn get_repository_by_id(db: &DbPool, id: i64) -> Result<Repository, AppError
                 row.get(9)?,
                 row.get(10)?,
                 row.get(11)?,
+                row.get(12)?,
+                row.get(13)?,
             ))
         })
         .map_err(|e| match e {
@@ -64,6 +68,8 @@ pub fn get_repository_by_id(db: &DbPool, id: i64) -> Result<Repository, AppError
         owner: row_data.6,
         repo_name: row_data.7,
         local_path: row_data.8,
+        repo_slug: row_data.12,
+        token_status: row_data.13,
         last_synced_at: row_data.9,
         created_at: row_data.10,
         updated_at: row_data.11,
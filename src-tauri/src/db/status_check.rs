@@ -0,0 +1,288 @@
+// Consistency check between AgentJobStatus and the agent_jobs CHECK constraint
+use regex::Regex;
+use rusqlite::Connection;
+use serde::Serialize;
+
+use super::connection::DbPool;
+use super::models::AgentJobStatus;
+use crate::error::AppError;
+
+/// Difference between the Rust status enum and the database CHECK constraint
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct StatusConstraintReport {
+    /// Statuses the app can write that the database rejects
+    pub missing_in_db: Vec<String>,
+    /// Statuses the database accepts that the app cannot read
+    pub unknown_to_app: Vec<String>,
+}
+
+impl StatusConstraintReport {
+    pub fn is_consistent(&self) -> bool {
+        self.missing_in_db.is_empty() && self.unknown_to_app.is_empty()
+    }
+}
+
+fn status_list_regex() -> Regex {
+    Regex::new(r"(?is)CHECK\s*\(\s*status\s+IN\s*\(([^)]*)\)").expect("valid regex")
+}
+
+fn table_sql(conn: &Connection) -> Result<String, AppError> {
+    Ok(conn.query_row(
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'agent_jobs'",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+/// Compare `AgentJobStatus` variants with the statuses the CHECK constraint allows
+pub fn check_status_constraint(conn: &Connection) -> Result<StatusConstraintReport, AppError> {
+    let sql = table_sql(conn)?;
+    let list = status_list_regex()
+        .captures(&sql)
+        .map(|caps| caps[1].to_string())
+        .ok_or_else(|| {
+            AppError::Internal("agent_jobs has no status CHECK constraint".to_string())
+        })?;
+    let db_statuses: Vec<String> = Regex::new(r"'([^']*)'")
+        .expect("valid regex")
+        .captures_iter(&list)
+        .map(|caps| caps[1].to_string())
+        .collect();
+    let app_statuses: Vec<String> = AgentJobStatus::ALL.iter().map(|s| s.to_string()).collect();
+
+    Ok(StatusConstraintReport {
+        missing_in_db: app_statuses
+            .iter()
+            .filter(|s| !db_statuses.contains(s))
+            .cloned()
+            .collect(),
+        unknown_to_app: db_statuses
+            .iter()
+            .filter(|s| !app_statuses.contains(s))
+            .cloned()
+            .collect(),
+    })
+}
+
+/// Rebuild agent_jobs so its CHECK constraint allows exactly the `AgentJobStatus` variants
+///
+/// SQLite cannot alter a CHECK constraint, so the table is recreated from its
+/// current definition with a new status list, following SQLite's table
+/// rebuild procedure: foreign keys are off during the rebuild so dropping the
+/// old table does not cascade into job_logs and job_status_history, and its
+/// indexes and triggers are restored. Fails without changes if existing rows
+/// hold a status the app does not know.
+pub fn repair_status_constraint(conn: &mut Connection) -> Result<StatusConstraintReport, AppError> {
+    let report = check_status_constraint(conn)?;
+    if report.is_consistent() {
+        return Ok(report);
+    }
+
+    let allowed: Vec<String> = AgentJobStatus::ALL.iter().map(|s| s.to_string()).collect();
+    let placeholders = vec!["?"; allowed.len()].join(", ");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT DISTINCT status FROM agent_jobs WHERE status NOT IN ({})",
+        placeholders
+    ))?;
+    let stray: Vec<String> = stmt
+        .query_map(rusqlite::params_from_iter(&allowed), |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    drop(stmt);
+    if !stray.is_empty() {
+        return Err(AppError::Internal(format!(
+            "agent_jobs contains statuses unknown to this version: {}",
+            stray.join(", ")
+        )));
+    }
+
+    let status_list = allowed
+        .iter()
+        .map(|s| format!("'{}'", s))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let table_sql = table_sql(conn)?;
+    let new_table_sql = status_list_regex()
+        .replace(
+            &table_sql,
+            format!("CHECK (status IN ({})", status_list).as_str(),
+        )
+        .replacen("agent_jobs", "agent_jobs_new", 1);
+
+    // The pragma is a no-op inside a transaction, so toggle it around it
+    conn.execute_batch("PRAGMA foreign_keys = OFF")?;
+    let rebuilt = rebuild_agent_jobs(conn, &new_table_sql);
+    conn.execute_batch("PRAGMA foreign_keys = ON")?;
+    rebuilt?;
+
+    tracing::info!("Rebuilt agent_jobs status constraint: {}", status_list);
+    check_status_constraint(conn)
+}
+
+/// Replace agent_jobs with `new_table_sql`, keeping rows, indexes and triggers
+///
+/// Must run with foreign keys off; references are checked before commit.
+fn rebuild_agent_jobs(conn: &mut Connection, new_table_sql: &str) -> Result<(), AppError> {
+    let tx = conn.transaction()?;
+    let schema_sql: Vec<String> = {
+        let mut stmt = tx.prepare(
+            "SELECT sql FROM sqlite_master
+             WHERE type IN ('index', 'trigger') AND tbl_name = 'agent_jobs' AND sql IS NOT NULL",
+        )?;
+        let sql = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        sql
+    };
+    tx.execute_batch(&format!(
+        "{};
+         INSERT INTO agent_jobs_new SELECT * FROM agent_jobs;
+         DROP TABLE agent_jobs;
+         ALTER TABLE agent_jobs_new RENAME TO agent_jobs;",
+        new_table_sql
+    ))?;
+    for sql in &schema_sql {
+        tx.execute_batch(sql)?;
+    }
+    let violation = tx
+        .prepare("PRAGMA foreign_key_check")?
+        .query([])?
+        .next()?
+        .is_some();
+    if violation {
+        return Err(AppError::Internal(
+            "Rebuilding agent_jobs would break foreign key references".to_string(),
+        ));
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Startup self-check; repairs drift where possible and logs the outcome
+///
+/// Never fails startup: an unrepairable constraint is reported in the log and
+/// through the `check_status_constraint` command.
+pub fn verify_status_constraint(pool: &DbPool) {
+    let result = pool
+        .get()
+        .map_err(|e| AppError::Internal(e.to_string()))
+        .and_then(|mut conn| {
+            let report = check_status_constraint(&conn)?;
+            if report.is_consistent() {
+                return Ok(report);
+            }
+            tracing::warn!(
+                "agent_jobs status constraint out of date (missing: {:?}, unknown: {:?}); repairing",
+                report.missing_in_db,
+                report.unknown_to_app
+            );
+            repair_status_constraint(&mut conn)
+        });
+    if let Err(e) = result {
+        tracing::error!("agent_jobs status constraint check failed: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_constraint_matches_enum() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let conn = pool.get().unwrap();
+
+        assert!(check_status_constraint(&conn).unwrap().is_consistent());
+    }
+
+    #[test]
+    fn test_repair_status_constraint() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let conn = pool.get().unwrap();
+
+        // Simulate a schema that predates the NoChanges status
+        conn.execute_batch(
+            "PRAGMA writable_schema = ON;
+             UPDATE sqlite_master SET sql = replace(sql, '''NoChanges'', ', '')
+             WHERE type = 'table' AND name = 'agent_jobs';
+             PRAGMA writable_schema = OFF;",
+        )
+        .unwrap();
+        drop(conn);
+        let mut conn = pool.get().unwrap();
+        let repository_id = crate::db::test_support::insert_repository(&conn);
+        conn.execute(
+            "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status)
+             VALUES (?1, 7, 'j1', 'RunningAgent')",
+            [repository_id],
+        )
+        .unwrap();
+        let job_id = conn.last_insert_rowid();
+        crate::db::append_job_log(&mut conn, job_id, "first chunk").unwrap();
+        crate::db::append_job_log(&mut conn, job_id, "second chunk").unwrap();
+        assert_eq!(
+            check_status_constraint(&conn).unwrap().missing_in_db,
+            vec!["NoChanges".to_string()]
+        );
+
+        let report = repair_status_constraint(&mut conn).unwrap();
+        assert!(report.is_consistent());
+        let indexes: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND tbl_name = 'agent_jobs'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(indexes >= 3);
+
+        // Child rows and the status history triggers survive the rebuild
+        assert_eq!(
+            crate::db::job_logs_after(&conn, job_id, 0).unwrap().len(),
+            2
+        );
+        let foreign_keys: i64 = conn
+            .query_row("PRAGMA foreign_keys", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(foreign_keys, 1);
+        conn.execute(
+            "UPDATE agent_jobs SET status = 'NoChanges' WHERE id = ?1",
+            [job_id],
+        )
+        .unwrap();
+        let statuses: Vec<AgentJobStatus> = crate::db::job_status_history(&conn, job_id)
+            .unwrap()
+            .into_iter()
+            .map(|change| change.status)
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![AgentJobStatus::RunningAgent, AgentJobStatus::NoChanges]
+        );
+    }
+
+    #[test]
+    fn test_all_statuses_listed() {
+        use AgentJobStatus::*;
+
+        // A new variant fails to compile here until it is given its position in ALL
+        let position = |status: AgentJobStatus| match status {
+            Pending => 0,
+            PreparingWorkspace => 1,
+            FetchingIssue => 2,
+            RunningAgent => 3,
+            CreatingPR => 4,
+            PrCreated => 5,
+            Merged => 6,
+            Completed => 7,
+            NoChanges => 8,
+            Failed => 9,
+            Cancelled => 10,
+        };
+        for (index, status) in AgentJobStatus::ALL.into_iter().enumerate() {
+            assert_eq!(position(status), index, "{} is out of place in ALL", status);
+        }
+        assert_eq!(position(Cancelled) + 1, AgentJobStatus::ALL.len());
+    }
+}
@@ -0,0 +1,14 @@
+// Shared fixtures for database tests
+use rusqlite::Connection;
+
+/// Insert the `octo/app` GitHub repository and return its id
+pub fn insert_repository(conn: &Connection) -> i64 {
+    conn.execute(
+        "INSERT INTO repositories (mcp_server_name, platform, base_url, name, url, owner, repo_name)
+         VALUES ('github', 'GitHub', 'https://api.github.com', 'octo/app',
+                 'https://github.com/octo/app', 'octo', 'app')",
+        [],
+    )
+    .unwrap();
+    conn.last_insert_rowid()
+}
@@ -18,6 +18,10 @@ pub enum AppError {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
+    /// Invalid value for a specific input field
+    #[error("Invalid input: {message}")]
+    Validation { field: String, message: String },
+
     #[error("Not found: {0}")]
     NotFound(String),
 
@@ -26,11 +30,50 @@ pub enum AppError {
 
     #[error("Internal error: {0}")]
     Internal(String),
+
+    /// A backend job did not finish within its time limit
+    #[error("Job timed out after {0:?}")]
+    Timeout(std::time::Duration),
+
+    #[error("Rate limit exceeded: {message}{}", retry_hint(*.reset_at))]
+    RateLimited {
+        message: String,
+        /// Unix timestamp (seconds) at which the limit resets, if known
+        reset_at: Option<i64>,
+    },
+}
+
+impl AppError {
+    /// Validation error for a named input field
+    pub fn validation(field: impl Into<String>, message: impl Into<String>) -> Self {
+        AppError::Validation {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Human-readable "retry in N seconds" suffix for rate-limit errors
+fn retry_hint(reset_at: Option<i64>) -> String {
+    match reset_at {
+        Some(reset_at) => {
+            let remaining = reset_at - chrono::Utc::now().timestamp();
+            if remaining > 0 {
+                format!(" (retry in {} seconds)", remaining)
+            } else {
+                " (retry now)".to_string()
+            }
+        }
+        None => String::new(),
+    }
 }
 
 impl From<tonic::Status> for AppError {
     fn from(status: tonic::Status) -> Self {
         tracing::error!("gRPC error: {:?}", status);
+        if let Some(err) = crate::grpc::proxy::find_proxy_error(&status) {
+            return err.into();
+        }
         AppError::Grpc(status.message().to_string())
     }
 }
@@ -38,6 +81,9 @@ impl From<tonic::Status> for AppError {
 impl From<tonic::transport::Error> for AppError {
     fn from(err: tonic::transport::Error) -> Self {
         tracing::error!("gRPC transport error: {:?}", err);
+        if let Some(err) = crate::grpc::proxy::find_proxy_error(&err) {
+            return err.into();
+        }
         AppError::Grpc(err.to_string())
     }
 }
@@ -61,6 +107,15 @@ impl Serialize for AppError {
     where
         S: serde::Serializer,
     {
+        // Field-level errors are sent as `{ field, message }` so forms can highlight the input
+        if let AppError::Validation { field, message } = self {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("ValidationError", 2)?;
+            state.serialize_field("field", field)?;
+            state.serialize_field("message", message)?;
+            return state.end();
+        }
+
         // In debug mode, return detailed error messages for debugging
         #[cfg(debug_assertions)]
         let user_message = self.to_string();
@@ -73,9 +128,12 @@ impl Serialize for AppError {
             AppError::Crypto(_) => "Encryption error occurred".to_string(),
             AppError::Io(_) => "File operation failed".to_string(),
             AppError::InvalidInput(msg) => msg.clone(),
+            AppError::Validation { .. } => self.to_string(),
             AppError::NotFound(msg) => msg.clone(),
             AppError::Config(_) => "Configuration error".to_string(),
             AppError::Internal(_) => "Internal error occurred".to_string(),
+            AppError::Timeout(_) => self.to_string(),
+            AppError::RateLimited { .. } => self.to_string(),
         };
 
         serializer.serialize_str(&user_message)
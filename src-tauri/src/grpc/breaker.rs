@@ -0,0 +1,281 @@
+// Per-server circuit breaker for MCP tool calls
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::error::AppError;
+
+/// Consecutive failures that open a server's circuit
+const FAILURE_THRESHOLD: u32 = 5;
+/// Time an open circuit rejects calls before a probe is allowed
+const COOLDOWN: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Default)]
+struct Circuit {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    /// A half-open probe call is in flight
+    probing: bool,
+}
+
+/// Circuit state of one MCP server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+/// Circuit breaker state of one MCP server
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerCircuitStatus {
+    pub server_name: String,
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    /// Time until a probe call is allowed, while the circuit is open
+    pub retry_in_ms: Option<u64>,
+}
+
+/// Whether an error suggests the server itself is failing
+///
+/// Transport errors and timeouts count; rate limits and validation or lookup
+/// errors do not.
+fn is_server_failure(error: &AppError) -> bool {
+    matches!(error, AppError::Grpc(_) | AppError::Timeout(_))
+}
+
+impl Circuit {
+    fn state(&self, now: Instant) -> CircuitState {
+        match self.opened_at {
+            None => CircuitState::Closed,
+            Some(opened_at) if now.duration_since(opened_at) < COOLDOWN => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+}
+
+/// Circuit breakers keyed by MCP server name
+///
+/// A dead MCP server makes every call to it wait for a timeout. After
+/// `FAILURE_THRESHOLD` consecutive failures the server's circuit opens and
+/// calls fail immediately for `COOLDOWN`. Then one probe call is let through
+/// (half-open): success closes the circuit, failure opens it again.
+#[derive(Debug, Default)]
+pub struct McpCircuitBreaker {
+    circuits: Mutex<HashMap<String, Circuit>>,
+}
+
+impl McpCircuitBreaker {
+    fn lock_circuits(&self) -> std::sync::MutexGuard<'_, HashMap<String, Circuit>> {
+        self.circuits.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Allow or reject a call to `server_name`
+    ///
+    /// Once the cooldown has passed, only one caller gets through as the probe.
+    /// Returns whether the allowed call is that probe.
+    pub fn check(&self, server_name: &str, now: Instant) -> Result<bool, AppError> {
+        let mut circuits = self.lock_circuits();
+        let Some(circuit) = circuits.get_mut(server_name) else {
+            return Ok(false);
+        };
+        match circuit.state(now) {
+            CircuitState::Closed => Ok(false),
+            CircuitState::HalfOpen if !circuit.probing => {
+                circuit.probing = true;
+                tracing::info!(
+                    "Probing MCP server '{}' after circuit cooldown",
+                    server_name
+                );
+                Ok(true)
+            }
+            _ => Err(AppError::Grpc(format!(
+                "MCP server '{}' circuit open after {} consecutive failures",
+                server_name, circuit.consecutive_failures
+            ))),
+        }
+    }
+
+    /// Record the outcome of a call let through by [`McpCircuitBreaker::check`]
+    pub fn record<T>(&self, server_name: &str, result: &Result<T, AppError>, now: Instant) {
+        let mut circuits = self.lock_circuits();
+        match result {
+            Err(e) if is_server_failure(e) => {
+                let circuit = circuits.entry(server_name.to_string()).or_default();
+                circuit.consecutive_failures += 1;
+                if circuit.probing || circuit.consecutive_failures >= FAILURE_THRESHOLD {
+                    if circuit.opened_at.is_none() {
+                        tracing::warn!(
+                            "MCP server '{}' failed {} times in a row; circuit open for {:?}",
+                            server_name,
+                            circuit.consecutive_failures,
+                            COOLDOWN
+                        );
+                    }
+                    circuit.opened_at = Some(now);
+                    circuit.probing = false;
+                }
+            }
+            // Other errors neither count nor clear failures, unless they end a
+            // probe: the server answered, so the circuit closes
+            Err(_) if !circuits.get(server_name).is_some_and(|c| c.probing) => {}
+            _ => {
+                if circuits
+                    .remove(server_name)
+                    .is_some_and(|c| c.opened_at.is_some())
+                {
+                    tracing::info!("MCP server '{}' recovered; circuit closed", server_name);
+                }
+            }
+        }
+    }
+
+    /// Let the next caller probe, after a probe call ended without an outcome
+    fn cancel_probe(&self, server_name: &str) {
+        if let Some(circuit) = self.lock_circuits().get_mut(server_name) {
+            circuit.probing = false;
+        }
+    }
+
+    /// Close a server's circuit, e.g. after its runner was reconfigured
+    pub fn reset(&self, server_name: &str) {
+        self.lock_circuits().remove(server_name);
+    }
+
+    /// State of every server with recent failures
+    pub fn status(&self) -> Vec<ServerCircuitStatus> {
+        let now = Instant::now();
+        let mut servers: Vec<ServerCircuitStatus> = self
+            .lock_circuits()
+            .iter()
+            .map(|(name, circuit)| ServerCircuitStatus {
+                server_name: name.clone(),
+                state: circuit.state(now),
+                consecutive_failures: circuit.consecutive_failures,
+                retry_in_ms: circuit
+                    .opened_at
+                    .map(|opened_at| COOLDOWN.saturating_sub(now.duration_since(opened_at)))
+                    .filter(|remaining| !remaining.is_zero())
+                    .map(|remaining| remaining.as_millis() as u64),
+            })
+            .collect();
+        servers.sort_by(|a, b| a.server_name.cmp(&b.server_name));
+        servers
+    }
+}
+
+/// Cancels a probe whose call is dropped before its outcome is recorded
+///
+/// Without it, a probe future dropped mid-call (e.g. by a caller's timeout)
+/// would leave the circuit waiting for an outcome that never comes.
+pub struct ProbeGuard<'a> {
+    breaker: &'a McpCircuitBreaker,
+    server_name: &'a str,
+    armed: bool,
+}
+
+impl<'a> ProbeGuard<'a> {
+    /// Guard the call `check` let through; does nothing unless `probe`
+    pub fn new(breaker: &'a McpCircuitBreaker, server_name: &'a str, probe: bool) -> Self {
+        Self {
+            breaker,
+            server_name,
+            armed: probe,
+        }
+    }
+
+    /// The outcome was recorded; nothing to cancel
+    pub fn disarm(&mut self) {
+        self.armed = false;
+    }
+}
+
+impl Drop for ProbeGuard<'_> {
+    fn drop(&mut self) {
+        if self.armed {
+            self.breaker.cancel_probe(self.server_name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure() -> Result<(), AppError> {
+        Err(AppError::Grpc("transport error".to_string()))
+    }
+
+    #[test]
+    fn test_circuit_opens_and_half_opens() {
+        let breaker = McpCircuitBreaker::default();
+        let now = Instant::now();
+        for _ in 0..FAILURE_THRESHOLD - 1 {
+            breaker.record("github", &failure(), now);
+        }
+        assert!(breaker.check("github", now).is_ok());
+        // Errors that do not point at the server leave the count alone
+        breaker.record::<()>(
+            "github",
+            &Err(AppError::NotFound("Issue #7".to_string())),
+            now,
+        );
+        breaker.record("github", &failure(), now);
+        assert_eq!(breaker.status()[0].state, CircuitState::Open);
+        assert!(breaker.check("github", now).is_err());
+        assert!(breaker.check("gitea", now).is_ok());
+
+        // After the cooldown one probe goes through; a failed probe reopens
+        let later = now + COOLDOWN;
+        assert!(breaker.check("github", later).is_ok());
+        assert!(breaker.check("github", later).is_err());
+        breaker.record("github", &failure(), later);
+        assert!(breaker.check("github", later).is_err());
+
+        let recovered = later + COOLDOWN;
+        assert!(breaker.check("github", recovered).is_ok());
+        breaker.record("github", &Ok(()), recovered);
+        assert!(breaker.check("github", recovered).is_ok());
+        assert!(breaker.status().is_empty());
+
+        for _ in 0..FAILURE_THRESHOLD {
+            breaker.record("github", &failure(), now);
+        }
+        breaker.reset("github");
+        assert!(breaker.check("github", now).is_ok());
+    }
+
+    #[test]
+    fn test_dropped_probe_lets_next_caller_probe() {
+        let breaker = McpCircuitBreaker::default();
+        let now = Instant::now();
+        for _ in 0..FAILURE_THRESHOLD {
+            breaker.record("github", &failure(), now);
+        }
+        let later = now + COOLDOWN;
+        assert!(!breaker.check("gitea", later).unwrap());
+        let probe = breaker.check("github", later).unwrap();
+        assert!(probe);
+        drop(ProbeGuard::new(&breaker, "github", probe));
+        assert!(breaker.check("github", later).unwrap());
+
+        // A probe whose outcome was recorded is not cancelled again
+        let mut guard = ProbeGuard::new(&breaker, "github", true);
+        breaker.record("github", &failure(), later);
+        guard.disarm();
+        drop(guard);
+        assert!(breaker.check("github", later).is_err());
+    }
+
+    #[test]
+    fn test_timeouts_count_as_server_failures() {
+        let timeout = AppError::Timeout(Duration::from_secs(30));
+        assert!(is_server_failure(&timeout));
+        // Only the error kind matters, not its wording
+        let internal = AppError::Internal(timeout.to_string());
+        assert!(!is_server_failure(&internal));
+    }
+}
@@ -1,30 +1,74 @@
-use std::sync::Arc;
-use tokio::sync::OnceCell;
+use std::sync::{Arc, RwLock};
 use tonic::metadata::MetadataValue;
-use tonic::transport::{Channel, Endpoint};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint};
 
 use crate::error::AppError;
 
 // Generated proto modules
+use super::breaker::{McpCircuitBreaker, ProbeGuard, ServerCircuitStatus};
 use super::data;
+use super::proxy::ProxyConfig;
+use super::rate_limit;
+use super::result_limit::{self, OversizeResult};
+use super::schema::message_to_json_schema;
 use super::service::{
-    job_result_service_client::JobResultServiceClient, job_service_client::JobServiceClient,
-    runner_service_client::RunnerServiceClient, worker_service_client::WorkerServiceClient,
-    CreateRunnerRequest, FindRunnerListRequest, FindWorkerListRequest, JobRequest, ListenRequest,
+    job_request, job_result_service_client::JobResultServiceClient,
+    job_service_client::JobServiceClient, runner_service_client::RunnerServiceClient,
+    worker_service_client::WorkerServiceClient, CreateRunnerRequest, FindJobResultListRequest,
+    FindListRequest, FindRunnerListRequest, FindWorkerListRequest, JobRequest, ListenRequest,
     RunnerNameRequest, WorkerNameRequest,
 };
+use super::throttle::{McpThrottle, McpThrottleStatus};
+use super::tls::TrustedCa;
+use super::tool_overrides::{McpOperation, ToolOverrides};
 
 // jobworkerp-client for dynamic protobuf decoding
 use command_utils::protobuf::ProtobufDescriptor;
 use jobworkerp_client::proto::JobworkerpProto;
 
+/// Upper bound for the startup connection warm-up
+const WARM_UP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Response header carrying the ID of a job enqueued for streaming
+const JOB_ID_HEADER: &str = "x-job-id-bin";
+
+/// Connection settings for [`JobworkerpClient`]
+#[derive(Debug, Clone, Default)]
+pub struct ClientConfig {
+    /// Proxy for the gRPC connection; see [`super::proxy`] for supported schemes
+    pub proxy: Option<ProxyConfig>,
+    /// Extra CA certificates trusted for `https` backends
+    pub trusted_ca: Option<TrustedCa>,
+}
+
+impl ClientConfig {
+    /// Settings from the environment (`HTTPS_PROXY`, `ALL_PROXY`, `NO_PROXY`)
+    pub fn from_env() -> Result<Self, AppError> {
+        Ok(Self {
+            proxy: ProxyConfig::from_env()?,
+            trusted_ca: None,
+        })
+    }
+}
+
+/// Parse an auth token into the `jobworkerp-auth` header value
+fn parse_auth_token(token: &str) -> Result<MetadataValue<tonic::metadata::Ascii>, AppError> {
+    token
+        .parse()
+        .map_err(|e| AppError::Config(format!("Invalid auth token format: {}", e)))
+}
+
 /// gRPC client for jobworkerp-rs
 ///
 /// Uses lazy channel initialization to avoid requiring Tokio runtime at construction time.
+/// The backend URL can be replaced at runtime with [`JobworkerpClient::set_url`].
 pub struct JobworkerpClient {
-    endpoint: Endpoint,
-    channel: OnceCell<Channel>,
-    auth_metadata: Option<MetadataValue<tonic::metadata::Ascii>>,
+    config: RwLock<ClientConfig>,
+    connection: RwLock<Connection>,
+    auth_metadata: RwLock<Option<MetadataValue<tonic::metadata::Ascii>>>,
+    throttle: McpThrottle,
+    breaker: McpCircuitBreaker,
+    tool_overrides: ToolOverrides,
 }
 
 impl JobworkerpClient {
@@ -33,37 +77,227 @@ impl JobworkerpClient {
     /// The actual gRPC channel is created lazily on first use to avoid
     /// requiring a Tokio runtime at construction time.
     pub fn new(url: &str) -> Result<Self, AppError> {
-        let endpoint =
+        Self::with_config(url, ClientConfig::from_env()?)
+    }
+
+    /// Create a new client with explicit connection settings
+    pub fn with_config(url: &str, config: ClientConfig) -> Result<Self, AppError> {
+        let mut endpoint =
             Endpoint::from_shared(url.to_string()).map_err(|e| AppError::Config(e.to_string()))?;
+        if let Some(ca) = config
+            .trusted_ca
+            .as_ref()
+            .filter(|_| endpoint.uri().scheme_str() == Some("https"))
+        {
+            endpoint = endpoint
+                .tls_config(ClientTlsConfig::new().ca_certificate(Certificate::from_pem(&ca.pem)))
+                .map_err(|e| AppError::Config(format!("Invalid TLS configuration: {}", e)))?;
+        }
+        let proxy = config
+            .proxy
+            .clone()
+            .filter(|proxy| endpoint.uri().host().is_some_and(|h| proxy.applies_to(h)));
+        if let Some(proxy) = &proxy {
+            tracing::info!(
+                "Connecting to {} via proxy {}:{}",
+                url,
+                proxy.host,
+                proxy.port
+            );
+        }
 
         // Parse auth token at construction time to fail early on invalid tokens
         let auth_metadata = match std::env::var("JOBWORKERP_AUTH_TOKEN") {
-            Ok(token) => {
-                let value: MetadataValue<tonic::metadata::Ascii> = token
-                    .parse()
-                    .map_err(|e| AppError::Config(format!("Invalid auth token format: {}", e)))?;
-                Some(value)
-            }
+            Ok(token) => Some(parse_auth_token(&token)?),
             Err(_) => None,
         };
 
         Ok(Self {
-            endpoint,
-            channel: OnceCell::new(),
-            auth_metadata,
+            config: RwLock::new(config),
+            connection: RwLock::new(Connection {
+                endpoint,
+                proxy,
+                channel: None,
+            }),
+            auth_metadata: RwLock::new(auth_metadata),
+            throttle: McpThrottle::default(),
+            breaker: McpCircuitBreaker::default(),
+            tool_overrides: ToolOverrides::default(),
         })
     }
 
+    /// Send `token` instead of the `JOBWORKERP_AUTH_TOKEN` one
+    pub fn with_auth_token(self, token: &str) -> Result<Self, AppError> {
+        *self
+            .auth_metadata
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = Some(parse_auth_token(token)?);
+        Ok(self)
+    }
+
     /// Create a new client wrapped in Arc for shared ownership
     pub fn new_shared(url: &str) -> Result<Arc<Self>, AppError> {
         Ok(Arc::new(Self::new(url)?))
     }
 
+    /// Backend URL with any embedded credentials removed
+    pub fn url(&self) -> String {
+        let uri = self.read_connection().endpoint.uri().to_string();
+        match url::Url::parse(&uri) {
+            Ok(mut parsed) => {
+                let _ = parsed.set_username("");
+                let _ = parsed.set_password(None);
+                parsed.to_string()
+            }
+            Err(_) => uri,
+        }
+    }
+
+    /// Whether an auth token is sent with requests
+    pub fn has_auth_token(&self) -> bool {
+        self.read_auth_metadata().is_some()
+    }
+
+    /// Client for `url` and `config` sending the current auth token, for a test connection
+    fn candidate(&self, url: &str, config: ClientConfig) -> Result<Self, AppError> {
+        let candidate = Self::with_config(url, config)?;
+        *candidate
+            .auth_metadata
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = self.read_auth_metadata();
+        Ok(candidate)
+    }
+
+    /// Switch to a different backend URL
+    ///
+    /// The new URL is checked with a test connection first; on failure the
+    /// current endpoint stays in place and the connection error is returned.
+    pub async fn set_url(&self, url: &str) -> Result<(), AppError> {
+        let candidate = self.candidate(url, self.read_config().clone())?;
+        candidate.check_connection().await?;
+
+        let replacement = candidate
+            .connection
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner());
+        *self.connection.write().unwrap_or_else(|e| e.into_inner()) = replacement;
+        Ok(())
+    }
+
+    /// Trust extra CA certificates for the backend, or stop trusting them with None
+    ///
+    /// Like [`JobworkerpClient::set_url`], new certificates are checked with a
+    /// test connection first and only applied if it succeeds. Removing them
+    /// is applied directly.
+    pub async fn set_trusted_ca(&self, trusted_ca: Option<TrustedCa>) -> Result<(), AppError> {
+        let uri = self.read_connection().endpoint.uri().to_string();
+        let check = trusted_ca.is_some();
+        let config = ClientConfig {
+            trusted_ca,
+            ..self.read_config().clone()
+        };
+        let candidate = self.candidate(&uri, config)?;
+        if check {
+            candidate.check_connection().await?;
+        }
+
+        let Self {
+            config, connection, ..
+        } = candidate;
+        *self.connection.write().unwrap_or_else(|e| e.into_inner()) =
+            connection.into_inner().unwrap_or_else(|e| e.into_inner());
+        *self.config.write().unwrap_or_else(|e| e.into_inner()) =
+            config.into_inner().unwrap_or_else(|e| e.into_inner());
+        Ok(())
+    }
+
+    /// Replace the auth token sent to the backend
+    ///
+    /// A client using the new token is tested first; if the backend rejects
+    /// it, the current token stays in place and the connection error is
+    /// returned.
+    pub async fn set_auth_token(&self, token: &str) -> Result<(), AppError> {
+        let uri = self.read_connection().endpoint.uri().to_string();
+        let candidate = self
+            .candidate(&uri, self.read_config().clone())?
+            .with_auth_token(token)?;
+        candidate.check_connection().await?;
+
+        *self
+            .auth_metadata
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = candidate.read_auth_metadata();
+        Ok(())
+    }
+
+    /// Extra CA certificates currently trusted for the backend
+    pub fn trusted_ca(&self) -> Option<TrustedCa> {
+        self.read_config().trusted_ca.clone()
+    }
+
+    /// Limit MCP tool calls to `calls_per_minute` per server; 0 disables the limit
+    pub fn set_mcp_calls_per_minute(&self, calls_per_minute: u32) {
+        self.throttle.set_calls_per_minute(calls_per_minute);
+    }
+
+    /// Current MCP call throttle configuration and per-server state
+    pub fn mcp_throttle_status(&self) -> McpThrottleStatus {
+        self.throttle.status()
+    }
+
+    /// Circuit breaker state of MCP servers with recent failures
+    pub fn mcp_circuit_status(&self) -> Vec<ServerCircuitStatus> {
+        self.breaker.status()
+    }
+
+    /// Close an MCP server's circuit so calls to it are attempted again
+    pub fn reset_mcp_circuit(&self, server_name: &str) {
+        self.breaker.reset(server_name);
+    }
+
+    /// Per-server tool name overrides, see [`super::tool_overrides`]
+    pub fn tool_overrides(&self) -> &ToolOverrides {
+        &self.tool_overrides
+    }
+
+    /// Tool name to call for an operation on an MCP server
+    ///
+    /// Returns the override saved for the server, or `default`.
+    pub fn tool_for(&self, server_name: &str, operation: McpOperation, default: &str) -> String {
+        self.tool_overrides.resolve(server_name, operation, default)
+    }
+
+    fn read_config(&self) -> std::sync::RwLockReadGuard<'_, ClientConfig> {
+        self.config.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn read_auth_metadata(&self) -> Option<MetadataValue<tonic::metadata::Ascii>> {
+        self.auth_metadata
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn read_connection(&self) -> std::sync::RwLockReadGuard<'_, Connection> {
+        self.connection.read().unwrap_or_else(|e| e.into_inner())
+    }
+
     /// Get or create the gRPC channel lazily
     async fn get_channel(&self) -> Channel {
-        self.channel
-            .get_or_init(|| async { self.endpoint.connect_lazy() })
-            .await
+        if let Some(channel) = &self.read_connection().channel {
+            return channel.clone();
+        }
+        let mut connection = self.connection.write().unwrap_or_else(|e| e.into_inner());
+        let Connection {
+            endpoint,
+            proxy,
+            channel,
+        } = &mut *connection;
+        channel
+            .get_or_insert_with(|| match proxy {
+                Some(proxy) => proxy.connect_lazy(endpoint),
+                None => endpoint.connect_lazy(),
+            })
             .clone()
     }
 
@@ -89,16 +323,36 @@ impl JobworkerpClient {
 
     /// Add auth header to request if token is configured
     fn add_auth_header<T>(&self, mut request: tonic::Request<T>) -> tonic::Request<T> {
-        if let Some(value) = &self.auth_metadata {
-            request
-                .metadata_mut()
-                .insert("jobworkerp-auth", value.clone());
+        if let Some(value) = self.read_auth_metadata() {
+            request.metadata_mut().insert("jobworkerp-auth", value);
         }
         request
     }
 
+    /// Establish the gRPC connection ahead of the first real request
+    ///
+    /// The channel connects lazily, so without this the first user action pays
+    /// the connection cost. Failures are only logged; the channel reconnects on
+    /// next use.
+    pub async fn warm_up(&self) {
+        let started = std::time::Instant::now();
+        match tokio::time::timeout(WARM_UP_TIMEOUT, self.check_connection()).await {
+            Ok(Ok(_)) => tracing::info!("gRPC channel ready in {:?}", started.elapsed()),
+            Ok(Err(e)) => tracing::warn!("gRPC warm-up failed, backend may be down: {}", e),
+            Err(_) => tracing::warn!("gRPC warm-up timed out after {:?}", WARM_UP_TIMEOUT),
+        }
+    }
+
     /// Check connection to jobworkerp-rs
     pub async fn check_connection(&self) -> Result<bool, AppError> {
+        self.probe().await?;
+        Ok(true)
+    }
+
+    /// Make a minimal authenticated request, keeping the raw gRPC status
+    ///
+    /// Lets callers tell an unreachable backend from a rejected auth token.
+    pub async fn probe(&self) -> Result<(), tonic::Status> {
         let mut client = self.worker_client().await;
         let request = self.add_auth_header(tonic::Request::new(FindWorkerListRequest {
             limit: Some(1),
@@ -106,7 +360,7 @@ impl JobworkerpClient {
         }));
 
         client.find_list(request).await?;
-        Ok(true)
+        Ok(())
     }
 
     /// Enqueue a job and return job ID
@@ -137,9 +391,8 @@ impl JobworkerpClient {
 
     /// Enqueue a job and stream results
     ///
-    /// Returns only the stream. If you need the job_id, use `enqueue_job()` followed
-    /// by `listen_stream()` instead. The job_id can be extracted from the first
-    /// `ResultOutputItem` in the stream if needed by the caller.
+    /// Returns only the stream. If you need the job_id, use
+    /// `enqueue_for_stream_with_id()` instead.
     pub async fn enqueue_for_stream(
         &self,
         worker_name: &str,
@@ -160,7 +413,90 @@ impl JobworkerpClient {
         Ok(response.into_inner())
     }
 
+    /// Enqueue a job and stream results, returning the job ID with the stream
+    ///
+    /// The backend reports the ID of a streamed job in the `x-job-id-bin`
+    /// response header, so results are read from the enqueue stream itself and
+    /// none can be missed. Fails if the backend does not report the ID.
+    pub async fn enqueue_for_stream_with_id(
+        &self,
+        worker_name: &str,
+        args: &serde_json::Value,
+    ) -> Result<(String, tonic::Streaming<data::ResultOutputItem>), AppError> {
+        let mut client = self.job_client().await;
+
+        let request = JobRequest {
+            worker: Some(super::service::job_request::Worker::WorkerName(
+                worker_name.to_string(),
+            )),
+            args: serde_json::to_vec(args)?,
+            ..Default::default()
+        };
+
+        let req = self.add_auth_header(tonic::Request::new(request));
+        let response = client.enqueue_for_stream(req).await?;
+        let job_id = job_id_from_metadata(response.metadata())
+            .ok_or_else(|| AppError::Grpc("No job ID returned for the streamed job".into()))?;
+        Ok((job_id.to_string(), response.into_inner()))
+    }
+
+    /// Enqueue a job and wait for its complete result
+    ///
+    /// Drains the result stream, preferring the `FinalCollected` payload over
+    /// concatenated `Data` chunks, and returns the raw result bytes. With a
+    /// `timeout`, the whole call (enqueue and stream) is bounded; on expiry
+    /// the stream is dropped, which stops listening for the job.
+    pub async fn enqueue_and_collect(
+        &self,
+        worker: job_request::Worker,
+        args: &serde_json::Value,
+        using: Option<&str>,
+        timeout: Option<std::time::Duration>,
+    ) -> Result<Vec<u8>, AppError> {
+        let collect = async {
+            let mut client = self.job_client().await;
+            let request = JobRequest {
+                worker: Some(worker),
+                args: serde_json::to_vec(args)?,
+                using: using.map(String::from),
+                ..Default::default()
+            };
+
+            let req = self.add_auth_header(tonic::Request::new(request));
+            let mut stream = client
+                .enqueue_for_stream(req)
+                .await
+                .map_err(rate_limit_or_status)?
+                .into_inner();
+
+            let mut result_bytes = Vec::new();
+            while let Some(item) = stream.message().await.map_err(rate_limit_or_status)? {
+                match item.item {
+                    Some(data::result_output_item::Item::Data(data)) => {
+                        result_bytes.extend(data);
+                    }
+                    Some(data::result_output_item::Item::FinalCollected(data)) => {
+                        // Prefer final collected result if available
+                        result_bytes = data;
+                    }
+                    Some(data::result_output_item::Item::End(_)) => break,
+                    None => {}
+                }
+            }
+            Ok(result_bytes)
+        };
+
+        match timeout {
+            Some(limit) => tokio::time::timeout(limit, collect)
+                .await
+                .map_err(|_| AppError::Timeout(limit))?,
+            None => collect.await,
+        }
+    }
+
     /// Listen to job result stream
+    ///
+    /// Returns `AppError::NotFound` if the backend no longer has the job.
     pub async fn listen_stream(
         &self,
         job_id: &str,
@@ -177,11 +513,19 @@ impl JobworkerpClient {
         };
 
         let req = self.add_auth_header(tonic::Request::new(request));
-        let response = client.listen_stream(req).await?;
+        let response = client.listen_stream(req).await.map_err(|status| {
+            if status.code() == tonic::Code::NotFound {
+                AppError::NotFound(format!("Job {} not found on the backend", job_id))
+            } else {
+                status.into()
+            }
+        })?;
         Ok(response.into_inner())
     }
 
     /// Delete/cancel a job
+    ///
+    /// Returns `AppError::NotFound` if the backend no longer has the job.
     pub async fn delete_job(&self, job_id: &str) -> Result<(), AppError> {
         let mut client = self.job_client().await;
 
@@ -192,10 +536,72 @@ impl JobworkerpClient {
         };
 
         let req = self.add_auth_header(tonic::Request::new(request));
-        client.delete(req).await?;
+        client.delete(req).await.map_err(|status| {
+            if status.code() == tonic::Code::NotFound {
+                AppError::NotFound(format!("Job {} not found on the backend", job_id))
+            } else {
+                status.into()
+            }
+        })?;
         Ok(())
     }
 
+    /// List jobs queued or running on the backend
+    ///
+    /// Returns `InvalidInput` if the backend does not implement job listing.
+    pub async fn list_jobs(&self, limit: Option<i32>) -> Result<Vec<data::Job>, AppError> {
+        let mut client = self.job_client().await;
+
+        let request = FindListRequest {
+            limit,
+            ..Default::default()
+        };
+
+        let req = self.add_auth_header(tonic::Request::new(request));
+        let mut stream = client
+            .find_list(req)
+            .await
+            .map_err(job_listing_error)?
+            .into_inner();
+
+        let mut jobs = Vec::new();
+        while let Some(job) = stream.message().await.map_err(job_listing_error)? {
+            jobs.push(job);
+        }
+        Ok(jobs)
+    }
+
+    /// List results stored for a worker
+    ///
+    /// Only results the worker is configured to store (`store_success` /
+    /// `store_failure`) are returned.
+    pub async fn list_results_by_worker(
+        &self,
+        worker_id: i64,
+        limit: Option<i32>,
+    ) -> Result<Vec<data::JobResult>, AppError> {
+        let mut client = self.result_client().await;
+
+        let request = FindJobResultListRequest {
+            worker_ids: vec![worker_id],
+            limit,
+            ..Default::default()
+        };
+
+        let req = self.add_auth_header(tonic::Request::new(request));
+        let mut stream = client
+            .find_list_by(req)
+            .await
+            .map_err(job_listing_error)?
+            .into_inner();
+
+        let mut results = Vec::new();
+        while let Some(result) = stream.message().await.map_err(job_listing_error)? {
+            results.push(result);
+        }
+        Ok(results)
+    }
+
     /// Find a worker by name
     pub async fn find_worker_by_name(&self, name: &str) -> Result<Option<data::Worker>, AppError> {
         let mut client = self.worker_client().await;
@@ -233,32 +639,155 @@ impl JobworkerpClient {
         tool_name: &str,
         args: &serde_json::Value,
     ) -> Result<serde_json::Value, AppError> {
+        self.call_mcp_tool_with_options(server_name, tool_name, args, &McpCallOptions::default())
+            .await
+    }
+
+    /// Call an MCP server tool with per-call options
+    ///
+    /// Rate-limit errors reported by the upstream platform are returned as
+    /// `AppError::RateLimited`. With `retry_on_rate_limit`, the call waits for
+    /// the reset (bounded by `MAX_RATE_LIMIT_WAIT`) and retries once.
+    pub async fn call_mcp_tool_with_options(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        args: &serde_json::Value,
+        options: &McpCallOptions,
+    ) -> Result<serde_json::Value, AppError> {
+        match self
+            .call_mcp_tool_once(server_name, tool_name, args, options)
+            .await
+        {
+            Err(AppError::RateLimited { message, reset_at }) if options.retry_on_rate_limit => {
+                let Some(delay) = rate_limit::retry_delay(reset_at) else {
+                    return Err(AppError::RateLimited { message, reset_at });
+                };
+                tracing::warn!(
+                    "Rate limited calling '{}' on '{}', retrying in {:?}",
+                    tool_name,
+                    server_name,
+                    delay
+                );
+                tokio::time::sleep(delay).await;
+                self.call_mcp_tool_once(server_name, tool_name, args, options)
+                    .await
+            }
+            result => result,
+        }
+    }
+
+    /// Single MCP tool call attempt (see `call_mcp_tool`)
+    async fn call_mcp_tool_once(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        args: &serde_json::Value,
+        options: &McpCallOptions,
+    ) -> Result<serde_json::Value, AppError> {
+        let output = self
+            .call_mcp_tool_decoded(server_name, tool_name, args, options)
+            .await?;
+
+        if let Some(info) = rate_limit::rate_limit_from_result(&output.result) {
+            return Err(info.into());
+        }
+
+        match options.max_result_bytes {
+            Some(max_bytes) => {
+                result_limit::enforce_limit(output.result, max_bytes, options.oversize)
+            }
+            None => Ok(output.result),
+        }
+    }
+
+    /// Call an MCP server tool and return the decoded result with decoding details
+    ///
+    /// Unlike `call_mcp_tool`, the decoded result is returned as-is, without
+    /// interpreting error payloads such as rate limits.
+    pub async fn call_mcp_tool_decoded(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        args: &serde_json::Value,
+        options: &McpCallOptions,
+    ) -> Result<McpToolOutput, AppError> {
         tracing::debug!(
             "call_mcp_tool: server='{}', tool='{}'",
             server_name,
             tool_name
         );
 
+        self.guard_mcp_call(
+            server_name,
+            self.run_mcp_tool(server_name, tool_name, args, options),
+        )
+        .await
+    }
+
+    /// Call an MCP server tool and return the undecoded result bytes
+    ///
+    /// For callers that decode results themselves, e.g. when the runner's
+    /// result_proto schema is broken. `max_result_bytes` is enforced on the
+    /// raw bytes; the other decoding options do not apply.
+    pub async fn call_mcp_tool_raw_bytes(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        args: &serde_json::Value,
+        options: &McpCallOptions,
+    ) -> Result<Vec<u8>, AppError> {
+        let (_, result_bytes) = self
+            .guard_mcp_call(
+                server_name,
+                self.run_mcp_job(server_name, tool_name, args, options),
+            )
+            .await?;
+        match options.max_result_bytes {
+            Some(max_bytes) if result_bytes.len() > max_bytes => {
+                Err(AppError::InvalidInput(format!(
+                    "Result of {} bytes exceeds the {} byte limit",
+                    result_bytes.len(),
+                    max_bytes
+                )))
+            }
+            _ => Ok(result_bytes),
+        }
+    }
+
+    /// Run an MCP call through the server's circuit breaker and throttle
+    async fn guard_mcp_call<T>(
+        &self,
+        server_name: &str,
+        call: impl std::future::Future<Output = Result<T, AppError>>,
+    ) -> Result<T, AppError> {
+        let probe = self.breaker.check(server_name, std::time::Instant::now())?;
+        // Ends the probe if this future is dropped before the outcome is known
+        let mut probe_guard = ProbeGuard::new(&self.breaker, server_name, probe);
+        self.throttle.acquire(server_name).await;
+        let output = call.await;
+        self.breaker
+            .record(server_name, &output, std::time::Instant::now());
+        probe_guard.disarm();
+        output
+    }
+
+    /// Run an MCP tool job and return the runner with the raw result bytes
+    async fn run_mcp_job(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        args: &serde_json::Value,
+        options: &McpCallOptions,
+    ) -> Result<(data::RunnerData, Vec<u8>), AppError> {
         // Get Runner info for result_proto schema
-        let runner = self
+        let runner_data = self
             .find_runner_by_exact_name(server_name)
             .await?
-            .ok_or_else(|| {
-                AppError::NotFound(format!("Runner '{}' not found", server_name))
-            })?;
-
-        let runner_data = runner
+            .ok_or_else(|| AppError::NotFound(format!("Runner '{}' not found", server_name)))?
             .data
-            .as_ref()
             .ok_or_else(|| AppError::Internal("Runner has no data".into()))?;
 
-        // Get result_proto descriptor for this tool
-        let result_descriptor = JobworkerpProto::parse_result_schema_descriptor(
-            runner_data,
-            Some(tool_name),
-        )
-        .map_err(|e| AppError::Internal(format!("Failed to parse result schema: {}", e)))?;
-
         // Ensure worker exists (auto-create if needed)
         let worker = match self.ensure_mcp_worker(server_name).await {
             Ok(w) => {
@@ -285,41 +814,60 @@ impl JobworkerpClient {
                 .unwrap_or(server_name)
         );
 
-        let mut client = self.job_client().await;
-
-        let request = JobRequest {
-            worker: Some(super::service::job_request::Worker::WorkerId(worker_id)),
-            args: serde_json::to_vec(args)?,
-            using: Some(tool_name.to_string()),
-            ..Default::default()
-        };
+        let result_bytes = self
+            .enqueue_and_collect(
+                job_request::Worker::WorkerId(worker_id),
+                args,
+                Some(tool_name),
+                options.timeout,
+            )
+            .await?;
+        Ok((runner_data, result_bytes))
+    }
 
-        let req = self.add_auth_header(tonic::Request::new(request));
-        let response = client.enqueue_for_stream(req).await?;
-        let mut stream = response.into_inner();
-
-        // Collect stream data
-        let mut result_bytes = Vec::new();
-        while let Some(item) = stream.message().await? {
-            match item.item {
-                Some(data::result_output_item::Item::Data(data)) => {
-                    result_bytes.extend(data);
-                }
-                Some(data::result_output_item::Item::FinalCollected(data)) => {
-                    // Prefer final collected result if available
-                    result_bytes = data;
-                }
-                Some(data::result_output_item::Item::End(_)) => {
-                    // Stream ended
-                    break;
+    /// Run an MCP tool job and decode its result (see `call_mcp_tool_decoded`)
+    ///
+    /// A result_proto schema that fails to parse is logged and the result is
+    /// parsed as JSON instead (`McpDecodePath::SchemaFallback`).
+    async fn run_mcp_tool(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        args: &serde_json::Value,
+        options: &McpCallOptions,
+    ) -> Result<McpToolOutput, AppError> {
+        let (runner_data, result_bytes) = self
+            .run_mcp_job(server_name, tool_name, args, options)
+            .await?;
+
+        // Get result_proto descriptor for this tool (skipped when JSON is forced)
+        let mut schema_error = None;
+        let result_descriptor = if options.force_json {
+            None
+        } else {
+            match JobworkerpProto::parse_result_schema_descriptor(&runner_data, Some(tool_name)) {
+                Ok(descriptor) => descriptor,
+                Err(e) => {
+                    tracing::warn!(
+                        server = server_name,
+                        tool = tool_name,
+                        error = %e,
+                        "Failed to parse result schema; decoding result as JSON"
+                    );
+                    schema_error = Some(e.to_string());
+                    None
                 }
-                None => {}
             }
-        }
+        };
 
         // Decode result using result_proto schema
+        let byte_length = result_bytes.len();
         if result_bytes.is_empty() {
-            return Ok(serde_json::json!(null));
+            return Ok(McpToolOutput {
+                result: serde_json::json!(null),
+                decode_path: McpDecodePath::Empty,
+                byte_length,
+            });
         }
 
         match result_descriptor {
@@ -338,8 +886,8 @@ impl JobworkerpClient {
                     )?;
 
                 // Convert to JSON
-                let json_result =
-                    ProtobufDescriptor::message_to_json_value(&dynamic_message).map_err(|e| {
+                let json_result = ProtobufDescriptor::message_to_json_value(&dynamic_message)
+                    .map_err(|e| {
                         tracing::error!("Failed to convert protobuf to JSON: {}", e);
                         AppError::Internal(format!("Failed to convert to JSON: {}", e))
                     })?;
@@ -349,7 +897,11 @@ impl JobworkerpClient {
                     serde_json::to_string(&json_result).unwrap_or_else(|_| "?".to_string())
                 );
 
-                Ok(json_result)
+                Ok(McpToolOutput {
+                    result: json_result,
+                    decode_path: McpDecodePath::Protobuf,
+                    byte_length,
+                })
             }
             None => {
                 // No result_proto schema, try JSON fallback
@@ -357,20 +909,140 @@ impl JobworkerpClient {
                     "No result_proto for tool '{}', attempting JSON parse",
                     tool_name
                 );
-                serde_json::from_slice(&result_bytes).map_err(|e| {
-                    let raw_content = String::from_utf8_lossy(&result_bytes);
-                    tracing::error!(
-                        "Failed to parse result as JSON: {}. Raw content: {}",
-                        e,
-                        raw_content
-                    );
-                    AppError::Internal(format!("Failed to parse as JSON: {}", e))
-                })
+                match serde_json::from_slice::<serde_json::Value>(&result_bytes) {
+                    Ok(json_result) => Ok(McpToolOutput {
+                        result: json_result,
+                        decode_path: if schema_error.is_some() {
+                            McpDecodePath::SchemaFallback
+                        } else {
+                            McpDecodePath::Json
+                        },
+                        byte_length,
+                    }),
+                    // Forced JSON is a debugging aid: show the raw text instead of failing
+                    Err(_) if options.force_json => Ok(McpToolOutput {
+                        result: serde_json::Value::String(
+                            String::from_utf8_lossy(&result_bytes).into_owned(),
+                        ),
+                        decode_path: McpDecodePath::Text,
+                        byte_length,
+                    }),
+                    Err(e) => {
+                        let raw_content = String::from_utf8_lossy(&result_bytes);
+                        tracing::error!(
+                            "Failed to parse result as JSON: {}. Raw content: {}",
+                            e,
+                            raw_content
+                        );
+                        Err(AppError::Internal(match schema_error {
+                            Some(schema_error) => format!(
+                                "Failed to parse result schema ({}) and the result is not JSON: {}",
+                                schema_error, e
+                            ),
+                            None => format!("Failed to parse as JSON: {}", e),
+                        }))
+                    }
+                }
             }
         }
     }
 
-    /// List MCP server runners
+    /// List the tools in a runner's method_proto_map with their schemas
+    ///
+    /// Descriptors are parsed the same way `call_mcp_tool` does, so the
+    /// result shows which tools decode via protobuf and which fall back to
+    /// JSON. Tools are sorted by name.
+    pub async fn inspect_runner_schema(
+        &self,
+        server_name: &str,
+    ) -> Result<Vec<ToolSchemaInfo>, AppError> {
+        let runner = self
+            .find_runner_by_exact_name(server_name)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Runner '{}' not found", server_name)))?;
+        let runner_data = runner
+            .data
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("Runner has no data".into()))?;
+        let Some(map) = runner_data.method_proto_map.as_ref() else {
+            return Ok(Vec::new());
+        };
+
+        let mut tools: Vec<ToolSchemaInfo> = map
+            .schemas
+            .iter()
+            .map(|(tool_name, method)| {
+                let args =
+                    JobworkerpProto::parse_job_args_schema_descriptor(runner_data, Some(tool_name));
+                let result =
+                    JobworkerpProto::parse_result_schema_descriptor(runner_data, Some(tool_name));
+                let schema_error = match (&args, &result) {
+                    (Err(e), _) => Some(format!("Failed to parse args schema: {}", e)),
+                    (_, Err(e)) => Some(format!("Failed to parse result schema: {}", e)),
+                    _ => None,
+                };
+                let has_result_schema = matches!(result, Ok(Some(_)));
+                let decode_path = match result {
+                    Ok(Some(_)) => McpDecodePath::Protobuf,
+                    Ok(None) => McpDecodePath::Json,
+                    Err(_) => McpDecodePath::SchemaFallback,
+                };
+                ToolSchemaInfo {
+                    tool_name: tool_name.clone(),
+                    description: method.description.clone().filter(|d| !d.is_empty()),
+                    has_args_schema: matches!(args, Ok(Some(_))),
+                    has_result_schema,
+                    decode_path,
+                    schema_error,
+                }
+            })
+            .collect();
+        tools.sort_by(|a, b| a.tool_name.cmp(&b.tool_name));
+        Ok(tools)
+    }
+
+    /// JSON Schema for the arguments of an MCP server tool
+    ///
+    /// Built from the tool's `args_proto` in the runner's method map; the
+    /// tool description, if any, becomes the schema description.
+    pub async fn get_tool_arg_schema(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+    ) -> Result<serde_json::Value, AppError> {
+        let runner = self
+            .find_runner_by_exact_name(server_name)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Runner '{}' not found", server_name)))?;
+        let runner_data = runner
+            .data
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("Runner has no data".into()))?;
+        let method = runner_data
+            .method_proto_map
+            .as_ref()
+            .and_then(|map| map.schemas.get(tool_name))
+            .ok_or_else(|| {
+                AppError::NotFound(format!(
+                    "Tool '{}' not found on MCP server '{}'",
+                    tool_name, server_name
+                ))
+            })?;
+
+        let descriptor =
+            JobworkerpProto::parse_job_args_schema_descriptor(runner_data, Some(tool_name))
+                .map_err(|e| AppError::Internal(format!("Failed to parse args schema: {}", e)))?;
+        let mut schema = match descriptor {
+            Some(desc) => message_to_json_schema(&desc),
+            // Tools without arguments
+            None => serde_json::json!({ "type": "object", "properties": {} }),
+        };
+        if let Some(description) = method.description.as_deref().filter(|d| !d.is_empty()) {
+            schema["description"] = serde_json::json!(description);
+        }
+        Ok(schema)
+    }
+
     pub async fn list_mcp_servers(&self) -> Result<Vec<McpServerInfo>, AppError> {
         let mut client = self.runner_client().await;
 
@@ -398,6 +1070,35 @@ impl JobworkerpClient {
 
     // ===== Runner Management =====
 
+    /// List runners of the given types, or of every type when `runner_types` is empty
+    pub async fn list_runners(&self, runner_types: Vec<i32>) -> Result<Vec<RunnerInfo>, AppError> {
+        let mut client = self.runner_client().await;
+
+        let request = FindRunnerListRequest {
+            runner_types,
+            ..Default::default()
+        };
+
+        let req = self.add_auth_header(tonic::Request::new(request));
+        let mut stream = client.find_list_by(req).await?.into_inner();
+
+        let mut runners = Vec::new();
+        while let Some(runner) = stream.message().await? {
+            if let (Some(id), Some(runner_data)) = (runner.id, runner.data) {
+                runners.push(RunnerInfo {
+                    id: id.value,
+                    name: runner_data.name,
+                    runner_type: data::RunnerType::try_from(runner_data.runner_type)
+                        .map(|t| t.as_str_name().to_string())
+                        .unwrap_or_else(|_| format!("UNKNOWN({})", runner_data.runner_type)),
+                    description: runner_data.description,
+                });
+            }
+        }
+
+        Ok(runners)
+    }
+
     /// Find a runner by exact name match
     pub async fn find_runner_by_exact_name(
         &self,
@@ -443,6 +1144,15 @@ impl JobworkerpClient {
         Ok(id.value)
     }
 
+    /// Delete a runner by ID
+    pub async fn delete_runner(&self, runner_id: i64) -> Result<(), AppError> {
+        let mut client = self.runner_client().await;
+
+        let req = self.add_auth_header(tonic::Request::new(data::RunnerId { value: runner_id }));
+        client.delete(req).await?;
+        Ok(())
+    }
+
     // ===== Worker Management =====
 
     /// Find a worker by exact name match
@@ -476,6 +1186,15 @@ impl JobworkerpClient {
         Ok(id.value)
     }
 
+    /// Delete a worker by ID
+    pub async fn delete_worker(&self, worker_id: i64) -> Result<(), AppError> {
+        let mut client = self.worker_client().await;
+
+        let req = self.add_auth_header(tonic::Request::new(data::WorkerId { value: worker_id }));
+        client.delete(req).await?;
+        Ok(())
+    }
+
     /// Ensure an MCP worker exists for the given MCP server name
     ///
     /// This method implements the automatic worker provisioning logic:
@@ -570,6 +1289,98 @@ impl JobworkerpClient {
     }
 }
 
+/// Per-call options for `call_mcp_tool_with_options`
+#[derive(Debug, Clone)]
+pub struct McpCallOptions {
+    /// Wait for a reported rate limit to reset and retry once instead of failing
+    pub retry_on_rate_limit: bool,
+    /// Skip the runner's result_proto schema and parse the raw result as JSON
+    pub force_json: bool,
+    /// Cap on the serialized result size, to keep huge results off the IPC bridge
+    pub max_result_bytes: Option<usize>,
+    /// What to do when `max_result_bytes` is exceeded
+    pub oversize: OversizeResult,
+    /// Upper bound for the job to complete; `None` waits indefinitely
+    pub timeout: Option<std::time::Duration>,
+}
+
+impl Default for McpCallOptions {
+    fn default() -> Self {
+        Self {
+            retry_on_rate_limit: false,
+            force_json: false,
+            max_result_bytes: Some(result_limit::DEFAULT_MAX_RESULT_BYTES),
+            oversize: OversizeResult::Reject,
+            timeout: None,
+        }
+    }
+}
+
+/// Report an unimplemented job listing RPC as unsupported
+fn job_listing_error(status: tonic::Status) -> AppError {
+    if status.code() == tonic::Code::Unimplemented {
+        AppError::InvalidInput("backend does not support job listing".into())
+    } else {
+        status.into()
+    }
+}
+
+/// Convert a stream/enqueue error, surfacing upstream rate limits as a typed error
+fn rate_limit_or_status(status: tonic::Status) -> AppError {
+    match rate_limit::detect_rate_limit(status.message()) {
+        Some(info) => info.into(),
+        None => status.into(),
+    }
+}
+
+/// How an MCP tool result was decoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpDecodePath {
+    /// Decoded with the Runner's result_proto schema
+    Protobuf,
+    /// No result_proto schema (or JSON forced); parsed as JSON
+    Json,
+    /// The result_proto schema failed to parse; parsed as JSON instead
+    SchemaFallback,
+    /// JSON forced but the bytes were not JSON; returned as a UTF-8 string
+    Text,
+    /// The tool produced no output
+    Empty,
+}
+
+/// Decoded MCP tool result with decoding details
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct McpToolOutput {
+    pub result: serde_json::Value,
+    pub decode_path: McpDecodePath,
+    /// Length of the raw result bytes before decoding
+    pub byte_length: usize,
+}
+
+/// Schemas a runner declares for one MCP tool
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ToolSchemaInfo {
+    pub tool_name: String,
+    pub description: Option<String>,
+    pub has_args_schema: bool,
+    pub has_result_schema: bool,
+    /// How `call_mcp_tool` will decode results: `protobuf`, `json` or
+    /// `schema_fallback`
+    pub decode_path: McpDecodePath,
+    /// Why a declared schema could not be parsed; results of the tool are
+    /// then parsed as JSON
+    pub schema_error: Option<String>,
+}
+
+/// Backend endpoint and its lazily created channel
+struct Connection {
+    endpoint: Endpoint,
+    /// Proxy used for this endpoint, if it is not bypassed
+    proxy: Option<ProxyConfig>,
+    channel: Option<Channel>,
+}
+
 /// MCP Server information
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct McpServerInfo {
@@ -578,11 +1389,29 @@ pub struct McpServerInfo {
     pub runner_type: String,
 }
 
+/// Backend runner of any type
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct RunnerInfo {
+    pub id: i64,
+    pub name: String,
+    /// Runner type name (e.g. "MCP_SERVER", "COMMAND")
+    pub runner_type: String,
+    pub description: String,
+}
+
 /// Get default gRPC URL from environment or fallback
 pub fn default_grpc_url() -> String {
     std::env::var("JOBWORKERP_GRPC_URL").unwrap_or_else(|_| "http://localhost:9000".to_string())
 }
 
+/// ID of a streamed job, from the `x-job-id-bin` header of its enqueue response
+fn job_id_from_metadata(metadata: &tonic::metadata::MetadataMap) -> Option<i64> {
+    use prost::Message;
+
+    let bytes = metadata.get_bin(JOB_ID_HEADER)?.to_bytes().ok()?;
+    data::JobId::decode(bytes).ok().map(|id| id.value)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -604,4 +1433,17 @@ mod tests {
         let url = default_grpc_url();
         assert!(!url.is_empty());
     }
+
+    #[test]
+    fn test_job_id_from_metadata() {
+        use prost::Message;
+
+        let mut metadata = tonic::metadata::MetadataMap::new();
+        assert_eq!(job_id_from_metadata(&metadata), None);
+        metadata.insert_bin(
+            JOB_ID_HEADER,
+            tonic::metadata::MetadataValue::from_bytes(&data::JobId { value: 42 }.encode_to_vec()),
+        );
+        assert_eq!(job_id_from_metadata(&metadata), Some(42));
+    }
 }
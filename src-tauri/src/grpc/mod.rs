@@ -2,6 +2,18 @@
 pub use jobworkerp_client::jobworkerp::data;
 pub use jobworkerp_client::jobworkerp::service;
 
+pub mod breaker;
 pub mod client;
+pub mod payload;
+pub mod proxy;
+pub mod rate_limit;
+pub mod result_limit;
+pub mod schema;
+pub mod throttle;
+pub mod tls;
+pub mod tool_overrides;
 
-pub use client::{default_grpc_url, JobworkerpClient, McpServerInfo};
+pub use client::{
+    default_grpc_url, ClientConfig, JobworkerpClient, McpCallOptions, McpServerInfo, McpToolOutput,
+    RunnerInfo, ToolSchemaInfo,
+};
@@ -0,0 +1,106 @@
+/// Unwrap the MCP content structure into the JSON document it carries
+///
+/// MCP tools wrap their output as `{"content": [{"text": "..."}]}`, or
+/// `{"content": [{"text": {"text": "..."}}]}` when decoded from protobuf.
+/// The first text item that parses as JSON is returned; anything else is
+/// returned unchanged.
+pub fn normalize_mcp_payload(result: &serde_json::Value) -> serde_json::Value {
+    if let Some(content) = result.get("content").and_then(|c| c.as_array()) {
+        for item in content {
+            let text_str = item.get("text").and_then(|t| {
+                t.get("text")
+                    .and_then(|inner| inner.as_str())
+                    .or_else(|| t.as_str())
+            });
+            if let Some(text) = text_str {
+                match serde_json::from_str::<serde_json::Value>(text) {
+                    Ok(parsed) => return parsed,
+                    Err(_) => {
+                        tracing::debug!(
+                            "MCP text content is not JSON: {}",
+                            text.chars().take(500).collect::<String>()
+                        )
+                    }
+                }
+            }
+        }
+    }
+    result.clone()
+}
+
+/// Find the item list in a normalized payload
+///
+/// Accepts a direct array or an object holding the array under one of `keys`.
+pub fn payload_items<'a>(
+    payload: &'a serde_json::Value,
+    keys: &[&str],
+) -> Option<&'a Vec<serde_json::Value>> {
+    payload.as_array().or_else(|| {
+        keys.iter()
+            .find_map(|key| payload.get(*key).and_then(|v| v.as_array()))
+    })
+}
+
+/// Pagination details reported alongside a list payload
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageInfo {
+    pub total_count: Option<i64>,
+    pub has_next_page: Option<bool>,
+}
+
+/// Read `pageInfo` and `totalCount` (GitHub) or `total_count` from a payload
+pub fn page_info(payload: &serde_json::Value) -> PageInfo {
+    PageInfo {
+        total_count: payload
+            .get("totalCount")
+            .or_else(|| payload.get("total_count"))
+            .and_then(|v| v.as_i64()),
+        has_next_page: payload
+            .get("pageInfo")
+            .and_then(|i| i.get("hasNextPage"))
+            .and_then(|v| v.as_bool()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_normalize_mcp_payload_unwraps_text_content() {
+        let flat = json!({"content": [{"text": "[{\"number\": 1}]"}]});
+        assert_eq!(normalize_mcp_payload(&flat), json!([{"number": 1}]));
+
+        let nested = json!({"content": [{"text": {"text": "{\"issues\": []}"}}]});
+        assert_eq!(normalize_mcp_payload(&nested), json!({"issues": []}));
+    }
+
+    #[test]
+    fn test_normalize_mcp_payload_keeps_unwrapped_result() {
+        let direct = json!([{"number": 1}]);
+        assert_eq!(normalize_mcp_payload(&direct), direct);
+
+        let not_json = json!({"content": [{"text": "plain text"}]});
+        assert_eq!(normalize_mcp_payload(&not_json), not_json);
+    }
+
+    #[test]
+    fn test_payload_items_and_page_info() {
+        let payload = json!({
+            "pull_requests": [{"number": 1}, {"number": 2}],
+            "pageInfo": {"hasNextPage": true},
+            "totalCount": 42,
+        });
+        let items = payload_items(&payload, &["issues", "pull_requests"]).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(
+            page_info(&payload),
+            PageInfo {
+                total_count: Some(42),
+                has_next_page: Some(true),
+            }
+        );
+        assert_eq!(page_info(&json!([])), PageInfo::default());
+    }
+}
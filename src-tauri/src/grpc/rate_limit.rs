@@ -0,0 +1,194 @@
+use regex::Regex;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use crate::error::AppError;
+
+/// Longest wait honored when retrying a rate-limited MCP call
+pub const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// Wait used when the upstream does not report a reset time
+const DEFAULT_RATE_LIMIT_WAIT: Duration = Duration::from_secs(5);
+
+/// Rate-limit phrases, or a 429 that is clearly an HTTP status
+static RATE_LIMIT_MARKER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)rate[- ]?limit|too many requests|\b(?:http(?:/\d(?:\.\d)?)?|status(?:[ _]?code)?)\W{0,3}429\b",
+    )
+    .expect("valid regex")
+});
+
+static RESET_HEADER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)x-ratelimit-reset["']?\s*[:=]\s*["']?(\d{9,11})"#).expect("valid regex")
+});
+
+static RETRY_AFTER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)retry[- ]after["']?\s*[:=]?\s*["']?(\d+)"#).expect("valid regex")
+});
+
+static RESET_TIMESTAMP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)reset[^0-9]{0,20}(\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:\d{2}))",
+    )
+    .expect("valid regex")
+});
+
+/// Rate limit reported by the upstream platform
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    pub message: String,
+    /// Unix timestamp (seconds) at which the limit resets, if reported
+    pub reset_at: Option<i64>,
+}
+
+impl From<RateLimitInfo> for AppError {
+    fn from(info: RateLimitInfo) -> Self {
+        tracing::warn!("Upstream rate limit: {:?}", info);
+        AppError::RateLimited {
+            message: info.message,
+            reset_at: info.reset_at,
+        }
+    }
+}
+
+/// Detect a rate-limit error in an error message
+///
+/// Recognizes "rate limit exceeded" / "too many requests" messages and 429s
+/// given as an HTTP status ("HTTP 429", "status: 429"); a bare 429 such as an
+/// issue number is not enough. The reset time is taken from
+/// `X-RateLimit-Reset`, `Retry-After` or an RFC 3339 timestamp.
+pub fn detect_rate_limit(text: &str) -> Option<RateLimitInfo> {
+    if !RATE_LIMIT_MARKER.is_match(text) {
+        return None;
+    }
+
+    Some(RateLimitInfo {
+        message: text.lines().next().unwrap_or(text).trim().to_string(),
+        reset_at: parse_reset_at(text),
+    })
+}
+
+/// Detect a rate-limit error in a decoded MCP tool result
+///
+/// Only results flagged as errors (`isError`/`is_error`) are inspected, so
+/// issue bodies mentioning rate limits are never mistaken for failures.
+pub fn rate_limit_from_result(result: &serde_json::Value) -> Option<RateLimitInfo> {
+    let is_error = result
+        .get("isError")
+        .or_else(|| result.get("is_error"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if !is_error {
+        return None;
+    }
+
+    result
+        .get("content")
+        .and_then(|c| c.as_array())?
+        .iter()
+        .filter_map(|item| {
+            // Handle nested text.text structure (Protobuf decoded format)
+            item.get("text").and_then(|t| {
+                t.get("text")
+                    .and_then(|inner| inner.as_str())
+                    .or_else(|| t.as_str())
+            })
+        })
+        .find_map(detect_rate_limit)
+}
+
+/// How long to wait before retrying, or None if the reset is too far away
+pub fn retry_delay(reset_at: Option<i64>) -> Option<Duration> {
+    let Some(reset_at) = reset_at else {
+        return Some(DEFAULT_RATE_LIMIT_WAIT);
+    };
+
+    let remaining = reset_at - chrono::Utc::now().timestamp();
+    if remaining <= 0 {
+        return Some(Duration::from_secs(1));
+    }
+
+    let wait = Duration::from_secs(remaining as u64 + 1);
+    (wait <= MAX_RATE_LIMIT_WAIT).then_some(wait)
+}
+
+/// Extract the reset time from a rate-limit message as a Unix timestamp
+fn parse_reset_at(text: &str) -> Option<i64> {
+    if let Some(caps) = RESET_HEADER.captures(text) {
+        return caps[1].parse().ok();
+    }
+
+    if let Some(caps) = RETRY_AFTER.captures(text) {
+        let seconds: i64 = caps[1].parse().ok()?;
+        return Some(chrono::Utc::now().timestamp() + seconds);
+    }
+
+    let caps = RESET_TIMESTAMP.captures(text)?;
+    chrono::DateTime::parse_from_rfc3339(&caps[1])
+        .ok()
+        .map(|dt| dt.timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_rate_limit_with_reset_header() {
+        let info = detect_rate_limit("403 API rate limit exceeded, X-RateLimit-Reset: 1700000000")
+            .unwrap();
+        assert_eq!(info.reset_at, Some(1700000000));
+    }
+
+    #[test]
+    fn test_detect_rate_limit_with_retry_after() {
+        let before = chrono::Utc::now().timestamp();
+        let info = detect_rate_limit("HTTP 429 Too Many Requests; Retry-After: 30").unwrap();
+        let reset_at = info.reset_at.unwrap();
+        assert!(reset_at >= before + 30 && reset_at <= before + 31);
+    }
+
+    #[test]
+    fn test_detect_rate_limit_with_rfc3339_reset() {
+        let info =
+            detect_rate_limit("rate limit exceeded, resets at 2024-01-01T00:00:00Z").unwrap();
+        assert_eq!(info.reset_at, Some(1704067200));
+    }
+
+    #[test]
+    fn test_detect_rate_limit_ignores_other_errors() {
+        assert!(detect_rate_limit("404 Not Found").is_none());
+        assert!(detect_rate_limit("Failed to update issue #429: not found").is_none());
+        assert!(detect_rate_limit("request took 429ms").is_none());
+    }
+
+    #[test]
+    fn test_detect_rate_limit_http_status() {
+        assert!(detect_rate_limit("HTTP 429").is_some());
+        assert!(detect_rate_limit("HTTP/1.1 429").is_some());
+        assert!(detect_rate_limit("upstream error (status: 429)").is_some());
+        assert!(detect_rate_limit("status_code=429").is_some());
+    }
+
+    #[test]
+    fn test_rate_limit_from_result_requires_error_flag() {
+        let ok = serde_json::json!({
+            "content": [{"text": "Discussing the API rate limit exceeded message"}]
+        });
+        assert!(rate_limit_from_result(&ok).is_none());
+
+        let err = serde_json::json!({
+            "isError": true,
+            "content": [{"text": {"text": "API rate limit exceeded"}}]
+        });
+        assert!(rate_limit_from_result(&err).is_some());
+    }
+
+    #[test]
+    fn test_retry_delay_bounds() {
+        let now = chrono::Utc::now().timestamp();
+        assert!(retry_delay(Some(now + 10)).is_some());
+        assert!(retry_delay(Some(now + 3600)).is_none());
+        assert_eq!(retry_delay(None), Some(DEFAULT_RATE_LIMIT_WAIT));
+    }
+}
@@ -0,0 +1,151 @@
+use serde_json::Value;
+
+use super::payload::normalize_mcp_payload;
+use crate::error::AppError;
+
+/// Default cap on the serialized size of an MCP result sent over IPC
+pub const DEFAULT_MAX_RESULT_BYTES: usize = 8 * 1024 * 1024;
+
+/// How to handle a result larger than `max_result_bytes`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OversizeResult {
+    /// Fail with `InvalidInput`
+    #[default]
+    Reject,
+    /// Drop trailing array elements and mark the result with `truncated: true`
+    Truncate,
+}
+
+fn too_large() -> AppError {
+    AppError::InvalidInput("result too large; narrow your query".into())
+}
+
+fn json_len(value: &Value) -> usize {
+    serde_json::to_vec(value).map_or(usize::MAX, |bytes| bytes.len())
+}
+
+/// Enforce a size limit on a decoded MCP result
+///
+/// In `Truncate` mode the MCP content wrapper is unwrapped (see
+/// `normalize_mcp_payload`) so arrays inside the text payload can be shortened.
+pub fn enforce_limit(
+    value: Value,
+    max_bytes: usize,
+    mode: OversizeResult,
+) -> Result<Value, AppError> {
+    if json_len(&value) <= max_bytes {
+        return Ok(value);
+    }
+    tracing::warn!("MCP result exceeds {} bytes ({:?})", max_bytes, mode);
+
+    match mode {
+        OversizeResult::Reject => Err(too_large()),
+        OversizeResult::Truncate => {
+            let mut payload = normalize_mcp_payload(&value);
+            while json_len(&payload) > max_bytes {
+                if !halve_longest_array(&mut payload) {
+                    return Err(too_large());
+                }
+            }
+            Ok(mark_truncated(payload))
+        }
+    }
+}
+
+/// Halve the longest array in `value`; false if no array has more than one element
+fn halve_longest_array(value: &mut Value) -> bool {
+    let mut longest: Option<(String, usize)> = None;
+    find_longest_array(value, String::new(), &mut longest);
+
+    match longest {
+        Some((pointer, len)) if len > 1 => value
+            .pointer_mut(&pointer)
+            .and_then(Value::as_array_mut)
+            .map(|arr| arr.truncate(len / 2))
+            .is_some(),
+        _ => false,
+    }
+}
+
+/// Record the JSON pointer and length of the longest array under `value`
+fn find_longest_array(value: &Value, pointer: String, longest: &mut Option<(String, usize)>) {
+    match value {
+        Value::Array(arr) => {
+            if !matches!(longest, Some((_, len)) if *len >= arr.len()) {
+                *longest = Some((pointer.clone(), arr.len()));
+            }
+            for (i, item) in arr.iter().enumerate() {
+                find_longest_array(item, format!("{}/{}", pointer, i), longest);
+            }
+        }
+        Value::Object(map) => {
+            for (key, item) in map {
+                // JSON pointer escaping (RFC 6901)
+                let key = key.replace('~', "~0").replace('/', "~1");
+                find_longest_array(item, format!("{}/{}", pointer, key), longest);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Add the `truncated: true` marker, wrapping a bare array as `{"items": [...]}`
+fn mark_truncated(payload: Value) -> Value {
+    match payload {
+        Value::Object(mut map) => {
+            map.insert("truncated".into(), Value::Bool(true));
+            Value::Object(map)
+        }
+        Value::Array(items) => serde_json::json!({ "items": items, "truncated": true }),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn issues(n: usize) -> Value {
+        let items: Vec<Value> = (0..n)
+            .map(|i| json!({"number": i, "title": "x".repeat(50)}))
+            .collect();
+        json!({ "issues": items, "totalCount": n })
+    }
+
+    #[test]
+    fn test_enforce_limit_passes_small_results() {
+        let value = issues(2);
+        assert_eq!(
+            enforce_limit(value.clone(), 1024, OversizeResult::Reject).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn test_enforce_limit_rejects_large_results() {
+        let err = enforce_limit(issues(100), 1024, OversizeResult::Reject).unwrap_err();
+        assert!(matches!(err, AppError::InvalidInput(_)));
+    }
+
+    #[test]
+    fn test_enforce_limit_truncates_content_wrapped_arrays() {
+        let text = serde_json::to_string(&issues(100)).unwrap();
+        let wrapped = json!({"content": [{"text": text}]});
+
+        let result = enforce_limit(wrapped, 1024, OversizeResult::Truncate).unwrap();
+        assert_eq!(result["truncated"], json!(true));
+        assert_eq!(result["totalCount"], json!(100));
+        let kept = result["issues"].as_array().unwrap().len();
+        assert!(kept > 0 && kept < 100);
+        assert!(json_len(&result) <= 1024 + r#","truncated":true"#.len());
+    }
+
+    #[test]
+    fn test_enforce_limit_wraps_truncated_array() {
+        let items: Vec<Value> = (0..100).map(|i| json!({"number": i})).collect();
+        let result = enforce_limit(Value::Array(items), 200, OversizeResult::Truncate).unwrap();
+        assert_eq!(result["truncated"], json!(true));
+        assert!(result["items"].as_array().unwrap().len() < 100);
+    }
+}
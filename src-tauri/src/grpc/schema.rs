@@ -0,0 +1,206 @@
+// JSON Schema for protobuf message descriptors
+
+use prost_reflect::{FieldDescriptor, Kind, MessageDescriptor};
+use serde_json::{json, Map, Value};
+
+/// Nesting depth at which recursive messages are cut off as plain objects
+const MAX_SCHEMA_DEPTH: usize = 8;
+
+/// Build a JSON Schema (draft 2020-12 subset) for a message
+///
+/// Singular fields without explicit presence (proto3 scalars not marked
+/// `optional`, proto2 `required`) are listed in `required`.
+///
+/// MCP runners describe each tool's arguments as a protobuf message
+/// (`args_proto` in the runner's method map). This converts the parsed
+/// descriptor into a JSON Schema so clients can render a form for it.
+pub fn message_to_json_schema(descriptor: &MessageDescriptor) -> Value {
+    let mut schema = message_schema(descriptor, 0);
+    if let Value::Object(map) = &mut schema {
+        map.insert(
+            "$schema".to_string(),
+            json!("https://json-schema.org/draft/2020-12/schema"),
+        );
+        map.insert("title".to_string(), json!(descriptor.name()));
+    }
+    schema
+}
+
+fn message_schema(descriptor: &MessageDescriptor, depth: usize) -> Value {
+    if let Some(schema) = well_known_schema(descriptor.full_name()) {
+        return schema;
+    }
+    if depth >= MAX_SCHEMA_DEPTH {
+        return json!({ "type": "object" });
+    }
+
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for field in descriptor.fields() {
+        if field.is_required()
+            || (!field.supports_presence() && !field.is_list() && !field.is_map())
+        {
+            required.push(json!(field.name()));
+        }
+        properties.insert(field.name().to_string(), field_schema(&field, depth));
+    }
+
+    let mut schema = json!({
+        "type": "object",
+        "properties": properties,
+        "additionalProperties": false,
+    });
+    if !required.is_empty() {
+        schema["required"] = Value::Array(required);
+    }
+    schema
+}
+
+fn field_schema(field: &FieldDescriptor, depth: usize) -> Value {
+    if field.is_map() {
+        let value_schema = match field.kind() {
+            Kind::Message(entry) => kind_schema(&entry.map_entry_value_field().kind(), depth + 1),
+            _ => json!({}),
+        };
+        return json!({ "type": "object", "additionalProperties": value_schema });
+    }
+
+    let item = kind_schema(&field.kind(), depth + 1);
+    if field.is_list() {
+        json!({ "type": "array", "items": item })
+    } else {
+        item
+    }
+}
+
+fn kind_schema(kind: &Kind, depth: usize) -> Value {
+    match kind {
+        Kind::Double | Kind::Float => json!({ "type": "number" }),
+        Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 => {
+            json!({ "type": "integer", "format": "int32" })
+        }
+        Kind::Uint32 | Kind::Fixed32 => {
+            json!({ "type": "integer", "format": "uint32", "minimum": 0 })
+        }
+        Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 => {
+            json!({ "type": "integer", "format": "int64" })
+        }
+        Kind::Uint64 | Kind::Fixed64 => {
+            json!({ "type": "integer", "format": "uint64", "minimum": 0 })
+        }
+        Kind::Bool => json!({ "type": "boolean" }),
+        Kind::String => json!({ "type": "string" }),
+        Kind::Bytes => json!({ "type": "string", "contentEncoding": "base64" }),
+        Kind::Enum(descriptor) => json!({
+            "type": "string",
+            "enum": descriptor.values().map(|v| v.name().to_string()).collect::<Vec<_>>(),
+        }),
+        Kind::Message(descriptor) => message_schema(descriptor, depth),
+    }
+}
+
+/// Schemas for well-known types, which have a special JSON mapping
+fn well_known_schema(full_name: &str) -> Option<Value> {
+    let schema = match full_name {
+        "google.protobuf.Struct" => json!({ "type": "object" }),
+        "google.protobuf.Value" => json!({}),
+        "google.protobuf.ListValue" => json!({ "type": "array" }),
+        "google.protobuf.Timestamp" => json!({ "type": "string", "format": "date-time" }),
+        "google.protobuf.Duration" => json!({ "type": "string" }),
+        "google.protobuf.StringValue" => json!({ "type": ["string", "null"] }),
+        "google.protobuf.BoolValue" => json!({ "type": ["boolean", "null"] }),
+        "google.protobuf.DoubleValue" | "google.protobuf.FloatValue" => {
+            json!({ "type": ["number", "null"] })
+        }
+        "google.protobuf.Int32Value"
+        | "google.protobuf.Int64Value"
+        | "google.protobuf.UInt32Value"
+        | "google.protobuf.UInt64Value" => json!({ "type": ["integer", "null"] }),
+        _ => return None,
+    };
+    Some(schema)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost_reflect::DescriptorPool;
+    use prost_types::field_descriptor_proto::{Label, Type};
+    use prost_types::{
+        DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
+        FileDescriptorProto,
+    };
+
+    fn field(name: &str, number: i32, r#type: Type, label: Label) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_string()),
+            number: Some(number),
+            r#type: Some(r#type as i32),
+            label: Some(label as i32),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_message_to_json_schema() {
+        let mut state = field("state", 4, Type::Enum, Label::Optional);
+        state.type_name = Some(".test.State".to_string());
+        let mut per_page = field("per_page", 3, Type::Int32, Label::Optional);
+        per_page.proto3_optional = Some(true);
+        per_page.oneof_index = Some(0);
+
+        let file = FileDescriptorProto {
+            name: Some("test.proto".to_string()),
+            package: Some("test".to_string()),
+            syntax: Some("proto3".to_string()),
+            message_type: vec![DescriptorProto {
+                name: Some("ListIssuesArgs".to_string()),
+                field: vec![
+                    field("owner", 1, Type::String, Label::Optional),
+                    field("labels", 2, Type::String, Label::Repeated),
+                    per_page,
+                    state,
+                ],
+                oneof_decl: vec![prost_types::OneofDescriptorProto {
+                    name: Some("_per_page".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            enum_type: vec![EnumDescriptorProto {
+                name: Some("State".to_string()),
+                value: vec![
+                    EnumValueDescriptorProto {
+                        name: Some("OPEN".to_string()),
+                        number: Some(0),
+                        ..Default::default()
+                    },
+                    EnumValueDescriptorProto {
+                        name: Some("CLOSED".to_string()),
+                        number: Some(1),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let mut pool = DescriptorPool::new();
+        pool.add_file_descriptor_proto(file).unwrap();
+        let descriptor = pool.get_message_by_name("test.ListIssuesArgs").unwrap();
+
+        let schema = message_to_json_schema(&descriptor);
+        assert_eq!(schema["title"], "ListIssuesArgs");
+        assert_eq!(schema["properties"]["owner"], json!({ "type": "string" }));
+        assert_eq!(
+            schema["properties"]["labels"],
+            json!({ "type": "array", "items": { "type": "string" } })
+        );
+        assert_eq!(schema["properties"]["per_page"]["type"], "integer");
+        assert_eq!(
+            schema["properties"]["state"]["enum"],
+            json!(["OPEN", "CLOSED"])
+        );
+        assert_eq!(schema["required"], json!(["owner", "state"]));
+    }
+}
@@ -0,0 +1,199 @@
+// Per-server throttling of MCP tool calls
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+struct Bucket {
+    /// Available calls; negative while calls are waiting for reserved tokens
+    tokens: f64,
+    refilled_at: Instant,
+    waiting: Arc<AtomicU32>,
+}
+
+/// Token-bucket limiter keyed by MCP server name
+///
+/// Each MCP server gets a token bucket holding up to one minute's worth of
+/// calls, refilled continuously at the configured rate. A call that finds the
+/// bucket empty reserves the next token and sleeps until it is due, so
+/// waiting calls proceed in arrival order.
+#[derive(Debug, Default)]
+pub struct McpThrottle {
+    /// Calls per minute per server; 0 disables throttling
+    calls_per_minute: AtomicU32,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+/// Throttle state of one MCP server
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerThrottleState {
+    pub server_name: String,
+    /// Calls that can be made right now without waiting
+    pub available: u32,
+    /// Calls currently waiting for a permit
+    pub waiting: u32,
+    /// Time until the next permit becomes available, when none is available
+    pub next_permit_ms: Option<u64>,
+}
+
+/// Throttle configuration and per-server state
+#[derive(Debug, Clone, Serialize)]
+pub struct McpThrottleStatus {
+    /// 0 when throttling is disabled
+    pub calls_per_minute: u32,
+    pub servers: Vec<ServerThrottleState>,
+}
+
+/// Decrements a bucket's waiting count when a wait ends or is cancelled
+struct WaitingGuard(Arc<AtomicU32>);
+
+impl Drop for WaitingGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Bucket {
+    fn full(capacity: f64, now: Instant) -> Self {
+        Self {
+            tokens: capacity,
+            refilled_at: now,
+            waiting: Arc::new(AtomicU32::new(0)),
+        }
+    }
+
+    fn refill(&mut self, per_second: f64, capacity: f64, now: Instant) {
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * per_second).min(capacity);
+        self.refilled_at = now;
+    }
+}
+
+impl McpThrottle {
+    /// Set the per-server rate; 0 disables throttling
+    ///
+    /// Buckets are reset, so a changed rate applies immediately.
+    pub fn set_calls_per_minute(&self, calls_per_minute: u32) {
+        let previous = self
+            .calls_per_minute
+            .swap(calls_per_minute, Ordering::SeqCst);
+        if previous != calls_per_minute {
+            self.lock_buckets().clear();
+            tracing::info!("MCP call throttle set to {} calls/minute", calls_per_minute);
+        }
+    }
+
+    pub fn calls_per_minute(&self) -> u32 {
+        self.calls_per_minute.load(Ordering::SeqCst)
+    }
+
+    fn lock_buckets(&self) -> std::sync::MutexGuard<'_, HashMap<String, Bucket>> {
+        self.buckets.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Reserve a call for `server_name`, returning how long to wait for it
+    fn reserve(&self, server_name: &str, now: Instant) -> Option<(Duration, Arc<AtomicU32>)> {
+        let rate = self.calls_per_minute();
+        if rate == 0 {
+            return None;
+        }
+        let capacity = rate as f64;
+        let per_second = capacity / 60.0;
+
+        let mut buckets = self.lock_buckets();
+        let bucket = buckets
+            .entry(server_name.to_string())
+            .or_insert_with(|| Bucket::full(capacity, now));
+        bucket.refill(per_second, capacity, now);
+        bucket.tokens -= 1.0;
+        let wait = if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / per_second)
+        };
+        Some((wait, bucket.waiting.clone()))
+    }
+
+    /// Wait until a call to `server_name` is allowed
+    pub async fn acquire(&self, server_name: &str) {
+        let Some((wait, waiting)) = self.reserve(server_name, Instant::now()) else {
+            return;
+        };
+        if wait.is_zero() {
+            return;
+        }
+
+        waiting.fetch_add(1, Ordering::SeqCst);
+        let _guard = WaitingGuard(waiting);
+        tracing::debug!("Throttling MCP call to '{}' for {:?}", server_name, wait);
+        tokio::time::sleep(wait).await;
+    }
+
+    /// Current configuration and the state of every server called so far
+    pub fn status(&self) -> McpThrottleStatus {
+        let rate = self.calls_per_minute();
+        let capacity = rate as f64;
+        let per_second = capacity / 60.0;
+        let now = Instant::now();
+
+        let mut servers: Vec<ServerThrottleState> = self
+            .lock_buckets()
+            .iter_mut()
+            .map(|(name, bucket)| {
+                bucket.refill(per_second, capacity, now);
+                ServerThrottleState {
+                    server_name: name.clone(),
+                    available: bucket.tokens.max(0.0).floor() as u32,
+                    waiting: bucket.waiting.load(Ordering::SeqCst),
+                    next_permit_ms: (bucket.tokens < 1.0 && per_second > 0.0)
+                        .then(|| ((1.0 - bucket.tokens) / per_second * 1000.0).ceil() as u64),
+                }
+            })
+            .collect();
+        servers.sort_by(|a, b| a.server_name.cmp(&b.server_name));
+
+        McpThrottleStatus {
+            calls_per_minute: rate,
+            servers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_by_default() {
+        let throttle = McpThrottle::default();
+        assert!(throttle.reserve("github", Instant::now()).is_none());
+        assert!(throttle.status().servers.is_empty());
+    }
+
+    #[test]
+    fn test_reserve_waits_once_bucket_is_empty() {
+        let throttle = McpThrottle::default();
+        throttle.set_calls_per_minute(2);
+        let now = Instant::now();
+
+        assert_eq!(throttle.reserve("github", now).unwrap().0, Duration::ZERO);
+        assert_eq!(throttle.reserve("github", now).unwrap().0, Duration::ZERO);
+        // 2 calls/minute: the next token is 30 seconds away, the one after 60
+        assert_eq!(
+            throttle.reserve("github", now).unwrap().0,
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            throttle.reserve("github", now).unwrap().0,
+            Duration::from_secs(60)
+        );
+        // Buckets are per server
+        assert_eq!(throttle.reserve("gitea", now).unwrap().0, Duration::ZERO);
+
+        let later = now + Duration::from_secs(90);
+        assert_eq!(throttle.reserve("github", later).unwrap().0, Duration::ZERO);
+    }
+}
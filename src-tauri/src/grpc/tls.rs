@@ -0,0 +1,205 @@
+// Extra CA certificate trusted for the backend's TLS endpoint
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::path::Path;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::CertificateDer;
+
+use crate::error::AppError;
+
+/// PEM bundle of CA certificates with the validity common to all of them
+#[derive(Debug, Clone)]
+pub struct TrustedCa {
+    pub pem: Vec<u8>,
+    pub certificates: Vec<CertificateDer<'static>>,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+}
+
+/// Read one DER element, returning its tag, contents and the bytes after it
+fn der_element(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (len, rest) = if first & 0x80 == 0 {
+        (usize::from(first), rest)
+    } else {
+        let octets = usize::from(first & 0x7f);
+        if octets == 0 || octets > 4 || rest.len() < octets {
+            return None;
+        }
+        let len = rest[..octets]
+            .iter()
+            .fold(0usize, |len, &b| (len << 8) | usize::from(b));
+        (len, &rest[octets..])
+    };
+    (rest.len() >= len).then(|| (tag, &rest[..len], &rest[len..]))
+}
+
+/// Parse an X.509 UTCTime (tag 0x17) or GeneralizedTime (tag 0x18)
+fn der_time(tag: u8, contents: &[u8]) -> Option<DateTime<Utc>> {
+    let text = std::str::from_utf8(contents).ok()?;
+    let full = match tag {
+        // RFC 5280: two-digit years 50-99 are 19xx
+        0x17 => {
+            let yy: u32 = text.get(..2)?.parse().ok()?;
+            format!("{}{}", if yy >= 50 { "19" } else { "20" }, text)
+        }
+        0x18 => text.to_string(),
+        _ => return None,
+    };
+    NaiveDateTime::parse_from_str(&full, "%Y%m%d%H%M%SZ")
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+/// Validity period (notBefore, notAfter) of a DER-encoded X.509 certificate
+fn certificate_validity(der: &[u8]) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let (0x30, certificate, _) = der_element(der)? else {
+        return None;
+    };
+    let (0x30, tbs, _) = der_element(certificate)? else {
+        return None;
+    };
+    // Optional explicit version, then serial number, signature algorithm, issuer
+    let (tag, _, mut rest) = der_element(tbs)?;
+    if tag == 0xa0 {
+        rest = der_element(rest)?.2;
+    }
+    let rest = der_element(der_element(rest)?.2)?.2;
+    let (0x30, validity, _) = der_element(rest)? else {
+        return None;
+    };
+    let (tag, not_before, rest) = der_element(validity)?;
+    let not_before = der_time(tag, not_before)?;
+    let (tag, not_after, _) = der_element(rest)?;
+    Some((not_before, der_time(tag, not_after)?))
+}
+
+/// Check a PEM bundle holds certificates that are valid at `now`
+///
+/// Lets the app talk to a backend with a self-signed or private-CA
+/// certificate. Only the PEM envelope and each certificate's validity
+/// period are checked here; the chain itself is verified by the TLS
+/// handshake.
+pub fn parse_ca_pem(pem: Vec<u8>, now: DateTime<Utc>) -> Result<TrustedCa, AppError> {
+    let certificates = CertificateDer::pem_slice_iter(&pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::validation("cert_path", format!("Invalid PEM file: {}", e)))?;
+    if certificates.is_empty() {
+        return Err(AppError::validation(
+            "cert_path",
+            "No certificate found; expected a PEM file with BEGIN CERTIFICATE blocks",
+        ));
+    }
+
+    let mut not_before = DateTime::<Utc>::MIN_UTC;
+    let mut not_after = DateTime::<Utc>::MAX_UTC;
+    for (index, certificate) in certificates.iter().enumerate() {
+        let (from, until) = certificate_validity(certificate).ok_or_else(|| {
+            AppError::validation(
+                "cert_path",
+                format!("Certificate {} is not a valid X.509 certificate", index + 1),
+            )
+        })?;
+        if now > until {
+            return Err(AppError::validation(
+                "cert_path",
+                format!(
+                    "Certificate {} expired on {}",
+                    index + 1,
+                    until.to_rfc3339()
+                ),
+            ));
+        }
+        if now < from {
+            return Err(AppError::validation(
+                "cert_path",
+                format!(
+                    "Certificate {} is not valid until {}",
+                    index + 1,
+                    from.to_rfc3339()
+                ),
+            ));
+        }
+        not_before = not_before.max(from);
+        not_after = not_after.min(until);
+    }
+
+    Ok(TrustedCa {
+        pem,
+        certificates,
+        not_before,
+        not_after,
+    })
+}
+
+/// Load and check a PEM file of CA certificates
+pub fn load_ca_file(path: &Path) -> Result<TrustedCa, AppError> {
+    let pem = std::fs::read(path).map_err(|e| {
+        AppError::validation(
+            "cert_path",
+            format!("Cannot read {}: {}", path.display(), e),
+        )
+    })?;
+    parse_ca_pem(pem, Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Self-signed, valid 2026-10-16T20:11:32Z to 2126-09-22T20:11:32Z
+    const VALID_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBijCCATGgAwIBAgIULJ02YcEflnwWtIKVx0VMSMgbVWwwCgYIKoZIzj0EAwIw
+GjEYMBYGA1UEAwwPam9id29ya2VycC50ZXN0MCAXDTI2MTAxNjIwMTEzMloYDzIx
+MjYwOTIyMjAxMTMyWjAaMRgwFgYDVQQDDA9qb2J3b3JrZXJwLnRlc3QwWTATBgcq
+hkjOPQIBBggqhkjOPQMBBwNCAASHSntlUUo/FVFbjCLl7yvOTLW0FEySEf8tx1Dw
+1gEx0UcNrB+aG+KjWDerVtDccrUdWtIiofE8DcOgFqr/uXKLo1MwUTAdBgNVHQ4E
+FgQUeZfEqKecVL6PaUCCCaTiWfRqY6QwHwYDVR0jBBgwFoAUeZfEqKecVL6PaUCC
+CaTiWfRqY6QwDwYDVR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNHADBEAiAjcraT
+7q/mkh9pfhNIJr1FMFlmvClWgf3gn82CuqbBpAIgG9/n0Yj7DbNF2fOS3Fte82uu
+WpXZx24/gJQROpw6Pn4=
+-----END CERTIFICATE-----
+";
+
+    // Self-signed, valid 2020-01-01 to 2021-01-01
+    const EXPIRED_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBgzCCASmgAwIBAgIUUFnFBJW5Wwmu32ShiDDtjgMwJhswCgYIKoZIzj0EAwIw
+FzEVMBMGA1UEAwwMZXhwaXJlZC50ZXN0MB4XDTIwMDEwMTAwMDAwMFoXDTIxMDEw
+MTAwMDAwMFowFzEVMBMGA1UEAwwMZXhwaXJlZC50ZXN0MFkwEwYHKoZIzj0CAQYI
+KoZIzj0DAQcDQgAEVh3qL5qOqSXWjntusyj28G0/m3ty3cWXjw8nWns7b6+yF29p
+OIE+kAG6V15ZP609BvDbINAscnJkP6D6JCNowqNTMFEwHQYDVR0OBBYEFCTCc/o7
+AvAWYcGn8W/aakJHsId5MB8GA1UdIwQYMBaAFCTCc/o7AvAWYcGn8W/aakJHsId5
+MA8GA1UdEwEB/wQFMAMBAf8wCgYIKoZIzj0EAwIDSAAwRQIhANnuaKr2SZY46/wg
+bTnY9tKz0Wzoi6WLSE2nTOCfdzJOAiBGyw55uxqCsFdrMLcjiMGPlSV6Grx+tsCs
+3TBudfygKA==
+-----END CERTIFICATE-----
+";
+
+    fn at(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text).unwrap().to_utc()
+    }
+
+    #[test]
+    fn test_parse_ca_pem() {
+        let ca = parse_ca_pem(VALID_PEM.into(), at("2027-01-01T00:00:00Z")).unwrap();
+        assert_eq!(ca.certificates.len(), 1);
+        assert_eq!(ca.not_before, at("2026-10-16T20:11:32Z"));
+        assert_eq!(ca.not_after, at("2126-09-22T20:11:32Z"));
+
+        let err = parse_ca_pem(EXPIRED_PEM.into(), at("2027-01-01T00:00:00Z")).unwrap_err();
+        assert!(err.to_string().contains("expired on 2021-01-01"));
+        assert!(parse_ca_pem(VALID_PEM.into(), at("2026-01-01T00:00:00Z")).is_err());
+
+        // Later certificates in a bundle are checked too
+        let bundle = format!("{}{}", VALID_PEM, EXPIRED_PEM);
+        assert!(parse_ca_pem(bundle.into(), at("2027-01-01T00:00:00Z")).is_err());
+
+        for invalid in ["", "not a certificate"] {
+            assert!(matches!(
+                parse_ca_pem(invalid.into(), at("2027-01-01T00:00:00Z")),
+                Err(AppError::Validation { .. })
+            ));
+        }
+    }
+}
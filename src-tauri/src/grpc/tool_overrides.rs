@@ -0,0 +1,142 @@
+// Per-server MCP tool name overrides
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// App operation that calls an MCP tool
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum McpOperation {
+    ListIssues,
+    ReadIssue,
+    IssueTimeline,
+    IssueComments,
+    SearchIssues,
+    ListPulls,
+    ListLabels,
+    ListMyRepos,
+    PrReviews,
+    PrReviewComments,
+    SearchRepos,
+    CurrentUser,
+}
+
+impl McpOperation {
+    pub const ALL: [McpOperation; 12] = [
+        McpOperation::ListIssues,
+        McpOperation::ReadIssue,
+        McpOperation::IssueTimeline,
+        McpOperation::IssueComments,
+        McpOperation::SearchIssues,
+        McpOperation::ListPulls,
+        McpOperation::ListLabels,
+        McpOperation::ListMyRepos,
+        McpOperation::PrReviews,
+        McpOperation::PrReviewComments,
+        McpOperation::SearchRepos,
+        McpOperation::CurrentUser,
+    ];
+}
+
+impl std::fmt::Display for McpOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            McpOperation::ListIssues => write!(f, "list_issues"),
+            McpOperation::ReadIssue => write!(f, "read_issue"),
+            McpOperation::IssueTimeline => write!(f, "issue_timeline"),
+            McpOperation::IssueComments => write!(f, "issue_comments"),
+            McpOperation::SearchIssues => write!(f, "search_issues"),
+            McpOperation::ListPulls => write!(f, "list_pulls"),
+            McpOperation::ListLabels => write!(f, "list_labels"),
+            McpOperation::ListMyRepos => write!(f, "list_my_repos"),
+            McpOperation::PrReviews => write!(f, "pr_reviews"),
+            McpOperation::PrReviewComments => write!(f, "pr_review_comments"),
+            McpOperation::SearchRepos => write!(f, "search_repos"),
+            McpOperation::CurrentUser => write!(f, "current_user"),
+        }
+    }
+}
+
+impl std::str::FromStr for McpOperation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        McpOperation::ALL
+            .into_iter()
+            .find(|op| op.to_string() == s)
+            .ok_or_else(|| format!("Unknown MCP operation: {}", s))
+    }
+}
+
+/// Tool names overriding the defaults, keyed by MCP server and operation
+///
+/// Commands call MCP tools by fixed, per-platform names, but MCP server
+/// versions rename tools (e.g. `get_issue` became `issue_read`). An override
+/// maps an operation to the tool name a given server actually exposes; without
+/// one the built-in default is used.
+#[derive(Debug, Default)]
+pub struct ToolOverrides {
+    tools: RwLock<HashMap<(String, McpOperation), String>>,
+}
+
+impl ToolOverrides {
+    /// Replace all overrides
+    pub fn replace(&self, overrides: impl IntoIterator<Item = (String, McpOperation, String)>) {
+        let tools = overrides
+            .into_iter()
+            .map(|(server, operation, tool)| ((server, operation), tool))
+            .collect();
+        *self.tools.write().unwrap_or_else(|e| e.into_inner()) = tools;
+    }
+
+    /// Set or, with None, remove one override
+    pub fn set(&self, server_name: &str, operation: McpOperation, tool_name: Option<&str>) {
+        let mut tools = self.tools.write().unwrap_or_else(|e| e.into_inner());
+        let key = (server_name.to_string(), operation);
+        match tool_name {
+            Some(tool) => tools.insert(key, tool.to_string()),
+            None => tools.remove(&key),
+        };
+    }
+
+    /// Tool name to call for an operation on a server
+    pub fn resolve(&self, server_name: &str, operation: McpOperation, default: &str) -> String {
+        self.tools
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&(server_name.to_string(), operation))
+            .cloned()
+            .unwrap_or_else(|| default.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_falls_back_to_default() {
+        let overrides = ToolOverrides::default();
+        overrides.replace([(
+            "github".to_string(),
+            McpOperation::ReadIssue,
+            "get_issue".to_string(),
+        )]);
+        assert_eq!(
+            overrides.resolve("github", McpOperation::ReadIssue, "issue_read"),
+            "get_issue"
+        );
+        assert_eq!(
+            overrides.resolve("github-work", McpOperation::ReadIssue, "issue_read"),
+            "issue_read"
+        );
+
+        overrides.set("github", McpOperation::ReadIssue, None);
+        assert_eq!(
+            overrides.resolve("github", McpOperation::ReadIssue, "issue_read"),
+            "issue_read"
+        );
+        assert_eq!("list_my_repos".parse(), Ok(McpOperation::ListMyRepos));
+    }
+}
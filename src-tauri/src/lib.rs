@@ -6,7 +6,12 @@ mod crypto;
 mod db;
 mod error;
 mod grpc;
+mod hooks;
+mod logging;
+mod reattach;
 mod state;
+mod token_monitor;
+mod workflow_result;
 
 use dotenvy::dotenv;
 use state::AppState;
@@ -16,49 +21,162 @@ use tauri::Manager;
 pub fn run() {
     // Initialize tracing
     dotenv().ok();
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive(tracing::Level::INFO.into()),
-        )
-        .init();
+    let log_controller = logging::init();
 
     tracing::info!("Starting Local Code Agent");
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .setup(|app| {
+        .setup(move |app| {
             // Initialize application state inside setup hook where Tokio runtime is available
             let app_state = AppState::init().map_err(|e| {
                 tracing::error!("Failed to initialize application state: {:?}", e);
                 e.to_string()
             })?;
 
+            // Connect to jobworkerp-rs in the background so the first command is fast
+            let grpc = app_state.grpc.clone();
+            let db = app_state.db.clone();
+            tauri::async_runtime::spawn(async move {
+                grpc.warm_up().await;
+                // Warn early about runners deleted by a backend reset
+                match commands::find_missing_runner_repositories(&db, &grpc).await {
+                    Ok(missing) => {
+                        for repo in missing {
+                            tracing::warn!(
+                                "Repository '{}' uses missing MCP server runner '{}'",
+                                repo.name,
+                                repo.mcp_server_name
+                            );
+                        }
+                    }
+                    Err(e) => tracing::debug!("Skipped MCP server audit: {}", e),
+                }
+            });
+
+            // Resume result listeners of jobs that were running at the last exit
+            let listeners = reattach::ListenerRegistry::default();
+            tauri::async_runtime::spawn(reattach::reattach_running_jobs(
+                app.handle().clone(),
+                app_state.db.clone(),
+                app_state.grpc.clone(),
+                listeners.clone(),
+            ));
+
+            // Warn about MCP server tokens that expire while the app is open
+            tauri::async_runtime::spawn(token_monitor::run_token_monitor(
+                app.handle().clone(),
+                app_state.db.clone(),
+                app_state.grpc.clone(),
+            ));
+
             // Register shared state
             app.manage(app_state.db);
             app.manage(app_state.grpc);
             app.manage(app_state.crypto);
+            app.manage(commands::LabelCache::default());
+            app.manage(commands::JobFeed::default());
+            app.manage(listeners);
+            app.manage(log_controller);
 
             Ok(())
         })
         // Register commands
         .invoke_handler(tauri::generate_handler![
             commands::check_jobworkerp_connection,
+            commands::set_grpc_url,
+            commands::trust_backend_cert,
+            commands::rotate_backend_auth,
+            commands::set_log_level,
+            commands::diagnostics,
+            commands::diagnose_connection,
+            commands::self_test,
+            commands::list_app_events,
+            commands::supported_platforms,
+            commands::verify_gitea_url,
+            commands::crypto_status,
+            commands::migrate_key_to_keyring,
+            commands::verify_crypto,
+            commands::db_encryption_status,
+            commands::migrate_to_encrypted_db,
+            commands::check_status_constraint,
+            commands::repair_status_constraint,
             commands::get_app_settings,
             commands::update_app_settings,
+            commands::test_post_job_hook,
             commands::mcp_list_servers,
             commands::mcp_check_connection,
+            commands::mcp_check_image,
+            commands::rename_mcp_server,
+            commands::get_runner_definition,
+            commands::mcp_throttle_status,
+            commands::mcp_circuit_status,
+            commands::reset_mcp_circuit,
+            commands::mcp_server_usage,
+            commands::search_runners,
+            commands::audit_mcp_servers,
+            commands::debug_mcp_call,
+            commands::call_mcp_tool_raw_bytes,
+            commands::benchmark_mcp_tool,
+            commands::get_tool_arg_schema,
+            commands::inspect_runner_schema,
+            commands::list_tool_overrides,
+            commands::set_tool_override,
+            commands::list_results_by_worker,
             commands::mcp_create_runner,
+            commands::import_mcp_settings,
+            commands::export_mcp_settings,
             commands::list_jobs,
             commands::get_job,
+            commands::export_job_report,
+            commands::jobs_by_issue,
+            commands::compare_jobs,
+            commands::distinct_job_statuses,
+            commands::list_jobs_with_prs,
+            commands::agent_metrics,
+            commands::list_backend_jobs,
+            commands::cancel_backend_job,
+            commands::agent_cancel,
+            commands::reattach_job,
+            commands::reparse_job_result,
+            commands::reparse_all_job_results,
+            commands::subscribe_all_jobs,
+            commands::unsubscribe_all_jobs,
+            commands::inspect_worktree,
+            commands::list_workflows,
+            commands::worktree_usage,
+            commands::find_orphan_worktrees,
+            commands::delete_orphan_worktrees,
+            commands::preview_worktree_path,
+            commands::diff_job_branch,
             commands::list_repositories,
             commands::get_repository,
             commands::create_repository,
+            commands::setup_repository,
             commands::delete_repository,
+            commands::rename_repository,
+            commands::get_repository_prompt,
+            commands::set_repository_prompt,
+            commands::set_repository_base_branch,
+            commands::check_base_branch,
+            commands::validate_prompt,
+            commands::estimate_agent_time,
+            commands::check_runner_token,
+            commands::check_all_runner_tokens,
+            commands::list_remote_repositories,
             commands::list_issues,
             commands::get_issue,
+            commands::check_issue_for_agent,
+            commands::list_repository_labels,
+            commands::get_issue_timeline,
+            commands::build_agent_context,
+            commands::search_issues,
+            commands::list_agent_assigned_issues,
+            commands::test_parse_issues,
             commands::list_pulls,
+            commands::get_pr_review_comments,
             commands::find_related_prs,
+            commands::open_external,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,87 @@
+use tracing_subscriber::{prelude::*, reload, EnvFilter, Registry};
+
+use crate::error::AppError;
+
+/// Runtime-adjustable log filter
+///
+/// Wraps the reload handle of the global subscriber so the filter can be
+/// changed from a Tauri command without restarting the app.
+pub struct LogController {
+    handle: reload::Handle<EnvFilter, Registry>,
+}
+
+impl LogController {
+    /// Replace the active filter
+    ///
+    /// Accepts a plain level (`debug`) or a full directive string
+    /// (`local_code_agent_lib=debug,tonic=info`). Returns the applied filter.
+    pub fn set_level(&self, directives: &str) -> Result<String, AppError> {
+        let directives = directives.trim();
+        if directives.is_empty() {
+            return Err(AppError::InvalidInput("Log level cannot be empty".into()));
+        }
+
+        let filter = EnvFilter::try_new(directives)
+            .map_err(|e| AppError::InvalidInput(format!("Invalid log level: {}", e)))?;
+
+        self.handle
+            .reload(filter)
+            .map_err(|e| AppError::Internal(format!("Failed to reload log filter: {}", e)))?;
+
+        tracing::info!("Log level changed to '{}'", directives);
+        Ok(self.current_level())
+    }
+
+    /// Get the active filter as a directive string
+    pub fn current_level(&self) -> String {
+        self.handle
+            .with_current(|filter| filter.to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Build the initial filter from `RUST_LOG` with INFO as the default level
+fn initial_filter() -> EnvFilter {
+    EnvFilter::from_default_env().add_directive(tracing::Level::INFO.into())
+}
+
+/// Install the global tracing subscriber with a reloadable filter
+pub fn init() -> LogController {
+    let (filter, handle) = reload::Layer::new(initial_filter());
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    LogController { handle }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the layer too: the handle only holds a weak reference to it
+    fn test_controller() -> (reload::Layer<EnvFilter, Registry>, LogController) {
+        let (layer, handle) = reload::Layer::new(EnvFilter::new("info"));
+        (layer, LogController { handle })
+    }
+
+    #[test]
+    fn test_set_level() {
+        let (_layer, controller) = test_controller();
+
+        let applied = controller.set_level("debug").unwrap();
+        assert_eq!(applied, "debug");
+        assert_eq!(controller.current_level(), "debug");
+    }
+
+    #[test]
+    fn test_set_level_rejects_invalid_input() {
+        let (_layer, controller) = test_controller();
+
+        assert!(controller.set_level("").is_err());
+        assert!(controller.set_level("not a level[").is_err());
+        assert_eq!(controller.current_level(), "info");
+    }
+}
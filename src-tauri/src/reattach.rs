@@ -0,0 +1,457 @@
+// Reattach to agent jobs left running when the app last exited
+
+use rusqlite::{Connection, TransactionBehavior};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Semaphore;
+
+use crate::commands::{fetch_settings, find_job};
+use crate::db::{append_job_log, record_event, AgentJobStatus, AppEventType, DbPool};
+use crate::error::AppError;
+use crate::grpc::data::result_output_item::Item;
+use crate::grpc::data::ResultOutputItem;
+use crate::grpc::JobworkerpClient;
+use crate::hooks::spawn_post_job_hook;
+use crate::workflow_result::{record_workflow_result, store_raw_result, WorkflowResult};
+
+/// Result streams being opened at the same time during startup
+const MAX_CONCURRENT_REATTACH: usize = 4;
+
+/// Error message of jobs too old to reattach
+const STALE_ON_STARTUP: &str = "stale on startup";
+
+/// Error message of jobs whose stream ended without a workflow result
+const NO_RESULT: &str = "stream ended without a workflow result";
+
+/// `job-status-<id>` payload of an active job that lost its result stream
+const DETACHED: &str = "Detached";
+
+/// Running job to listen to again
+#[derive(Debug, PartialEq)]
+struct RunningJob {
+    id: i64,
+    jobworkerp_job_id: String,
+}
+
+/// Agent jobs that currently have a result listener
+///
+/// Shared by the startup reattach and `reattach_job`, so a job never gets
+/// two listeners writing its log and final status.
+#[derive(Debug, Clone, Default)]
+pub struct ListenerRegistry {
+    jobs: Arc<Mutex<HashSet<i64>>>,
+}
+
+/// A job's claim in the registry, released when dropped
+struct Listener {
+    jobs: Arc<Mutex<HashSet<i64>>>,
+    job_id: i64,
+}
+
+impl ListenerRegistry {
+    /// Claim a job for a new listener, or None if one is already attached
+    fn claim(&self, job_id: i64) -> Option<Listener> {
+        let mut jobs = self.jobs.lock().unwrap_or_else(|e| e.into_inner());
+        jobs.insert(job_id).then(|| Listener {
+            jobs: self.jobs.clone(),
+            job_id,
+        })
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let mut jobs = self.jobs.lock().unwrap_or_else(|e| e.into_inner());
+        jobs.remove(&self.job_id);
+    }
+}
+
+/// Mark running jobs not updated within `window_minutes` as Failed
+///
+/// Returns the number of jobs marked and the remaining running jobs, most
+/// recently updated first.
+fn split_stale_jobs(
+    conn: &Connection,
+    window_minutes: i64,
+) -> Result<(usize, Vec<RunningJob>), AppError> {
+    let stale = conn.execute(
+        &format!(
+            "UPDATE agent_jobs SET status = 'Failed', error_message = ?1,
+                    updated_at = datetime('now')
+             WHERE status IN {} AND updated_at < datetime('now', ?2)",
+            AgentJobStatus::active_sql_list()
+        ),
+        rusqlite::params![STALE_ON_STARTUP, format!("-{} minutes", window_minutes)],
+    )?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, jobworkerp_job_id FROM agent_jobs
+         WHERE status IN {} ORDER BY updated_at DESC, id DESC",
+        AgentJobStatus::active_sql_list()
+    ))?;
+    let recent = stmt
+        .query_map([], |row| {
+            Ok(RunningJob {
+                id: row.get(0)?,
+                jobworkerp_job_id: row.get(1)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((stale, recent))
+}
+
+/// Give a job that is still active its final status
+///
+/// Uses the workflow result when there is one; otherwise the job is marked
+/// Failed with the given error message. Returns None, changing nothing, when
+/// the job already finished some other way (e.g. it was cancelled meanwhile).
+/// The status is read and written in one immediate transaction, so a cancel
+/// committed in between cannot be overwritten.
+fn finish_job(
+    conn: &mut Connection,
+    job_id: i64,
+    outcome: Result<&WorkflowResult, &str>,
+) -> Result<Option<AgentJobStatus>, AppError> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let status: String = tx.query_row(
+        "SELECT status FROM agent_jobs WHERE id = ?1",
+        [job_id],
+        |row| row.get(0),
+    )?;
+    if !status.parse().is_ok_and(AgentJobStatus::is_active) {
+        return Ok(None);
+    }
+    let status = match outcome {
+        Ok(result) => record_workflow_result(&tx, job_id, result)?,
+        Err(message) => {
+            tx.execute(
+                "UPDATE agent_jobs SET status = 'Failed', error_message = ?1,
+                        updated_at = datetime('now')
+                 WHERE id = ?2",
+                rusqlite::params![message, job_id],
+            )?;
+            AgentJobStatus::Failed
+        }
+    };
+    record_event(
+        &tx,
+        AppEventType::JobFinished,
+        &format!("Job {} finished: {}", job_id, status),
+    );
+    tx.commit()?;
+    Ok(Some(status))
+}
+
+/// Finish a job and announce it with `job-status-<id>` and the post-job hook
+fn complete_job(
+    app: &AppHandle,
+    db: &DbPool,
+    job_id: i64,
+    outcome: Result<&WorkflowResult, &str>,
+) -> Result<(), AppError> {
+    let mut conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+    if let Some(status) = finish_job(&mut conn, job_id, outcome)? {
+        let _ = app.emit(&format!("job-status-{}", job_id), status.to_string());
+        spawn_post_job_hook(db, &find_job(&conn, job_id)?);
+    }
+    Ok(())
+}
+
+/// Open a running job's result stream
+///
+/// A job the backend no longer knows is marked Failed, as if it were stale.
+/// On other errors, such as a backend that is not up yet, the job stays
+/// active so it can be reattached later.
+async fn connect_job(
+    app: &AppHandle,
+    db: &DbPool,
+    grpc: &JobworkerpClient,
+    job: &RunningJob,
+) -> Result<tonic::Streaming<ResultOutputItem>, AppError> {
+    match grpc.listen_stream(&job.jobworkerp_job_id).await {
+        Err(e @ AppError::NotFound(_)) => {
+            complete_job(app, db, job.id, Err(&format!("reattach failed: {}", e)))?;
+            Err(e)
+        }
+        connected => connected,
+    }
+}
+
+/// Connect to a running job and forward its output (see `forward_job`)
+async fn reattach_job(
+    app: &AppHandle,
+    db: &DbPool,
+    grpc: &JobworkerpClient,
+    job: &RunningJob,
+) -> Result<(), AppError> {
+    let stream = connect_job(app, db, grpc, job).await?;
+    forward_job(app, db, job, stream).await
+}
+
+/// Forward a running job's remaining output to its log and stream event
+///
+/// When the stream ends, the job gets its final status from the workflow
+/// result, or Failed if none arrived. If the stream breaks off, the agent may
+/// still be running: the job stays active and `job-status-<id>` reports it
+/// as `Detached` until it is reattached.
+async fn forward_job(
+    app: &AppHandle,
+    db: &DbPool,
+    job: &RunningJob,
+    mut stream: tonic::Streaming<ResultOutputItem>,
+) -> Result<(), AppError> {
+    match forward_stream(app, db, job, &mut stream).await {
+        Ok(result) => complete_job(app, db, job.id, result.as_ref().ok_or(NO_RESULT)),
+        Err(e) => {
+            let _ = app.emit(&format!("job-status-{}", job.id), DETACHED);
+            Err(e)
+        }
+    }
+}
+
+/// Forward stream items until the end, returning the workflow result if any
+async fn forward_stream(
+    app: &AppHandle,
+    db: &DbPool,
+    job: &RunningJob,
+    stream: &mut tonic::Streaming<ResultOutputItem>,
+) -> Result<Option<WorkflowResult>, AppError> {
+    let event = format!("job-stream-{}", job.jobworkerp_job_id);
+    let mut result = None;
+
+    while let Some(item) = stream.message().await? {
+        let payload = match item.item {
+            Some(Item::Data(data)) => {
+                let mut conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+                append_job_log(&mut conn, job.id, &String::from_utf8_lossy(&data))?;
+                serde_json::json!({ "type": "Data", "data": data })
+            }
+            Some(Item::FinalCollected(data)) => {
+                // Kept before parsing, so a result that is not understood
+                // can be parsed again after a workflow output fix
+                let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+                store_raw_result(&conn, job.id, &data)?;
+                result = WorkflowResult::parse(&data);
+                if result.is_none() {
+                    tracing::warn!("Job {} reported a result that is not JSON", job.id);
+                }
+                serde_json::json!({ "type": "FinalCollected", "data": data })
+            }
+            Some(Item::End(_)) => {
+                let _ = app.emit(&event, serde_json::json!({ "type": "End" }));
+                break;
+            }
+            None => continue,
+        };
+        let _ = app.emit(&event, payload);
+    }
+    Ok(result)
+}
+
+/// Reattach to one running job whose listener died
+///
+/// The listener runs in the background, as at startup. Fails when the job is
+/// no longer running or a listener is already attached to it.
+pub(crate) fn reattach_stuck_job(
+    app: &AppHandle,
+    db: &DbPool,
+    grpc: &Arc<JobworkerpClient>,
+    listeners: &ListenerRegistry,
+    job_id: i64,
+) -> Result<(), AppError> {
+    let job = {
+        let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+        find_job(&conn, job_id)?
+    };
+    if !job.status.is_active() {
+        return Err(AppError::validation(
+            "job_id",
+            format!("Job {} is not running ({})", job_id, job.status),
+        ));
+    }
+    let listener = listeners.claim(job_id).ok_or_else(|| {
+        AppError::validation("job_id", format!("Job {} is already listened to", job_id))
+    })?;
+    let job = RunningJob {
+        id: job.id,
+        jobworkerp_job_id: job.jobworkerp_job_id,
+    };
+    let (app, db, grpc) = (app.clone(), db.clone(), grpc.clone());
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = reattach_job(&app, &db, &grpc, &job).await {
+            tracing::warn!("Failed to reattach to job {}: {}", job.id, e);
+        }
+        drop(listener);
+    });
+    tracing::info!("Reattaching to job {}", job_id);
+    Ok(())
+}
+
+/// Reattach to recent running jobs and fail the stale ones
+///
+/// Jobs still in a running status are split by age: those updated within
+/// `reattach_window_minutes` get a result listener again, connecting a few
+/// at a time; older ones are assumed lost and marked `Failed` without
+/// contacting the backend. Run once in the background at startup. Errors are
+/// logged, never returned.
+pub async fn reattach_running_jobs(
+    app: AppHandle,
+    db: DbPool,
+    grpc: Arc<JobworkerpClient>,
+    listeners: ListenerRegistry,
+) {
+    let jobs = db
+        .get()
+        .map_err(|e| AppError::Internal(e.to_string()))
+        .and_then(|conn| {
+            let window = fetch_settings(&conn)?.reattach_window_minutes;
+            let (stale, recent) = split_stale_jobs(&conn, window)?;
+            if stale > 0 {
+                record_event(
+                    &conn,
+                    AppEventType::JobFinished,
+                    &format!("{} job(s) marked failed: {}", stale, STALE_ON_STARTUP),
+                );
+            }
+            Ok(recent)
+        });
+    let jobs = match jobs {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            tracing::error!("Failed to load running jobs for reattach: {}", e);
+            return;
+        }
+    };
+    if jobs.is_empty() {
+        return;
+    }
+    tracing::info!("Reattaching to {} running job(s)", jobs.len());
+
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_REATTACH));
+    for job in jobs {
+        // The user may have reattached it already
+        let Some(listener) = listeners.claim(job.id) else {
+            continue;
+        };
+        let (app, db, grpc, semaphore) = (app.clone(), db.clone(), grpc.clone(), semaphore.clone());
+        tauri::async_runtime::spawn(async move {
+            let result = async {
+                // Only connecting takes a slot; the stream may then run for hours
+                let stream = {
+                    let _permit = semaphore
+                        .acquire()
+                        .await
+                        .map_err(|e| AppError::Internal(e.to_string()))?;
+                    connect_job(&app, &db, &grpc, &job).await?
+                };
+                forward_job(&app, &db, &job, stream).await
+            }
+            .await;
+            if let Err(e) = result {
+                tracing::warn!("Failed to reattach to job {}: {}", job.id, e);
+            }
+            drop(listener);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_stale_jobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let conn = pool.get().unwrap();
+        let repository_id = crate::db::test_support::insert_repository(&conn);
+        for (issue, job_id, status, age) in [
+            (1, "j1", "RunningAgent", "-5 minutes"),
+            (2, "j2", "PreparingWorkspace", "-3 hours"),
+            (3, "j3", "Completed", "-3 hours"),
+        ] {
+            conn.execute(
+                "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, datetime('now', ?5))",
+                rusqlite::params![repository_id, issue, job_id, status, age],
+            )
+            .unwrap();
+        }
+
+        let (stale, recent) = split_stale_jobs(&conn, 60).unwrap();
+        assert_eq!(stale, 1);
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].jobworkerp_job_id, "j1");
+
+        let (status, error): (String, Option<String>) = conn
+            .query_row(
+                "SELECT status, error_message FROM agent_jobs WHERE jobworkerp_job_id = 'j2'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(status, "Failed");
+        assert_eq!(error.as_deref(), Some(STALE_ON_STARTUP));
+    }
+
+    #[test]
+    fn test_listener_registry() {
+        let listeners = ListenerRegistry::default();
+        let listener = listeners.claim(1).unwrap();
+        assert!(listeners.claim(1).is_none());
+        assert!(listeners.clone().claim(2).is_some());
+        drop(listener);
+        assert!(listeners.claim(1).is_some());
+    }
+
+    #[test]
+    fn test_finish_job() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let mut conn = pool.get().unwrap();
+        let repository_id = crate::db::test_support::insert_repository(&conn);
+        let insert = |status: &str| {
+            conn.execute(
+                "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status)
+                 VALUES (?1, 1, 'j', ?2)",
+                rusqlite::params![repository_id, status],
+            )
+            .unwrap();
+            conn.last_insert_rowid()
+        };
+        let (failed, ended, cancelled) = (
+            insert("RunningAgent"),
+            insert("CreatingPR"),
+            insert("Cancelled"),
+        );
+
+        let result =
+            WorkflowResult::parse(br#"{"status": "failed", "error": "tests failed"}"#).unwrap();
+        assert_eq!(
+            finish_job(&mut conn, failed, Ok(&result)).unwrap(),
+            Some(AgentJobStatus::Failed)
+        );
+        // A stream without a result is not a success
+        assert_eq!(
+            finish_job(&mut conn, ended, Err(NO_RESULT)).unwrap(),
+            Some(AgentJobStatus::Failed)
+        );
+        let error: Option<String> = conn
+            .query_row(
+                "SELECT error_message FROM agent_jobs WHERE id = ?1",
+                [ended],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(error.as_deref(), Some(NO_RESULT));
+        // Finished jobs keep their status and are no longer stale candidates
+        assert_eq!(
+            finish_job(&mut conn, cancelled, Err(NO_RESULT)).unwrap(),
+            None
+        );
+        assert_eq!(finish_job(&mut conn, ended, Err(NO_RESULT)).unwrap(), None);
+        let (stale, recent) = split_stale_jobs(&conn, 0).unwrap();
+        assert_eq!((stale, recent.len()), (0, 0));
+    }
+}
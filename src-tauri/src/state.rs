@@ -1,9 +1,12 @@
 use std::sync::Arc;
+use zeroize::Zeroizing;
 
 use crate::crypto::TokenCrypto;
 use crate::db::DbPool;
 use crate::error::AppError;
-use crate::grpc::{default_grpc_url, JobworkerpClient};
+use crate::grpc::tls::{load_ca_file, TrustedCa};
+use crate::grpc::tool_overrides::McpOperation;
+use crate::grpc::{default_grpc_url, ClientConfig, JobworkerpClient};
 
 /// Application state shared across Tauri commands
 pub struct AppState {
@@ -16,27 +19,164 @@ impl AppState {
     /// Create new application state
     pub fn new(db: DbPool, grpc_url: Option<&str>) -> Result<Self, AppError> {
         let crypto = TokenCrypto::new().map_err(|e| AppError::Crypto(e.to_string()))?;
+        Self::with_crypto(db, crypto, grpc_url)
+    }
 
-        let default_url = default_grpc_url();
-        let url = grpc_url.unwrap_or(&default_url);
-        let grpc = JobworkerpClient::new_shared(url)?;
+    fn with_crypto(
+        db: DbPool,
+        crypto: TokenCrypto,
+        grpc_url: Option<&str>,
+    ) -> Result<Self, AppError> {
+        let url = match grpc_url {
+            Some(url) => url.to_string(),
+            None => startup_grpc_url(&db),
+        };
+        let config = ClientConfig {
+            trusted_ca: startup_trusted_ca(&db),
+            ..ClientConfig::from_env()?
+        };
+        let mut client = JobworkerpClient::with_config(&url, config)?;
+        if let Some(token) = startup_auth_token(&db, &crypto) {
+            client = client.with_auth_token(&token)?;
+        }
+        let grpc = Arc::new(client);
+        grpc.set_mcp_calls_per_minute(startup_mcp_calls_per_minute(&db));
+        grpc.tool_overrides().replace(startup_tool_overrides(&db));
 
         Ok(Self { db, crypto, grpc })
     }
 
     /// Initialize with default configuration
     pub fn init() -> Result<Self, AppError> {
-        let db = crate::db::init_database(None)?;
-        Self::new(db, None)
+        Self::init_with_config(None, None)
     }
 
     /// Initialize with custom database path and gRPC URL
+    ///
+    /// The crypto key is loaded first so an encrypted database can be opened.
     pub fn init_with_config(
         db_path: Option<&std::path::Path>,
         grpc_url: Option<&str>,
     ) -> Result<Self, AppError> {
-        let db = crate::db::init_database(db_path)?;
-        Self::new(db, grpc_url)
+        let crypto = TokenCrypto::new().map_err(|e| AppError::Crypto(e.to_string()))?;
+        let db = crate::db::init_database_with_key(
+            db_path,
+            database_key(&crypto).as_deref().map(String::as_str),
+        )?;
+        Self::with_crypto(db, crypto, grpc_url)
+    }
+}
+
+/// Backend URL used at startup
+///
+/// `JOBWORKERP_GRPC_URL` takes precedence over the URL saved from the UI.
+fn startup_grpc_url(db: &DbPool) -> String {
+    if std::env::var("JOBWORKERP_GRPC_URL").is_ok() {
+        return default_grpc_url();
+    }
+    db.get()
+        .ok()
+        .and_then(|conn| {
+            conn.query_row(
+                "SELECT grpc_server_url FROM app_settings WHERE id = 1",
+                [],
+                |row| row.get::<_, String>(0),
+            )
+            .ok()
+        })
+        .unwrap_or_else(default_grpc_url)
+}
+
+/// Auth token saved with `rotate_backend_auth`
+///
+/// `JOBWORKERP_AUTH_TOKEN` takes precedence. A token that no longer decrypts
+/// is logged and ignored.
+fn startup_auth_token(db: &DbPool, crypto: &TokenCrypto) -> Option<Zeroizing<String>> {
+    if std::env::var("JOBWORKERP_AUTH_TOKEN").is_ok() {
+        return None;
+    }
+    let encrypted: Vec<u8> = db
+        .get()
+        .ok()?
+        .query_row(
+            "SELECT grpc_auth_token_encrypted FROM app_settings WHERE id = 1",
+            [],
+            |row| row.get::<_, Option<Vec<u8>>>(0),
+        )
+        .ok()??;
+    crypto
+        .decrypt(&encrypted)
+        .inspect_err(|e| tracing::warn!("Ignoring saved backend auth token: {}", e))
+        .ok()
+}
+
+/// Saved MCP call limit, or 0 (unthrottled) when it cannot be read
+fn startup_mcp_calls_per_minute(db: &DbPool) -> u32 {
+    db.get()
+        .ok()
+        .and_then(|conn| {
+            conn.query_row(
+                "SELECT mcp_calls_per_minute FROM app_settings WHERE id = 1",
+                [],
+                |row| row.get::<_, u32>(0),
+            )
+            .ok()
+        })
+        .unwrap_or(0)
+}
+
+/// Saved MCP tool overrides; unreadable or unknown entries are skipped
+fn startup_tool_overrides(db: &DbPool) -> Vec<(String, McpOperation, String)> {
+    let Ok(conn) = db.get() else {
+        return Vec::new();
+    };
+    let rows = conn
+        .prepare("SELECT mcp_server_name, operation, tool_name FROM mcp_tool_overrides")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()
+        })
+        .unwrap_or_default();
+    rows.into_iter()
+        .filter_map(|(server, operation, tool)| Some((server, operation.parse().ok()?, tool)))
+        .collect()
+}
+
+/// CA certificates saved with `trust_backend_cert`
+///
+/// A file that is gone or no longer valid is logged and ignored, so the app
+/// still starts and the user can pick a new certificate.
+fn startup_trusted_ca(db: &DbPool) -> Option<TrustedCa> {
+    let path: String = db
+        .get()
+        .ok()?
+        .query_row(
+            "SELECT grpc_ca_cert_path FROM app_settings WHERE id = 1",
+            [],
+            |row| row.get::<_, Option<String>>(0),
+        )
+        .ok()??;
+    load_ca_file(std::path::Path::new(&path))
+        .inspect_err(|e| tracing::warn!("Ignoring trusted backend certificate {}: {}", path, e))
+        .ok()
+}
+
+/// SQLCipher key for the database, when built with the `sqlcipher` feature
+fn database_key(crypto: &TokenCrypto) -> Option<zeroize::Zeroizing<String>> {
+    #[cfg(feature = "sqlcipher")]
+    {
+        Some(crypto.database_key_hex())
+    }
+    #[cfg(not(feature = "sqlcipher"))]
+    {
+        let _ = crypto;
+        None
     }
 }
 
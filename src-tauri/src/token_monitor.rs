@@ -0,0 +1,232 @@
+// Periodic check of the tokens in MCP server runners
+
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::{fetch_settings, runner_token_report};
+use crate::db::{get_repository_by_id, DbPool};
+use crate::error::AppError;
+use crate::grpc::JobworkerpClient;
+
+/// Tauri event emitted when a runner's token stops being accepted
+pub const TOKEN_EXPIRED_EVENT: &str = "token-expired";
+
+/// Wait before the first check, so it does not compete with startup work
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+
+/// How often the settings are re-read while checks are disabled
+const DISABLED_RECHECK: Duration = Duration::from_secs(15 * 60);
+
+/// Pause between two runners during a background check
+const BACKGROUND_CHECK_SPACING: Duration = Duration::from_secs(10);
+
+/// Token check outcome of one MCP server runner
+#[derive(Debug, Clone, Serialize)]
+pub struct RunnerTokenStatus {
+    pub mcp_server_name: String,
+    pub repository_ids: Vec<i64>,
+    /// "valid" or "invalid"; None when the check could not be performed
+    pub token_status: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Payload of the `token-expired` event
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenExpiredEvent {
+    pub mcp_server_name: String,
+    pub repository_ids: Vec<i64>,
+}
+
+/// Repository IDs grouped by the MCP server runner they use
+fn repositories_by_runner(conn: &Connection) -> Result<Vec<(String, Vec<i64>)>, AppError> {
+    let mut stmt =
+        conn.prepare("SELECT mcp_server_name, id FROM repositories ORDER BY mcp_server_name, id")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut groups: Vec<(String, Vec<i64>)> = Vec::new();
+    for (name, id) in rows {
+        match groups.last_mut() {
+            Some((last, ids)) if *last == name => ids.push(id),
+            _ => groups.push((name, vec![id])),
+        }
+    }
+    Ok(groups)
+}
+
+/// Store a token check result for repositories
+///
+/// Returns whether the token just became invalid, i.e. some repository was
+/// not already marked invalid.
+fn record_token_status(
+    conn: &mut Connection,
+    repository_ids: &[i64],
+    valid: bool,
+) -> Result<bool, AppError> {
+    let status = if valid { "valid" } else { "invalid" };
+    let tx = conn.transaction()?;
+    let mut newly_invalid = false;
+    for id in repository_ids {
+        let previous: Option<String> = tx
+            .query_row(
+                "SELECT token_status FROM repositories WHERE id = ?1",
+                [id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        tx.execute(
+            "UPDATE repositories SET token_status = ?1, token_checked_at = datetime('now')
+             WHERE id = ?2",
+            rusqlite::params![status, id],
+        )?;
+        newly_invalid |= !valid && previous.as_deref() != Some("invalid");
+    }
+    tx.commit()?;
+    Ok(newly_invalid)
+}
+
+/// Check the token of every runner used by a repository
+///
+/// Runners are checked one after another, `spacing` apart, to keep API usage
+/// low. The result is stored in `repositories.token_status`, and a token that
+/// turns invalid emits `token-expired`. A check that cannot be performed
+/// (runner missing, platform unreachable) leaves the stored status as it is.
+pub async fn check_runner_tokens(
+    app: &AppHandle,
+    db: &DbPool,
+    grpc: &JobworkerpClient,
+    spacing: Duration,
+) -> Result<Vec<RunnerTokenStatus>, AppError> {
+    let groups = {
+        let conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+        repositories_by_runner(&conn)?
+    };
+
+    let mut statuses = Vec::with_capacity(groups.len());
+    for (index, (mcp_server_name, repository_ids)) in groups.into_iter().enumerate() {
+        if index > 0 {
+            tokio::time::sleep(spacing).await;
+        }
+        let report = match get_repository_by_id(db, repository_ids[0]) {
+            Ok(repo) => runner_token_report(grpc, &repo).await,
+            Err(e) => Err(e),
+        };
+        let valid = match report {
+            Ok(report) => report.valid,
+            Err(e) => {
+                tracing::debug!("Skipped token check of '{}': {}", mcp_server_name, e);
+                statuses.push(RunnerTokenStatus {
+                    mcp_server_name,
+                    repository_ids,
+                    token_status: None,
+                    error: Some(e.to_string()),
+                });
+                continue;
+            }
+        };
+
+        let newly_invalid = {
+            let mut conn = db.get().map_err(|e| AppError::Internal(e.to_string()))?;
+            record_token_status(&mut conn, &repository_ids, valid)?
+        };
+        if newly_invalid {
+            tracing::warn!(
+                "Token of MCP server '{}' is no longer accepted",
+                mcp_server_name
+            );
+            let _ = app.emit(
+                TOKEN_EXPIRED_EVENT,
+                TokenExpiredEvent {
+                    mcp_server_name: mcp_server_name.clone(),
+                    repository_ids: repository_ids.clone(),
+                },
+            );
+        }
+        statuses.push(RunnerTokenStatus {
+            mcp_server_name,
+            repository_ids,
+            token_status: Some(if valid { "valid" } else { "invalid" }.to_string()),
+            error: None,
+        });
+    }
+    Ok(statuses)
+}
+
+/// Check runner tokens every `token_check_interval_minutes`, forever
+///
+/// Each runner used by a repository has its token checked once, however many
+/// repositories share it, so the UI can warn before an agent run fails on an
+/// expired token. Run once in the background at startup. Errors are logged,
+/// never returned.
+pub async fn run_token_monitor(app: AppHandle, db: DbPool, grpc: Arc<JobworkerpClient>) {
+    tokio::time::sleep(STARTUP_DELAY).await;
+    loop {
+        let interval = db
+            .get()
+            .map_err(|e| AppError::Internal(e.to_string()))
+            .and_then(|conn| fetch_settings(&conn))
+            .map(|settings| settings.token_check_interval_minutes)
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to read token check interval: {}", e);
+                0
+            });
+        if interval <= 0 {
+            tokio::time::sleep(DISABLED_RECHECK).await;
+            continue;
+        }
+
+        if let Err(e) = check_runner_tokens(&app, &db, &grpc, BACKGROUND_CHECK_SPACING).await {
+            tracing::warn!("Background token check failed: {}", e);
+        }
+        tokio::time::sleep(Duration::from_secs(interval as u64 * 60)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_token_status() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let mut conn = pool.get().unwrap();
+        for (server, repo_name) in [("github", "app"), ("github", "site"), ("gitea", "tool")] {
+            conn.execute(
+                "INSERT INTO repositories (mcp_server_name, platform, base_url, name, url, owner, repo_name, repo_slug)
+                 VALUES (?1, 'GitHub', 'https://api.github.com', ?2, ?2, 'octo', ?2, ?2)",
+                rusqlite::params![server, repo_name],
+            )
+            .unwrap();
+        }
+
+        let groups = repositories_by_runner(&conn).unwrap();
+        assert_eq!(
+            groups,
+            vec![
+                ("gitea".to_string(), vec![3]),
+                ("github".to_string(), vec![1, 2])
+            ]
+        );
+
+        assert!(!record_token_status(&mut conn, &[1, 2], true).unwrap());
+        assert!(record_token_status(&mut conn, &[1, 2], false).unwrap());
+        // Already invalid: no second alert
+        assert!(!record_token_status(&mut conn, &[1, 2], false).unwrap());
+        let status: Option<String> = conn
+            .query_row(
+                "SELECT token_status FROM repositories WHERE id = 2",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(status.as_deref(), Some("invalid"));
+    }
+}
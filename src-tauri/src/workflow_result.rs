@@ -0,0 +1,431 @@
+// Final result reported by the code agent workflow
+use rusqlite::{Connection, OptionalExtension, TransactionBehavior};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::commands::update_job_with_pr;
+use crate::db::{record_event, AgentJobStatus, AppEventType};
+use crate::error::AppError;
+
+/// Parsed `FinalCollected` output of the code agent workflow
+///
+/// Parsing is lenient so older and newer workflows both work: unknown fields
+/// are ignored, missing ones are None, and a value of the wrong type is
+/// treated as missing. Numbers may also be given as numeric strings.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct WorkflowResult {
+    /// `success` or `failed`, when reported
+    pub status: Option<String>,
+    pub pr_number: Option<i32>,
+    pub pr_url: Option<String>,
+    pub no_changes: bool,
+    pub error: Option<String>,
+    pub commit_sha: Option<String>,
+    pub files_changed: Option<i64>,
+    pub branch: Option<String>,
+    pub summary: Option<String>,
+}
+
+fn text_field(doc: &Value, key: &str) -> Option<String> {
+    doc.get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn int_field(doc: &Value, key: &str) -> Option<i64> {
+    match doc.get(key)? {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Error text from a string or a Serverless Workflow error object
+fn error_field(doc: &Value) -> Option<String> {
+    match doc.get("error")? {
+        Value::String(s) => Some(s.trim().to_string()).filter(|s| !s.is_empty()),
+        error @ Value::Object(_) => ["detail", "message", "title"]
+            .iter()
+            .find_map(|key| text_field(error, key))
+            .or_else(|| Some(error.to_string())),
+        _ => None,
+    }
+}
+
+impl WorkflowResult {
+    /// Parse the workflow output; None if it is not a JSON object
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let doc: Value = serde_json::from_slice(data).ok()?;
+        if !doc.is_object() {
+            return None;
+        }
+        Some(Self {
+            status: text_field(&doc, "status"),
+            pr_number: int_field(&doc, "pr_number").and_then(|n| i32::try_from(n).ok()),
+            pr_url: text_field(&doc, "pr_url"),
+            no_changes: doc
+                .get("no_changes")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            error: error_field(&doc),
+            commit_sha: text_field(&doc, "commit_sha"),
+            files_changed: int_field(&doc, "files_changed"),
+            branch: text_field(&doc, "branch"),
+            summary: text_field(&doc, "summary"),
+        })
+    }
+
+    /// Job status this result finishes the job with
+    pub fn final_status(&self) -> AgentJobStatus {
+        let failed = self
+            .status
+            .as_deref()
+            .is_some_and(|s| s.eq_ignore_ascii_case("failed") || s.eq_ignore_ascii_case("error"));
+        if failed || (self.error.is_some() && self.pr_number.is_none()) {
+            AgentJobStatus::Failed
+        } else if self.pr_number.is_some() {
+            AgentJobStatus::PrCreated
+        } else if self.no_changes {
+            AgentJobStatus::NoChanges
+        } else {
+            AgentJobStatus::Completed
+        }
+    }
+}
+
+/// Store a workflow result on its job and set the job's final status
+pub fn record_workflow_result(
+    conn: &Connection,
+    job_id: i64,
+    result: &WorkflowResult,
+) -> Result<AgentJobStatus, AppError> {
+    conn.execute(
+        "UPDATE agent_jobs SET commit_sha = ?2, files_changed = ?3, summary = ?4,
+                               branch_name = COALESCE(?5, branch_name),
+                               updated_at = datetime('now')
+         WHERE id = ?1",
+        rusqlite::params![
+            job_id,
+            result.commit_sha,
+            result.files_changed,
+            result.summary,
+            result.branch
+        ],
+    )?;
+
+    let status = result.final_status();
+    match (status, result.pr_number) {
+        (AgentJobStatus::PrCreated, Some(pr_number)) => {
+            update_job_with_pr(conn, job_id, pr_number, result.pr_url.as_deref())?;
+        }
+        _ => {
+            conn.execute(
+                "UPDATE agent_jobs SET status = ?2, error_message = COALESCE(?3, error_message),
+                                       updated_at = datetime('now')
+                 WHERE id = ?1",
+                rusqlite::params![job_id, status.to_string(), result.error],
+            )?;
+        }
+    }
+    Ok(status)
+}
+
+/// Keep a job's raw workflow output so it can be parsed again later
+pub fn store_raw_result(conn: &Connection, job_id: i64, data: &[u8]) -> Result<(), AppError> {
+    conn.execute(
+        "UPDATE agent_jobs SET raw_result = ?2 WHERE id = ?1",
+        rusqlite::params![job_id, data],
+    )?;
+    Ok(())
+}
+
+/// Whether a job's status came from its workflow result, so it can be parsed again
+fn is_reparsable(status: AgentJobStatus) -> bool {
+    !status.is_active() && !matches!(status, AgentJobStatus::Merged | AgentJobStatus::Cancelled)
+}
+
+/// Job fields a workflow result sets, to tell whether parsing again changed them
+fn result_fields(conn: &Connection, job_id: i64) -> Result<Vec<rusqlite::types::Value>, AppError> {
+    Ok(conn.query_row(
+        "SELECT status, pr_number, pr_url, commit_sha, files_changed, branch_name, summary,
+                error_message
+         FROM agent_jobs WHERE id = ?1",
+        [job_id],
+        |row| (0..8).map(|i| row.get(i)).collect(),
+    )?)
+}
+
+/// Apply a re-parsed result to a job; true if any of its fields changed
+fn apply_reparsed_result(
+    conn: &Connection,
+    job_id: i64,
+    status: AgentJobStatus,
+    result: &WorkflowResult,
+) -> Result<bool, AppError> {
+    let before = result_fields(conn, job_id)?;
+    // The previous outcome's error no longer applies
+    conn.execute(
+        "UPDATE agent_jobs SET error_message = NULL WHERE id = ?1",
+        [job_id],
+    )?;
+    let new_status = record_workflow_result(conn, job_id, result)?;
+    if new_status != status {
+        record_event(
+            conn,
+            AppEventType::JobFinished,
+            &format!("Job {} re-parsed: {} -> {}", job_id, status, new_status),
+        );
+    }
+    Ok(result_fields(conn, job_id)? != before)
+}
+
+/// Parse a finished job's stored workflow output again and apply the result
+///
+/// Fails when the job has no stored output or it still does not parse. Active
+/// jobs are left alone, as are Merged and Cancelled ones, whose status did
+/// not come from the result.
+pub fn reparse_stored_result(
+    conn: &mut Connection,
+    job_id: i64,
+) -> Result<WorkflowResult, AppError> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let (status, raw): (String, Option<Vec<u8>>) = tx
+        .query_row(
+            "SELECT status, raw_result FROM agent_jobs WHERE id = ?1",
+            [job_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound(format!("Job {} not found", job_id)))?;
+    let status: AgentJobStatus = status.parse().map_err(AppError::Internal)?;
+    if !is_reparsable(status) {
+        return Err(AppError::validation(
+            "job_id",
+            format!("Job {} result cannot be parsed again ({})", job_id, status),
+        ));
+    }
+    let raw = raw.ok_or_else(|| {
+        AppError::NotFound(format!("Job {} has no stored workflow result", job_id))
+    })?;
+    let result = WorkflowResult::parse(&raw).ok_or_else(|| {
+        AppError::InvalidInput(format!("Job {} result is still not JSON", job_id))
+    })?;
+
+    apply_reparsed_result(&tx, job_id, status, &result)?;
+    tx.commit()?;
+    Ok(result)
+}
+
+/// Parse every finished job's stored workflow output again
+///
+/// Covers the jobs `reparse_stored_result` accepts; output that still does
+/// not parse is skipped. Returns how many jobs changed.
+pub fn reparse_all_stored_results(conn: &mut Connection) -> Result<usize, AppError> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let jobs: Vec<(i64, String, Vec<u8>)> = tx
+        .prepare(&format!(
+            "SELECT id, status, raw_result FROM agent_jobs
+             WHERE raw_result IS NOT NULL AND status IN {}
+             ORDER BY id",
+            AgentJobStatus::terminal_sql_list()
+        ))?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let mut changed = 0;
+    for (job_id, status, raw) in jobs {
+        let status: AgentJobStatus = status.parse().map_err(AppError::Internal)?;
+        if !is_reparsable(status) {
+            continue;
+        }
+        let Some(result) = WorkflowResult::parse(&raw) else {
+            tracing::warn!("Job {} result is still not JSON; skipping it", job_id);
+            continue;
+        };
+        if apply_reparsed_result(&tx, job_id, status, &result)? {
+            changed += 1;
+        }
+    }
+    tx.commit()?;
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_is_lenient() {
+        let result = WorkflowResult::parse(
+            br#"{"status": "success", "pr_number": "12", "pr_url": "https://github.com/octo/app/pull/12",
+                 "files_changed": 3, "summary": 5, "reviewers": ["octo"]}"#,
+        )
+        .unwrap();
+        assert_eq!(result.pr_number, Some(12));
+        assert_eq!(result.files_changed, Some(3));
+        assert_eq!(result.summary, None);
+        assert_eq!(result.final_status(), AgentJobStatus::PrCreated);
+
+        let failed = WorkflowResult::parse(
+            br#"{"status": "failed", "error": {"title": "Agent failed", "detail": "tests failed"}}"#,
+        )
+        .unwrap();
+        assert_eq!(failed.error.as_deref(), Some("tests failed"));
+        assert_eq!(failed.final_status(), AgentJobStatus::Failed);
+
+        let unchanged = WorkflowResult::parse(br#"{"no_changes": true}"#).unwrap();
+        assert_eq!(unchanged.final_status(), AgentJobStatus::NoChanges);
+        assert_eq!(
+            WorkflowResult::parse(b"{}").unwrap().final_status(),
+            AgentJobStatus::Completed
+        );
+        assert_eq!(WorkflowResult::parse(b"done"), None);
+    }
+
+    #[test]
+    fn test_record_workflow_result() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let conn = pool.get().unwrap();
+        let repository_id = crate::db::test_support::insert_repository(&conn);
+        conn.execute(
+            "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status, branch_name)
+             VALUES (?1, 7, 'j1', 'CreatingPR', 'issue-7')",
+            [repository_id],
+        )
+        .unwrap();
+        let job_id = conn.last_insert_rowid();
+
+        let result = WorkflowResult::parse(
+            br#"{"status": "success", "pr_number": 12, "commit_sha": "abc123", "files_changed": 2}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            record_workflow_result(&conn, job_id, &result).unwrap(),
+            AgentJobStatus::PrCreated
+        );
+        let row: (
+            String,
+            Option<String>,
+            Option<String>,
+            Option<i64>,
+            Option<String>,
+        ) = conn
+            .query_row(
+                "SELECT status, pr_url, commit_sha, files_changed, branch_name
+                 FROM agent_jobs WHERE id = ?1",
+                [job_id],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                    ))
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            row,
+            (
+                "PrCreated".to_string(),
+                Some("https://github.com/octo/app/pull/12".to_string()),
+                Some("abc123".to_string()),
+                Some(2),
+                Some("issue-7".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn test_reparse_stored_result() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let mut conn = pool.get().unwrap();
+        let repository_id = crate::db::test_support::insert_repository(&conn);
+        conn.execute(
+            "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status, error_message)
+             VALUES (?1, 7, 'j1', 'Failed', 'stream ended without a workflow result')",
+            [repository_id],
+        )
+        .unwrap();
+        let job_id = conn.last_insert_rowid();
+
+        assert!(matches!(
+            reparse_stored_result(&mut conn, job_id),
+            Err(AppError::NotFound(_))
+        ));
+        store_raw_result(&conn, job_id, b"done").unwrap();
+        assert!(matches!(
+            reparse_stored_result(&mut conn, job_id),
+            Err(AppError::InvalidInput(_))
+        ));
+
+        store_raw_result(
+            &conn,
+            job_id,
+            br#"{"status": "success", "no_changes": true}"#,
+        )
+        .unwrap();
+        let result = reparse_stored_result(&mut conn, job_id).unwrap();
+        assert!(result.no_changes);
+        let (status, error): (String, Option<String>) = conn
+            .query_row(
+                "SELECT status, error_message FROM agent_jobs WHERE id = ?1",
+                [job_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((status.as_str(), error), ("NoChanges", None));
+
+        conn.execute(
+            "UPDATE agent_jobs SET status = 'Running' WHERE id = ?1",
+            [job_id],
+        )
+        .unwrap();
+        assert!(matches!(
+            reparse_stored_result(&mut conn, job_id),
+            Err(AppError::Validation { .. })
+        ));
+    }
+
+    #[test]
+    fn test_reparse_all_stored_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = crate::db::init_database(Some(&dir.path().join("test.db"))).unwrap();
+        let mut conn = pool.get().unwrap();
+        let repository_id = crate::db::test_support::insert_repository(&conn);
+        let insert = |job: &str, status: &str, raw: &[u8]| {
+            conn.execute(
+                "INSERT INTO agent_jobs (repository_id, issue_number, jobworkerp_job_id, status, raw_result)
+                 VALUES (?1, 7, ?2, ?3, ?4)",
+                rusqlite::params![repository_id, job, status, raw],
+            )
+            .unwrap();
+            conn.last_insert_rowid()
+        };
+        let misparsed = insert("j1", "Failed", br#"{"no_changes": true}"#);
+        let unchanged = insert("j2", "NoChanges", br#"{"no_changes": true}"#);
+        let not_json = insert("j3", "Failed", b"done");
+        let cancelled = insert("j4", "Cancelled", br#"{"no_changes": true}"#);
+        let running = insert("j5", "Running", br#"{"no_changes": true}"#);
+
+        assert_eq!(reparse_all_stored_results(&mut conn).unwrap(), 1);
+        let status = |job_id: i64| -> String {
+            conn.query_row(
+                "SELECT status FROM agent_jobs WHERE id = ?1",
+                [job_id],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        assert_eq!(status(misparsed), "NoChanges");
+        assert_eq!(status(unchanged), "NoChanges");
+        assert_eq!(status(not_json), "Failed");
+        assert_eq!(status(cancelled), "Cancelled");
+        assert_eq!(status(running), "Running");
+    }
+}
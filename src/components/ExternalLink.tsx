@@ -1,5 +1,5 @@
-import { openUrl } from "@tauri-apps/plugin-opener";
 import type { ReactNode, MouseEvent } from "react";
+import { openExternal } from "@/lib/tauri/commands";
 
 interface ExternalLinkProps {
   href: string;
@@ -21,7 +21,8 @@ function isAllowedUrl(href: string): boolean {
 /**
  * A link component that opens URLs in the system browser.
  * In Tauri apps, regular <a> tags with target="_blank" don't work as expected.
- * This component uses tauri-plugin-opener to open links externally.
+ * This component opens links externally via the `open_external` command,
+ * which validates the URL again before calling tauri-plugin-opener.
  *
  * Security: Only http: and https: URLs are allowed. Other schemes (javascript:,
  * file:, data:, etc.) are blocked and rendered as non-clickable text.
@@ -32,7 +33,7 @@ export function ExternalLink({ href, children, className }: ExternalLinkProps) {
   const handleClick = (e: MouseEvent<HTMLAnchorElement>) => {
     e.preventDefault();
     if (isAllowed) {
-      openUrl(href).catch((err) => {
+      openExternal(href).catch((err) => {
         console.error("Failed to open URL:", href, err);
       });
     }
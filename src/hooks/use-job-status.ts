@@ -211,6 +211,7 @@ export function getJobStatusLabel(status: AgentJob["status"]): string {
     PrCreated: "PR Created",
     Merged: "Merged",
     Completed: "Completed",
+    NoChanges: "No Changes",
     Failed: "Failed",
     Cancelled: "Cancelled",
   };
@@ -238,6 +239,7 @@ export function getJobStatusColor(
     case "Failed":
       return "danger";
     case "Cancelled":
+    case "NoChanges":
       return "warning";
     default:
       return "default";
@@ -10,8 +10,18 @@ import type {
   CreateRepositoryRequest,
   McpServerInfo,
   Issue,
-  PullRequest,
+  PaginatedPullRequests,
+  PaginatedRemoteRepositories,
+  RelatedPullRequests,
+  ReviewComment,
+  TimelineEvent,
+  Label,
+  PlatformInfo,
   AgentJob,
+  IssueJobSummary,
+  JobWithPr,
+  AgentJobStatus,
+  AppEvent,
 } from "@/types/models";
 
 // ============================================================================
@@ -24,17 +34,42 @@ export interface AppSettings {
   default_base_branch: string;
   agent_timeout_minutes: number;
   sync_interval_minutes: number;
+  /** Webhook URL or command run when an agent job finishes */
+  post_job_hook: string | null;
+  /** MCP tool calls allowed per minute for each server; 0 means unlimited */
+  mcp_calls_per_minute: number;
+  /** Longest prompt (default and custom combined) accepted for an agent run */
+  max_prompt_length: number;
+  /** Running jobs updated within this many minutes are reattached at startup */
+  reattach_window_minutes: number;
+  token_check_interval_minutes: number;
   grpc_server_url: string;
   locale: string;
   created_at: string;
   updated_at: string;
 }
 
+/**
+ * Result of running the post-job hook
+ */
+export interface HookOutcome {
+  success: boolean;
+  http_status: number | null;
+  exit_code: number | null;
+  output: string;
+}
+
 export interface UpdateAppSettingsRequest {
   worktree_base_path?: string;
   default_base_branch?: string;
   agent_timeout_minutes?: number;
   sync_interval_minutes?: number;
+  /** An empty string removes the hook */
+  post_job_hook?: string;
+  mcp_calls_per_minute?: number;
+  max_prompt_length?: number;
+  reattach_window_minutes?: number;
+  token_check_interval_minutes?: number;
   grpc_server_url?: string;
   locale?: string;
 }
@@ -50,6 +85,122 @@ export function checkJobworkerpConnection(): Promise<boolean> {
   return invoke<boolean>("check_jobworkerp_connection");
 }
 
+/**
+ * Switch to a different jobworkerp-rs backend URL
+ *
+ * Rejects with the connection error and keeps the current URL if the new
+ * backend cannot be reached.
+ */
+export function setGrpcUrl(url: string): Promise<string> {
+  return invoke<string>("set_grpc_url", { url });
+}
+
+/**
+ * CA certificate file trusted for the backend
+ */
+export interface TrustedCertInfo {
+  path: string;
+  certificate_count: number;
+  /** Earliest expiry among the certificates (RFC 3339) */
+  not_after: string;
+}
+
+/**
+ * Trust a PEM CA certificate for an https backend with a self-signed
+ * certificate. Rejects if the file is invalid or expired, or the TLS
+ * handshake still fails. Pass null to remove the trusted certificate.
+ */
+export function trustBackendCert(
+  certPath: string | null
+): Promise<TrustedCertInfo | null> {
+  return invoke<TrustedCertInfo | null>("trust_backend_cert", { certPath });
+}
+
+/**
+ * Switch the backend auth token. The token is only saved (encrypted) if the
+ * backend accepts it; otherwise the previous token stays in use.
+ */
+export function rotateBackendAuth(newToken: string): Promise<void> {
+  return invoke<void>("rotate_backend_auth", { newToken });
+}
+
+export type DiagnosticStepKind = "dns" | "tcp" | "tls" | "grpc" | "auth";
+
+/**
+ * Result of one connection diagnostic step
+ */
+export interface DiagnosticStep {
+  step: DiagnosticStepKind;
+  outcome: "passed" | "failed" | "skipped";
+  duration_ms: number;
+  detail: string;
+}
+
+export interface ConnectionDiagnostics {
+  url: string;
+  steps: DiagnosticStep[];
+  /** First step that failed, or null when the backend is fully usable */
+  failed_step: DiagnosticStepKind | null;
+  total_ms: number;
+}
+
+/**
+ * Check the backend connection step by step (DNS, TCP, TLS, gRPC, auth).
+ * Each step is also emitted as it completes; see `listenConnectionDiagnostics`.
+ */
+export function diagnoseConnection(): Promise<ConnectionDiagnostics> {
+  return invoke<ConnectionDiagnostics>("diagnose_connection");
+}
+
+export type SelfTestCheck =
+  | "crypto"
+  | "database"
+  | "backend"
+  | "mcp_issues"
+  | "workflows";
+
+export interface SelfTestResult {
+  check: SelfTestCheck;
+  outcome: "passed" | "failed" | "skipped";
+  duration_ms: number;
+  detail: string;
+}
+
+export interface SelfTestReport {
+  checks: SelfTestResult[];
+  passed: boolean;
+}
+
+/**
+ * Check crypto, database, backend, MCP access and workflow files in one go
+ */
+export function selfTest(): Promise<SelfTestReport> {
+  return invoke<SelfTestReport>("self_test");
+}
+
+/**
+ * List supported platforms and their capabilities
+ */
+export function supportedPlatforms(): Promise<PlatformInfo[]> {
+  return invoke<PlatformInfo[]>("supported_platforms");
+}
+
+/**
+ * Base URLs of a Gitea instance, which may be served under a sub-path
+ */
+export interface GiteaUrlInfo {
+  web_base: string;
+  api_base: string;
+  version: string;
+}
+
+/**
+ * Check that a URL points at a Gitea instance and normalize it
+ */
+export function verifyGiteaUrl(url: string): Promise<GiteaUrlInfo> {
+  return invoke<GiteaUrlInfo>("verify_gitea_url", { url });
+}
+
 // ============================================================================
 // Settings Commands
 // ============================================================================
@@ -70,6 +221,27 @@ export function updateAppSettings(
   return invoke<AppSettings>("update_app_settings", { settings });
 }
 
+/**
+ * List recent app-wide activity, newest first
+ *
+ * Pass the last event's id as `afterId` to fetch the next (older) page.
+ */
+export function listAppEvents(
+  options: { limit?: number; afterId?: number } = {}
+): Promise<AppEvent[]> {
+  return invoke<AppEvent[]>("list_app_events", {
+    limit: options.limit,
+    afterId: options.afterId,
+  });
+}
+
+/**
+ * Run the saved post-job hook with a sample payload
+ */
+export function testPostJobHook(): Promise<HookOutcome> {
+  return invoke<HookOutcome>("test_post_job_hook");
+}
+
 // ============================================================================
 // MCP Server Commands
 // ============================================================================
@@ -88,6 +260,273 @@ export function checkMcpConnection(serverName: string): Promise<boolean> {
   return invoke<boolean>("mcp_check_connection", { serverName });
 }
 
+export interface McpImageCheck {
+  image: string;
+  available: boolean;
+  pullable: boolean;
+  detail: string | null;
+}
+
+/**
+ * Check that an MCP server's docker image is present or pullable
+ * NOTE: Requires the backend to be built with the docker-image-check feature
+ */
+export function checkMcpImage(serverName: string): Promise<McpImageCheck> {
+  return invoke<McpImageCheck>("mcp_check_image", { serverName });
+}
+
+/**
+ * Move repositories from one MCP server runner to another
+ * Returns the number of repositories updated
+ */
+export function renameMcpServer(
+  oldName: string,
+  newName: string
+): Promise<number> {
+  return invoke<number>("rename_mcp_server", { oldName, newName });
+}
+
+/**
+ * Get an MCP server runner's stored TOML definition (secrets masked by default)
+ */
+export function getRunnerDefinition(
+  serverName: string,
+  revealSecrets = false
+): Promise<string> {
+  return invoke<string>("get_runner_definition", { serverName, revealSecrets });
+}
+
+/**
+ * Throttle state of one MCP server
+ */
+export interface ServerThrottleState {
+  server_name: string;
+  available: number;
+  waiting: number;
+  next_permit_ms: number | null;
+}
+
+export interface McpThrottleStatus {
+  /** 0 when throttling is disabled */
+  calls_per_minute: number;
+  servers: ServerThrottleState[];
+}
+
+/**
+ * Get the MCP call throttle configuration and per-server state
+ */
+export function getMcpThrottleStatus(): Promise<McpThrottleStatus> {
+  return invoke<McpThrottleStatus>("mcp_throttle_status");
+}
+
+/**
+ * Circuit breaker state of one MCP server
+ */
+export interface ServerCircuitStatus {
+  server_name: string;
+  state: "closed" | "open" | "half_open";
+  consecutive_failures: number;
+  retry_in_ms: number | null;
+}
+
+/**
+ * Get the circuit breaker state of MCP servers with recent failures
+ */
+export function getMcpCircuitStatus(): Promise<ServerCircuitStatus[]> {
+  return invoke<ServerCircuitStatus[]>("mcp_circuit_status");
+}
+
+/**
+ * Close an MCP server's circuit so calls to it are attempted right away
+ */
+export function resetMcpCircuit(serverName: string): Promise<void> {
+  return invoke<void>("reset_mcp_circuit", { serverName });
+}
+
+/**
+ * Latency statistics of successful benchmark calls, in milliseconds
+ */
+export interface LatencyStats {
+  min_ms: number;
+  max_ms: number;
+  mean_ms: number;
+  p95_ms: number;
+}
+
+/**
+ * Result of calling one MCP tool repeatedly
+ */
+export interface McpToolBenchmark {
+  server_name: string;
+  tool_name: string;
+  iterations: number;
+  succeeded: number;
+  failed: number;
+  latencies_ms: number[];
+  stats: LatencyStats | null;
+  first_error: string | null;
+  timed_out: boolean;
+}
+
+/**
+ * Call an MCP tool serially `iterations` times (1-50) and report its latency
+ */
+export function benchmarkMcpTool(
+  serverName: string,
+  toolName: string,
+  args: Record<string, unknown>,
+  iterations: number
+): Promise<McpToolBenchmark> {
+  return invoke<McpToolBenchmark>("benchmark_mcp_tool", {
+    serverName,
+    toolName,
+    args,
+    iterations,
+  });
+}
+
+/**
+ * Repository whose MCP server runner no longer exists on the backend
+ */
+export interface MissingRunnerRepository {
+  repository_id: number;
+  name: string;
+  mcp_server_name: string;
+}
+
+/**
+ * Find repositories pointing at MCP server runners missing from the backend
+ */
+export function auditMcpServers(): Promise<MissingRunnerRepository[]> {
+  return invoke<MissingRunnerRepository[]>("audit_mcp_servers");
+}
+
+/**
+ * Backend runner of any type
+ */
+export interface RunnerInfo {
+  id: number;
+  name: string;
+  runner_type: string;
+  description: string;
+}
+
+/**
+ * List backend runners whose name contains a substring (case-insensitive),
+ * optionally limited to runner types such as "MCP_SERVER"
+ */
+export function searchRunners(
+  nameSubstring?: string,
+  types?: string[]
+): Promise<RunnerInfo[]> {
+  return invoke<RunnerInfo[]>("search_runners", { nameSubstring, types });
+}
+
+/**
+ * Get the JSON Schema of an MCP tool's arguments
+ */
+export function getToolArgSchema(
+  serverName: string,
+  toolName: string
+): Promise<Record<string, unknown>> {
+  return invoke<Record<string, unknown>>("get_tool_arg_schema", {
+    serverName,
+    toolName,
+  });
+}
+
+/**
+ * Schemas an MCP server runner declares for one tool
+ */
+export interface ToolSchemaInfo {
+  tool_name: string;
+  description: string | null;
+  has_args_schema: boolean;
+  has_result_schema: boolean;
+  /** How tool results are decoded */
+  decode_path: "protobuf" | "json" | "schema_fallback";
+  /** Why a declared schema could not be parsed; results are parsed as JSON */
+  schema_error: string | null;
+}
+
+/**
+ * List an MCP server's tools and which decode via protobuf vs JSON
+ */
+export function inspectRunnerSchema(
+  serverName: string
+): Promise<ToolSchemaInfo[]> {
+  return invoke<ToolSchemaInfo[]>("inspect_runner_schema", { serverName });
+}
+
+/**
+ * Undecoded result of an MCP tool call
+ */
+export interface McpRawResult {
+  data_base64: string;
+  byte_length: number;
+}
+
+/**
+ * Call an MCP tool and return its result bytes without decoding them
+ */
+export function callMcpToolRawBytes(
+  serverName: string,
+  toolName: string,
+  args: Record<string, unknown>
+): Promise<McpRawResult> {
+  return invoke<McpRawResult>("call_mcp_tool_raw_bytes", {
+    serverName,
+    toolName,
+    args,
+  });
+}
+
+export type McpOperation =
+  | "list_issues"
+  | "read_issue"
+  | "issue_timeline"
+  | "issue_comments"
+  | "search_issues"
+  | "list_pulls"
+  | "list_labels"
+  | "list_my_repos"
+  | "pr_reviews"
+  | "pr_review_comments"
+  | "search_repos"
+  | "current_user";
+
+/**
+ * Tool name used for an operation on one MCP server instead of the default
+ */
+export interface ToolOverride {
+  mcp_server_name: string;
+  operation: McpOperation;
+  tool_name: string;
+}
+
+/**
+ * List saved MCP tool overrides
+ */
+export function listToolOverrides(): Promise<ToolOverride[]> {
+  return invoke<ToolOverride[]>("list_tool_overrides");
+}
+
+/**
+ * Remap an operation to the tool name an MCP server actually exposes.
+ * Pass null to go back to the default tool.
+ */
+export function setToolOverride(
+  mcpServerName: string,
+  operation: McpOperation,
+  toolName: string | null
+): Promise<void> {
+  return invoke<void>("set_tool_override", {
+    mcpServerName,
+    operation,
+    toolName,
+  });
+}
+
 /**
  * Create a new MCP server (Runner) dynamically
  */
@@ -105,6 +544,55 @@ export function createMcpRunner(
   });
 }
 
+/**
+ * Result of importing an mcp-settings.toml file
+ */
+export interface McpImportFailure {
+  name: string;
+  error: string;
+}
+
+export interface McpSettingsImport {
+  created: string[];
+  skipped: string[];
+  /** Servers whose runner could not be created; the others are still imported */
+  failed: McpImportFailure[];
+  created_count: number;
+  skipped_count: number;
+}
+
+/**
+ * Create MCP server runners from a jobworkerp-rs mcp-settings.toml file
+ */
+export function importMcpSettings(
+  tomlPath: string
+): Promise<McpSettingsImport> {
+  return invoke<McpSettingsImport>("import_mcp_settings", { tomlPath });
+}
+
+/**
+ * Result of exporting MCP runners to an mcp-settings.toml file
+ */
+export interface McpSettingsExport {
+  path: string;
+  exported: string[];
+  unparsed: string[];
+  secrets_included: boolean;
+}
+
+/**
+ * Write the current MCP server runners to an mcp-settings.toml file
+ */
+export function exportMcpSettings(
+  destPath: string,
+  includeSecrets: boolean
+): Promise<McpSettingsExport> {
+  return invoke<McpSettingsExport>("export_mcp_settings", {
+    destPath,
+    includeSecrets,
+  });
+}
+
 // ============================================================================
 // Repository Commands
 // ============================================================================
@@ -132,6 +620,118 @@ export function createRepository(
   return invoke<Repository>("create_repository", { request });
 }
 
+/**
+ * Create an MCP server and register a repository from its URL in one step
+ */
+export function setupRepository(
+  platform: "GitHub" | "Gitea",
+  url: string,
+  token: string
+): Promise<Repository> {
+  return invoke<Repository>("setup_repository", { platform, url, token });
+}
+
+/**
+ * Change a repository's display name
+ */
+export function renameRepository(id: number, name: string): Promise<Repository> {
+  return invoke<Repository>("rename_repository", { id, name });
+}
+
+/**
+ * Get the standing prompt applied to every agent run on a repository
+ */
+export function getRepositoryPrompt(id: number): Promise<string | null> {
+  return invoke<string | null>("get_repository_prompt", { id });
+}
+
+/**
+ * Set or clear (null or blank) a repository's default prompt
+ */
+export function setRepositoryPrompt(
+  id: number,
+  prompt: string | null
+): Promise<void> {
+  return invoke<void>("set_repository_prompt", { id, prompt });
+}
+
+/**
+ * Set or clear (null or blank) the base branch agents use for a repository;
+ * cleared, it follows default_base_branch from the settings
+ */
+export function setRepositoryBaseBranch(
+  id: number,
+  branch: string | null
+): Promise<void> {
+  return invoke<void>("set_repository_base_branch", { id, branch });
+}
+
+export interface BaseBranchCheck {
+  base_branch: string;
+  repository_override: boolean;
+  remote_default_branch: string | null;
+  warning: string | null;
+}
+
+/**
+ * Compare the base branch agents would use with the repository's default branch
+ */
+export function checkBaseBranch(repositoryId: number): Promise<BaseBranchCheck> {
+  return invoke<BaseBranchCheck>("check_base_branch", { repositoryId });
+}
+
+export interface TokenScopeReport {
+  token_kind: "classic" | "fine_grained" | "gitea" | "unknown";
+  valid: boolean;
+  scopes: string[] | null;
+  required_scopes: string[];
+  missing_scopes: string[];
+  can_push: boolean | null;
+  detail: string | null;
+}
+
+/**
+ * Check that a repository's MCP server token has the scopes the agent needs
+ */
+export function checkRunnerToken(
+  repositoryId: number
+): Promise<TokenScopeReport> {
+  return invoke<TokenScopeReport>("check_runner_token", { repositoryId });
+}
+
+export interface RunnerTokenStatus {
+  mcp_server_name: string;
+  repository_ids: number[];
+  token_status: "valid" | "invalid" | null;
+  error: string | null;
+}
+
+/**
+ * Check the token of every runner used by a repository now
+ */
+export function checkAllRunnerTokens(): Promise<RunnerTokenStatus[]> {
+  return invoke<RunnerTokenStatus[]>("check_all_runner_tokens");
+}
+
+export interface AgentTimeEstimate {
+  repository_size_bytes: number | null;
+  size_source: "local" | "remote" | "unknown";
+  estimated_clone_minutes: number;
+  recommended_timeout_minutes: number;
+  configured_timeout_minutes: number;
+  likely_insufficient: boolean;
+  message: string | null;
+}
+
+/**
+ * Estimate whether the agent timeout is long enough for a repository
+ */
+export function estimateAgentTime(
+  repositoryId: number
+): Promise<AgentTimeEstimate> {
+  return invoke<AgentTimeEstimate>("estimate_agent_time", { repositoryId });
+}
+
 /**
  * Delete a repository by ID
  */
@@ -139,6 +739,32 @@ export function deleteRepository(id: number): Promise<void> {
   return invoke<void>("delete_repository", { id });
 }
 
+/**
+ * Options for listing the authenticated user's repositories
+ */
+export interface ListRemoteRepositoriesOptions {
+  affiliation?: "owner" | "member" | "collaborator";
+  page?: number;
+  perPage?: number;
+}
+
+/**
+ * List repositories of the user authenticated on an MCP server
+ */
+export function listRemoteRepositories(
+  serverName: string,
+  platform: "GitHub" | "Gitea",
+  options: ListRemoteRepositoriesOptions = {}
+): Promise<PaginatedRemoteRepositories> {
+  return invoke<PaginatedRemoteRepositories>("list_remote_repositories", {
+    serverName,
+    platform,
+    affiliation: options.affiliation,
+    page: options.page,
+    perPage: options.perPage,
+  });
+}
+
 // ============================================================================
 // Issue Commands
 // ============================================================================
@@ -156,6 +782,76 @@ export function listIssues(
   });
 }
 
+/**
+ * Search issues in a repository (pull requests excluded)
+ */
+export function searchIssues(
+  repositoryId: number,
+  query: string,
+  state?: "open" | "closed" | "all"
+): Promise<Issue[]> {
+  return invoke<Issue[]>("search_issues", {
+    repositoryId,
+    query,
+    state: state ?? "open",
+  });
+}
+
+/**
+ * Open issue assigned to the agent user, with its repository
+ */
+export interface AgentInboxIssue extends Issue {
+  repository_id: number;
+  repository_name: string;
+}
+
+/**
+ * Issues assigned to the agent user across one or all repositories
+ */
+export interface AgentInbox {
+  issues: AgentInboxIssue[];
+  failed_repositories: { repository_id: number; error: string }[];
+}
+
+/**
+ * List open issues assigned to `assignee` in one repository, or in every
+ * repository when `repositoryId` is null
+ */
+export function listAgentAssignedIssues(
+  repositoryId: number | null,
+  assignee: string
+): Promise<AgentInbox> {
+  return invoke<AgentInbox>("list_agent_assigned_issues", {
+    repositoryId,
+    assignee,
+  });
+}
+
+export type IssuesPayloadShape =
+  | "array"
+  | "issues_key"
+  | "items_key"
+  | "single_issue"
+  | "unrecognized";
+
+export interface ParsedIssues {
+  issues: Issue[];
+  shape: IssuesPayloadShape;
+  from_text_content: boolean;
+  skipped: number;
+}
+
+/**
+ * Parse a pasted raw MCP response the way issue listing does
+ */
+export function testParseIssues(
+  raw: string,
+  repoUrl: string,
+  platform: "GitHub" | "Gitea"
+): Promise<ParsedIssues> {
+  return invoke<ParsedIssues>("test_parse_issues", { raw, repoUrl, platform });
+}
+
 /**
  * Get a single issue by number
  */
@@ -169,20 +865,100 @@ export function getIssue(
   });
 }
 
+/**
+ * Check that an issue exists and is open before starting an agent.
+ * Resolves to null when the check is skipped.
+ */
+export function checkIssueForAgent(
+  repositoryId: number,
+  issueNumber: number,
+  skipCheck = false
+): Promise<Issue | null> {
+  return invoke<Issue | null>("check_issue_for_agent", {
+    repositoryId,
+    issueNumber,
+    skipCheck,
+  });
+}
+
+/**
+ * Get an issue's timeline (empty if the MCP server has no timeline tool)
+ */
+export function getIssueTimeline(
+  repositoryId: number,
+  issueNumber: number
+): Promise<TimelineEvent[]> {
+  return invoke<TimelineEvent[]>("get_issue_timeline", {
+    repositoryId,
+    issueNumber,
+  });
+}
+
+export interface AgentContext {
+  text: string;
+  included_comments: number;
+  omitted_comments: number;
+}
+
+/**
+ * Build bounded, redacted prompt context from an issue and its comments
+ */
+export function buildAgentContext(
+  repositoryId: number,
+  issueNumber: number
+): Promise<AgentContext> {
+  return invoke<AgentContext>("build_agent_context", {
+    repositoryId,
+    issueNumber,
+  });
+}
+
+/**
+ * List a repository's labels (cached for ten minutes unless refreshed)
+ */
+export function listRepositoryLabels(
+  repositoryId: number,
+  refresh = false
+): Promise<Label[]> {
+  return invoke<Label[]>("list_repository_labels", { repositoryId, refresh });
+}
+
 // ============================================================================
 // Pull Request Commands
 // ============================================================================
 
+export interface ListPullsOptions {
+  page?: number;
+  perPage?: number;
+  labels?: string[];
+  sort?: string;
+}
+
 /**
- * List pull requests for a repository
+ * List a page of pull requests for a repository
  */
 export function listPulls(
   repositoryId: number,
-  state?: "open" | "closed" | "all"
-): Promise<PullRequest[]> {
-  return invoke<PullRequest[]>("list_pulls", {
+  state?: "open" | "closed" | "all",
+  options?: ListPullsOptions
+): Promise<PaginatedPullRequests> {
+  return invoke<PaginatedPullRequests>("list_pulls", {
     repositoryId,
     state: state ?? "open",
+    ...options,
+  });
+}
+
+/**
+ * Get inline review comments on a pull request, oldest first
+ */
+export function getPrReviewComments(
+  repositoryId: number,
+  prNumber: number
+): Promise<ReviewComment[]> {
+  return invoke<ReviewComment[]>("get_pr_review_comments", {
+    repositoryId,
+    prNumber,
   });
 }
 
@@ -191,14 +967,29 @@ export function listPulls(
  */
 export function findRelatedPrs(
   repositoryId: number,
-  issueNumber: number
-): Promise<PullRequest[]> {
-  return invoke<PullRequest[]>("find_related_prs", {
+  issueNumber: number,
+  options: { maxResults?: number; scanLimit?: number } = {}
+): Promise<RelatedPullRequests> {
+  return invoke<RelatedPullRequests>("find_related_prs", {
     repositoryId,
     issueNumber,
+    maxResults: options.maxResults,
+    scanLimit: options.scanLimit,
   });
 }
 
+// ============================================================================
+// Opener Commands
+// ============================================================================
+
+/**
+ * Open an issue/PR URL in the system browser.
+ * The backend only allows https, or http for registered repository hosts.
+ */
+export function openExternal(url: string): Promise<void> {
+  return invoke<void>("open_external", { url });
+}
+
 // ============================================================================
 // Job Commands
 // ============================================================================
@@ -217,6 +1008,169 @@ export function getJob(id: number): Promise<AgentJob> {
   return invoke<AgentJob>("get_job", { id });
 }
 
+export interface JobLogEntry {
+  job_id: number;
+  seq: number;
+  chunk: string;
+  created_at: string;
+}
+
+export interface JobReport {
+  app_version: string;
+  generated_at: string;
+  platform: "GitHub" | "Gitea";
+  repository: string;
+  job: AgentJob;
+  logs: JobLogEntry[];
+}
+
+/**
+ * Export a job and its log as a redacted report for bug reports
+ */
+export function exportJobReport(jobId: number): Promise<JobReport> {
+  return invoke<JobReport>("export_job_report", { jobId });
+}
+
+/**
+ * List a repository's issues with their latest agent job and attempt count
+ */
+export function jobsByIssue(repositoryId: number): Promise<IssueJobSummary[]> {
+  return invoke<IssueJobSummary[]>("jobs_by_issue", { repositoryId });
+}
+
+export interface FieldDiff<T> {
+  a: T;
+  b: T;
+  changed: boolean;
+}
+
+export interface JobStatusChange {
+  status: AgentJobStatus;
+  changed_at: string;
+  /** Time until the next status; null for the current one */
+  seconds_in_status: number | null;
+}
+
+export interface JobComparison {
+  repository_id: number;
+  issue_number: number;
+  job_a: AgentJob;
+  job_b: AgentJob;
+  timeline: {
+    a: JobStatusChange[];
+    b: JobStatusChange[];
+    /** Number of leading statuses both jobs went through in the same order */
+    common_prefix: number;
+  };
+  status: FieldDiff<AgentJobStatus>;
+  /** Run time of a finished job; null while it runs */
+  duration_secs: FieldDiff<number | null>;
+  error_message: FieldDiff<string | null>;
+  branch_name: FieldDiff<string | null>;
+  pr_number: FieldDiff<number | null>;
+  pr_url: FieldDiff<string | null>;
+  commit_sha: FieldDiff<string | null>;
+  files_changed: FieldDiff<number | null>;
+}
+
+/**
+ * Compare two agent jobs run for the same issue, e.g. a failed attempt and its retry
+ */
+export function compareJobs(
+  jobIdA: number,
+  jobIdB: number
+): Promise<JobComparison> {
+  return invoke<JobComparison>("compare_jobs", { jobIdA, jobIdB });
+}
+
+/**
+ * List the job statuses present for a repository, for a status filter
+ */
+export function distinctJobStatuses(
+  repositoryId: number
+): Promise<AgentJobStatus[]> {
+  return invoke<AgentJobStatus[]>("distinct_job_statuses", { repositoryId });
+}
+
+/**
+ * List agent jobs that produced a pull request, with PR links
+ */
+export function listJobsWithPrs(
+  state?: AgentJobStatus,
+  repositoryId?: number
+): Promise<JobWithPr[]> {
+  return invoke<JobWithPr[]>("list_jobs_with_prs", { state, repositoryId });
+}
+
+/**
+ * Agent job counts for one day or week (UTC)
+ */
+export interface AgentMetricsBucket {
+  /** First day of the bucket, YYYY-MM-DD */
+  bucket_start: string;
+  started: number;
+  completed: number;
+  failed: number;
+  average_duration_secs: number | null;
+}
+
+/**
+ * Count a repository's agent jobs per day or week, for a trends chart
+ */
+export function agentMetrics(
+  repositoryId: number,
+  bucket: "Day" | "Week"
+): Promise<AgentMetricsBucket[]> {
+  return invoke<AgentMetricsBucket[]>("agent_metrics", { repositoryId, bucket });
+}
+
+/**
+ * Directory under the worktree base path not used by any agent job
+ */
+export interface OrphanWorktree {
+  path: string;
+  name: string;
+  size_bytes: number;
+}
+
+/**
+ * Find worktree directories left behind by deleted jobs or crashed runs
+ */
+export function findOrphanWorktrees(): Promise<OrphanWorktree[]> {
+  return invoke<OrphanWorktree[]>("find_orphan_worktrees");
+}
+
+/**
+ * Delete orphan worktrees; paths that are no longer orphans are skipped.
+ * Resolves to the paths that were removed.
+ */
+export function deleteOrphanWorktrees(paths: string[]): Promise<string[]> {
+  return invoke<string[]>("delete_orphan_worktrees", { paths });
+}
+
+/**
+ * Where an agent job for an issue will check out its worktree
+ */
+export interface WorktreePathPreview {
+  path: string;
+  exists: boolean;
+  /** Active job of a different issue using the same path */
+  conflicting_job_id: number | null;
+}
+
+/**
+ * Show the worktree path an agent job for an issue would use
+ */
+export function previewWorktreePath(
+  repositoryId: number,
+  issueNumber: number
+): Promise<WorktreePathPreview> {
+  return invoke<WorktreePathPreview>("preview_worktree_path", {
+    repositoryId,
+    issueNumber,
+  });
+}
+
 // ============================================================================
 // Agent Commands (Phase 3 - placeholders)
 // ============================================================================
@@ -225,6 +1179,44 @@ export interface StartAgentRequest {
   repository_id: number;
   issue_number: number;
   issue_title: string;
+  /** Appended to the repository's default prompt for this run */
+  custom_prompt?: string;
+}
+
+/**
+ * Length check result for an agent prompt
+ */
+export interface PromptCheck {
+  length: number;
+  max_length: number;
+  estimated_tokens: number;
+}
+
+/**
+ * Validate a custom prompt before starting an agent. With a repository,
+ * the repository's default prompt is included in the check.
+ */
+export function validatePrompt(
+  prompt: string,
+  repositoryId?: number
+): Promise<PromptCheck> {
+  return invoke<PromptCheck>("validate_prompt", { prompt, repositoryId });
+}
+
+/**
+ * Workflow definition file available to agent jobs
+ */
+export interface WorkflowInfo {
+  name: string;
+  title: string | null;
+  description: string | null;
+}
+
+/**
+ * List workflow files in the bundled workflows directory (empty if absent)
+ */
+export function listWorkflows(): Promise<WorkflowInfo[]> {
+  return invoke<WorkflowInfo[]>("list_workflows");
 }
 
 export interface StartAgentResponse {
@@ -244,8 +1236,62 @@ export function startAgent(
 
 /**
  * Cancel a running agent job
- * NOTE: Not yet implemented in backend (Phase 3)
+ * With cleanupWorktree, the job's worktree is removed once the backend job is deleted
+ */
+export function cancelAgent(
+  jobworkerpJobId: string,
+  cleanupWorktree = false
+): Promise<void> {
+  return invoke<void>("agent_cancel", { jobworkerpJobId, cleanupWorktree });
+}
+
+/**
+ * Listen again to a running job whose result listener died
+ * Fails when the job is not running or is already listened to
+ */
+export function reattachJob(jobId: number): Promise<void> {
+  return invoke<void>("reattach_job", { jobId });
+}
+
+/** Parsed final output of the code agent workflow */
+export interface WorkflowResult {
+  status: string | null;
+  pr_number: number | null;
+  pr_url: string | null;
+  no_changes: boolean;
+  error: string | null;
+  commit_sha: string | null;
+  files_changed: number | null;
+  branch: string | null;
+  summary: string | null;
+}
+
+/**
+ * Parse a finished job's stored workflow output again and update the job
+ * Running, merged and cancelled jobs are rejected
+ */
+export function reparseJobResult(jobId: number): Promise<WorkflowResult> {
+  return invoke<WorkflowResult>("reparse_job_result", { jobId });
+}
+
+/**
+ * Parse every finished job's stored workflow output again
+ * Returns how many jobs changed
+ */
+export function reparseAllJobResults(): Promise<number> {
+  return invoke<number>("reparse_all_job_results");
+}
+
+/**
+ * Start the merged `all-jobs-stream` feed of every active job's stream events
+ */
+export function subscribeAllJobs(): Promise<void> {
+  return invoke<void>("subscribe_all_jobs");
+}
+
+/**
+ * Stop the merged job feed; resolves to whether one was running
  */
-export function cancelAgent(jobworkerpJobId: string): Promise<void> {
-  return invoke<void>("agent_cancel", { jobworkerpJobId });
+export function unsubscribeAllJobs(): Promise<boolean> {
+  return invoke<boolean>("unsubscribe_all_jobs");
 }
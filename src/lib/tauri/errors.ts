@@ -0,0 +1,43 @@
+/**
+ * Error helpers for Tauri command failures
+ *
+ * Commands reject with a message string, except field validation errors,
+ * which are sent as `{ field, message }`.
+ */
+
+export interface ValidationError {
+  field: string;
+  message: string;
+}
+
+/**
+ * Check whether a command error points at a specific input field
+ */
+export function isValidationError(error: unknown): error is ValidationError {
+  return (
+    typeof error === "object" &&
+    error !== null &&
+    typeof (error as ValidationError).field === "string" &&
+    typeof (error as ValidationError).message === "string"
+  );
+}
+
+/**
+ * Name of the invalid field, if the error is a validation error
+ */
+export function getInvalidField(error: unknown): string | undefined {
+  return isValidationError(error) ? error.field : undefined;
+}
+
+/**
+ * Extract a user-facing message from a command error
+ */
+export function formatCommandError(error: unknown): string {
+  if (isValidationError(error)) {
+    return error.message;
+  }
+  if (error instanceof Error) {
+    return error.message;
+  }
+  return String(error);
+}
@@ -5,6 +5,7 @@
  * particularly for streaming job results from the Rust backend.
  */
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { DiagnosticStep } from "./commands";
 
 // ============================================================================
 // Stream Event Types
@@ -75,9 +76,38 @@ export function listenJobStream(
   });
 }
 
+/**
+ * Stream event of one job in the merged `all-jobs-stream` feed
+ */
+export interface JobFeedEvent {
+  /** Local agent job ID */
+  job_id: number;
+  jobworkerp_job_id: string;
+  event: StreamEvent;
+}
+
+/**
+ * Listen to the stream events of every active job
+ *
+ * Events only arrive while the feed is started with `subscribeAllJobs`.
+ *
+ * @param callback - Function called with each job's stream events
+ * @returns Promise that resolves to an unlisten function
+ */
+export function listenAllJobs(
+  callback: (event: JobFeedEvent) => void
+): Promise<UnlistenFn> {
+  return listen<JobFeedEvent>("all-jobs-stream", (event) => {
+    callback(event.payload);
+  });
+}
+
 /**
  * Listen to job status change events for a specific job ID
  *
+ * Besides job statuses, `Detached` is sent when a running job's result
+ * stream broke off; the job stays active and can be reattached.
+ *
  * @param jobId - The local job ID to listen for
  * @param callback - Function called when status changes
  * @returns Promise that resolves to an unlisten function
@@ -91,6 +121,42 @@ export function listenJobStatus(
   });
 }
 
+/**
+ * Listen to connection diagnostic steps as `diagnoseConnection` runs them
+ *
+ * @param callback - Function called with each completed step
+ * @returns Promise that resolves to an unlisten function
+ */
+export function listenConnectionDiagnostics(
+  callback: (step: DiagnosticStep) => void
+): Promise<UnlistenFn> {
+  return listen<DiagnosticStep>("connection-diagnostic-step", (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Payload of the `token-expired` event
+ */
+export interface TokenExpiredEvent {
+  mcp_server_name: string;
+  repository_ids: number[];
+}
+
+/**
+ * Listen for runner tokens that stop being accepted
+ *
+ * @param callback - Function called with the runner and its repositories
+ * @returns Promise that resolves to an unlisten function
+ */
+export function listenTokenExpired(
+  callback: (event: TokenExpiredEvent) => void
+): Promise<UnlistenFn> {
+  return listen<TokenExpiredEvent>("token-expired", (event) => {
+    callback(event.payload);
+  });
+}
+
 // ============================================================================
 // Utility Functions
 // ============================================================================
@@ -5,4 +5,5 @@
  */
 
 export * from "./commands";
+export * from "./errors";
 export * from "./events";
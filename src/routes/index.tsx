@@ -61,7 +61,7 @@ function HomePage() {
               <p className="text-sm text-slate-500 dark:text-slate-400">Active Jobs</p>
               <p className="text-2xl font-bold">
                 {jobsQuery.data?.filter(
-                  (j) => !["Completed", "Failed", "Cancelled"].includes(j.status)
+                  (j) => !["Completed", "NoChanges", "Failed", "Cancelled"].includes(j.status)
                 ).length ?? 0}
               </p>
             </div>
@@ -103,7 +103,7 @@ function HomePage() {
                       ? "bg-green-100 dark:bg-green-900 text-green-700 dark:text-green-300"
                       : job.status === "Failed"
                         ? "bg-red-100 dark:bg-red-900 text-red-700 dark:text-red-300"
-                        : job.status === "Cancelled"
+                        : job.status === "Cancelled" || job.status === "NoChanges"
                           ? "bg-gray-100 dark:bg-gray-800 text-gray-700 dark:text-gray-300"
                           : "bg-blue-100 dark:bg-blue-900 text-blue-700 dark:text-blue-300"
                   }`}
@@ -31,6 +31,7 @@ const statusLabels: Record<AgentJobStatus, string> = {
   PrCreated: "PR Created",
   Merged: "Merged",
   Completed: "Completed",
+  NoChanges: "No Changes",
   Failed: "Failed",
   Cancelled: "Cancelled",
 };
@@ -117,9 +118,9 @@ function JobDetailPage() {
     ? `${repository.url}/issues/${job.issue_number}`
     : null;
 
-  const prUrl = repository && job.pr_number
-    ? buildPrUrl(repository, job.pr_number)
-    : null;
+  const prUrl =
+    job.pr_url ??
+    (repository && job.pr_number ? buildPrUrl(repository, job.pr_number) : null);
 
   return (
     <div className="container mx-auto p-8">
@@ -311,7 +312,7 @@ interface StatusProgressProps {
 }
 
 function StatusProgress({ status }: StatusProgressProps) {
-  if (status === "Failed" || status === "Cancelled") {
+  if (status === "Failed" || status === "Cancelled" || status === "NoChanges") {
     return (
       <div className={`border rounded-lg p-6 ${status === "Failed" ? "border-red-200 dark:border-red-800 bg-red-50 dark:bg-red-900/30" : "border-gray-200 dark:border-gray-700 bg-gray-50 dark:bg-gray-800"}`}>
         <div className="flex items-center gap-3">
@@ -21,6 +21,7 @@ const statusConfig: Record<AgentJobStatus, { label: string; color: string; darkC
   PrCreated: { label: "PR Created", color: "text-green-700", darkColor: "dark:text-green-300", bgColor: "bg-green-100", darkBgColor: "dark:bg-green-900" },
   Merged: { label: "Merged", color: "text-indigo-700", darkColor: "dark:text-indigo-300", bgColor: "bg-indigo-100", darkBgColor: "dark:bg-indigo-900" },
   Completed: { label: "Completed", color: "text-green-700", darkColor: "dark:text-green-300", bgColor: "bg-green-100", darkBgColor: "dark:bg-green-900" },
+  NoChanges: { label: "No Changes", color: "text-gray-700", darkColor: "dark:text-gray-300", bgColor: "bg-gray-100", darkBgColor: "dark:bg-gray-800" },
   Failed: { label: "Failed", color: "text-red-700", darkColor: "dark:text-red-300", bgColor: "bg-red-100", darkBgColor: "dark:bg-red-900" },
   Cancelled: { label: "Cancelled", color: "text-gray-700", darkColor: "dark:text-gray-300", bgColor: "bg-gray-100", darkBgColor: "dark:bg-gray-800" },
 };
@@ -13,7 +13,9 @@ import {
   createRepository,
   deleteRepository,
   createMcpRunner,
+  checkRunnerToken,
 } from "@/lib/tauri/commands";
+import { formatCommandError } from "@/lib/tauri/errors";
 
 export const Route = createFileRoute("/repositories")({
   component: RepositoriesLayout,
@@ -153,6 +155,11 @@ interface RepositoryCardProps {
 }
 
 function RepositoryCard({ repository, onDelete, isDeleting }: RepositoryCardProps) {
+  const tokenCheck = useMutation({
+    mutationFn: () => checkRunnerToken(repository.id),
+  });
+  const report = tokenCheck.data;
+
   return (
     <div className="border border-slate-200 dark:border-slate-700 bg-white dark:bg-slate-800 rounded-lg p-4 hover:shadow-md transition-shadow">
       <div className="flex justify-between items-start">
@@ -178,6 +185,31 @@ function RepositoryCard({ repository, onDelete, isDeleting }: RepositoryCardProp
               Last synced: {new Date(repository.last_synced_at).toLocaleString()}
             </p>
           )}
+          {report && (
+            <p
+              className={`text-xs mt-1 ${
+                report.valid && report.missing_scopes.length === 0
+                  ? "text-green-600 dark:text-green-400"
+                  : "text-red-600 dark:text-red-400"
+              }`}
+            >
+              {!report.valid
+                ? "Token rejected by the platform"
+                : report.missing_scopes.length > 0
+                  ? `Token is missing: ${report.missing_scopes.join(", ")}`
+                  : "Token has the required access"}
+              {report.detail && (
+                <span className="block text-gray-400 dark:text-gray-500">
+                  {report.detail}
+                </span>
+              )}
+            </p>
+          )}
+          {tokenCheck.error && (
+            <p className="text-xs mt-1 text-red-600 dark:text-red-400">
+              {formatCommandError(tokenCheck.error)}
+            </p>
+          )}
         </div>
         <div className="flex gap-2 ml-4 shrink-0">
           <a
@@ -188,6 +220,14 @@ function RepositoryCard({ repository, onDelete, isDeleting }: RepositoryCardProp
           >
             Open
           </a>
+          <button
+            type="button"
+            onClick={() => tokenCheck.mutate()}
+            disabled={tokenCheck.isPending}
+            className="px-3 py-1 text-sm border border-slate-300 dark:border-slate-600 rounded hover:bg-gray-50 dark:hover:bg-slate-700 disabled:opacity-50 cursor-pointer"
+          >
+            {tokenCheck.isPending ? "Checking..." : "Check Token"}
+          </button>
           <button
             type="button"
             onClick={onDelete}
@@ -423,7 +463,7 @@ function RepositoryForm({ mcpServers, onSuccess }: RepositoryFormProps) {
 
           {createMcpMutation.isError && (
             <p className="text-red-600 dark:text-red-400 mt-2">
-              Error: {String(createMcpMutation.error)}
+              Error: {formatCommandError(createMcpMutation.error)}
             </p>
           )}
         </div>
@@ -532,7 +572,7 @@ function RepositoryForm({ mcpServers, onSuccess }: RepositoryFormProps) {
 
           {createMutation.isError && (
             <p className="text-red-600 dark:text-red-400 mt-2">
-              Error: {String(createMutation.error)}
+              Error: {formatCommandError(createMutation.error)}
             </p>
           )}
         </>
@@ -123,7 +123,8 @@ function RepositoryDetailPage({ repoId }: RepositoryDetailPageProps) {
   }
 
   const openIssueCount = issuesQuery.data?.length ?? 0;
-  const openPullCount = pullsQuery.data?.length ?? 0;
+  const openPullCount =
+    pullsQuery.data?.total_count ?? pullsQuery.data?.items.length ?? 0;
 
   return (
     <div className="container mx-auto p-8">
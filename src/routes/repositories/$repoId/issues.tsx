@@ -134,7 +134,7 @@ function IssueCard({ issue, repositoryId }: IssueCardProps) {
   const relatedPrsQuery = useQuery(pullQueries.related(repositoryId, issue.number));
 
   // Only use data when query succeeded to avoid misclassifying loading/error as "0 PRs"
-  const relatedPrs = relatedPrsQuery.isSuccess ? relatedPrsQuery.data : [];
+  const relatedPrs = relatedPrsQuery.isSuccess ? relatedPrsQuery.data.items : [];
   const hasOpenPr = relatedPrsQuery.isSuccess && relatedPrs.some((pr) => pr.state === "open");
   const hasMergedPr = relatedPrsQuery.isSuccess && relatedPrs.some((pr) => pr.merged);
   const hasRelatedPrs = relatedPrsQuery.isSuccess && relatedPrs.length > 0;
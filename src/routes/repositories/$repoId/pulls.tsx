@@ -102,7 +102,7 @@ function RepositoryPullsPage() {
         <p className="text-slate-600 dark:text-slate-400">Loading pull requests...</p>
       ) : pullsQuery.error ? (
         <p className="text-red-600 dark:text-red-400">Error: {String(pullsQuery.error)}</p>
-      ) : pullsQuery.data?.length === 0 ? (
+      ) : pullsQuery.data?.items.length === 0 ? (
         <div className="text-center py-12">
           <p className="text-gray-500 dark:text-gray-400">
             {stateFilter === "all"
@@ -112,7 +112,7 @@ function RepositoryPullsPage() {
         </div>
       ) : (
         <div className="space-y-4">
-          {pullsQuery.data?.map((pr) => (
+          {pullsQuery.data?.items.map((pr) => (
             <PullRequestCard key={pr.number} pr={pr} />
           ))}
         </div>
@@ -2,7 +2,13 @@ import { createFileRoute, Link } from "@tanstack/react-router";
 import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
 import { useState, useEffect, type FormEvent } from "react";
 import { settingsQueries, queryKeys } from "@/lib/query";
-import { updateAppSettings, type UpdateAppSettingsRequest } from "@/lib/tauri/commands";
+import {
+  testPostJobHook,
+  updateAppSettings,
+  type UpdateAppSettingsRequest,
+} from "@/lib/tauri/commands";
+import { formatCommandError, getInvalidField } from "@/lib/tauri/errors";
+import { cn } from "@/lib/utils";
 
 export const Route = createFileRoute("/settings")({
   component: SettingsPage,
@@ -24,6 +30,12 @@ function SettingsPage() {
         default_base_branch: settingsQuery.data.default_base_branch,
         agent_timeout_minutes: settingsQuery.data.agent_timeout_minutes,
         sync_interval_minutes: settingsQuery.data.sync_interval_minutes,
+        post_job_hook: settingsQuery.data.post_job_hook ?? "",
+        mcp_calls_per_minute: settingsQuery.data.mcp_calls_per_minute,
+        max_prompt_length: settingsQuery.data.max_prompt_length,
+        reattach_window_minutes: settingsQuery.data.reattach_window_minutes,
+        token_check_interval_minutes:
+          settingsQuery.data.token_check_interval_minutes,
       });
     }
   }, [settingsQuery.data, isFormDirty]);
@@ -36,6 +48,8 @@ function SettingsPage() {
     },
   });
 
+  const testHookMutation = useMutation({ mutationFn: testPostJobHook });
+
   const handleSubmit = (e: FormEvent) => {
     e.preventDefault();
     updateMutation.mutate(formData);
@@ -52,7 +66,13 @@ function SettingsPage() {
 
   // Parse and validate numeric input
   const handleNumericChange = (
-    field: "agent_timeout_minutes" | "sync_interval_minutes",
+    field:
+      | "agent_timeout_minutes"
+      | "sync_interval_minutes"
+      | "mcp_calls_per_minute"
+      | "max_prompt_length"
+      | "reattach_window_minutes"
+      | "token_check_interval_minutes",
     value: string
   ) => {
     if (value === "") {
@@ -66,6 +86,14 @@ function SettingsPage() {
     // Invalid input is ignored (keeps previous value)
   };
 
+  // Highlight the input rejected by backend validation
+  const invalidField = getInvalidField(updateMutation.error);
+  const inputClassName = (field: keyof UpdateAppSettingsRequest) =>
+    cn(
+      "w-full p-2 border border-slate-300 dark:border-slate-600 rounded bg-white dark:bg-slate-700 text-slate-900 dark:text-slate-100",
+      invalidField === field && "border-red-500 dark:border-red-500"
+    );
+
   if (settingsQuery.isLoading) {
     return (
       <div className="container mx-auto p-8">
@@ -108,7 +136,8 @@ function SettingsPage() {
             onChange={(e) =>
               updateFormField("worktree_base_path", e.target.value)
             }
-            className="w-full p-2 border border-slate-300 dark:border-slate-600 rounded bg-white dark:bg-slate-700 text-slate-900 dark:text-slate-100"
+            aria-invalid={invalidField === "worktree_base_path"}
+            className={inputClassName("worktree_base_path")}
           />
         </div>
 
@@ -126,7 +155,8 @@ function SettingsPage() {
             onChange={(e) =>
               updateFormField("default_base_branch", e.target.value)
             }
-            className="w-full p-2 border border-slate-300 dark:border-slate-600 rounded bg-white dark:bg-slate-700 text-slate-900 dark:text-slate-100"
+            aria-invalid={invalidField === "default_base_branch"}
+            className={inputClassName("default_base_branch")}
           />
         </div>
 
@@ -145,7 +175,8 @@ function SettingsPage() {
             onChange={(e) =>
               handleNumericChange("agent_timeout_minutes", e.target.value)
             }
-            className="w-full p-2 border border-slate-300 dark:border-slate-600 rounded bg-white dark:bg-slate-700 text-slate-900 dark:text-slate-100"
+            aria-invalid={invalidField === "agent_timeout_minutes"}
+            className={inputClassName("agent_timeout_minutes")}
           />
         </div>
 
@@ -164,8 +195,153 @@ function SettingsPage() {
             onChange={(e) =>
               handleNumericChange("sync_interval_minutes", e.target.value)
             }
-            className="w-full p-2 border border-slate-300 dark:border-slate-600 rounded bg-white dark:bg-slate-700 text-slate-900 dark:text-slate-100"
+            aria-invalid={invalidField === "sync_interval_minutes"}
+            className={inputClassName("sync_interval_minutes")}
+          />
+        </div>
+
+        <div>
+          <label
+            htmlFor="mcp_calls_per_minute"
+            className="block text-sm font-medium mb-1"
+          >
+            MCP Calls per Minute
+          </label>
+          <input
+            id="mcp_calls_per_minute"
+            type="number"
+            min="0"
+            value={formData.mcp_calls_per_minute ?? ""}
+            onChange={(e) =>
+              handleNumericChange("mcp_calls_per_minute", e.target.value)
+            }
+            aria-invalid={invalidField === "mcp_calls_per_minute"}
+            className={inputClassName("mcp_calls_per_minute")}
+          />
+          <p className="mt-1 text-xs text-slate-500 dark:text-slate-400">
+            Limit on tool calls to each MCP server. 0 disables throttling.
+          </p>
+        </div>
+
+        <div>
+          <label
+            htmlFor="max_prompt_length"
+            className="block text-sm font-medium mb-1"
+          >
+            Max Prompt Length (characters)
+          </label>
+          <input
+            id="max_prompt_length"
+            type="number"
+            min="1"
+            value={formData.max_prompt_length ?? ""}
+            onChange={(e) =>
+              handleNumericChange("max_prompt_length", e.target.value)
+            }
+            aria-invalid={invalidField === "max_prompt_length"}
+            className={inputClassName("max_prompt_length")}
+          />
+        </div>
+
+        <div>
+          <label
+            htmlFor="reattach_window_minutes"
+            className="block text-sm font-medium mb-1"
+          >
+            Reattach Window on Startup (minutes)
+          </label>
+          <input
+            id="reattach_window_minutes"
+            type="number"
+            min="1"
+            value={formData.reattach_window_minutes ?? ""}
+            onChange={(e) =>
+              handleNumericChange("reattach_window_minutes", e.target.value)
+            }
+            aria-invalid={invalidField === "reattach_window_minutes"}
+            className={inputClassName("reattach_window_minutes")}
+          />
+          <p className="mt-1 text-xs text-slate-500 dark:text-slate-400">
+            Running jobs idle for longer are marked failed at startup
+          </p>
+        </div>
+
+        <div>
+          <label
+            htmlFor="token_check_interval_minutes"
+            className="block text-sm font-medium mb-1"
+          >
+            Token Check Interval (minutes)
+          </label>
+          <input
+            id="token_check_interval_minutes"
+            type="number"
+            min="0"
+            value={formData.token_check_interval_minutes ?? ""}
+            onChange={(e) =>
+              handleNumericChange(
+                "token_check_interval_minutes",
+                e.target.value
+              )
+            }
+            aria-invalid={invalidField === "token_check_interval_minutes"}
+            className={inputClassName("token_check_interval_minutes")}
           />
+          <p className="mt-1 text-xs text-slate-500 dark:text-slate-400">
+            0 disables the background check; otherwise at least 15
+          </p>
+        </div>
+
+        <div>
+          <label
+            htmlFor="post_job_hook"
+            className="block text-sm font-medium mb-1"
+          >
+            Post-Job Hook
+          </label>
+          <input
+            id="post_job_hook"
+            type="text"
+            placeholder="https://hooks.example.com/... or notify-send 'Job {job_id}: {status}'"
+            value={formData.post_job_hook ?? ""}
+            onChange={(e) => updateFormField("post_job_hook", e.target.value)}
+            aria-invalid={invalidField === "post_job_hook"}
+            className={inputClassName("post_job_hook")}
+          />
+          <p className="mt-1 text-xs text-slate-500 dark:text-slate-400">
+            Webhook URL or shell command run when an agent job finishes. Leave
+            empty to disable.
+          </p>
+          <button
+            type="button"
+            onClick={() => testHookMutation.mutate()}
+            disabled={testHookMutation.isPending || isFormDirty}
+            title={isFormDirty ? "Save settings before testing" : undefined}
+            className="mt-2 px-3 py-1 text-sm border border-slate-300 dark:border-slate-600 rounded hover:bg-slate-100 dark:hover:bg-slate-700 disabled:opacity-50"
+          >
+            {testHookMutation.isPending ? "Testing..." : "Send Test"}
+          </button>
+          {testHookMutation.data && (
+            <p
+              className={cn(
+                "mt-1 text-sm break-all",
+                testHookMutation.data.success
+                  ? "text-green-600 dark:text-green-400"
+                  : "text-red-600 dark:text-red-400"
+              )}
+            >
+              {testHookMutation.data.http_status !== null
+                ? `HTTP ${testHookMutation.data.http_status}`
+                : `Exit code ${testHookMutation.data.exit_code ?? "none"}`}
+              {testHookMutation.data.output &&
+                `: ${testHookMutation.data.output}`}
+            </p>
+          )}
+          {testHookMutation.isError && (
+            <p className="mt-1 text-sm text-red-600 dark:text-red-400">
+              {formatCommandError(testHookMutation.error)}
+            </p>
+          )}
         </div>
 
         <button
@@ -181,7 +357,7 @@ function SettingsPage() {
         )}
         {updateMutation.isError && (
           <p className="text-red-600 dark:text-red-400">
-            Error: {String(updateMutation.error)}
+            Error: {formatCommandError(updateMutation.error)}
           </p>
         )}
       </form>
@@ -7,6 +7,7 @@ export type AgentJobStatus =
   | "PrCreated"
   | "Merged"
   | "Completed"
+  | "NoChanges"
   | "Failed"
   | "Cancelled";
 
@@ -30,11 +31,37 @@ export interface AgentJob {
   worktree_path: string | null;
   branch_name: string | null;
   pr_number: number | null;
+  /** Pull request link as reported when the PR was opened */
+  pr_url: string | null;
   error_message: string | null;
+  commit_sha: string | null;
+  files_changed: number | null;
+  summary: string | null;
   created_at: string;
   updated_at: string;
 }
 
+/**
+ * Latest agent job for an issue, with the number of attempts made
+ */
+export interface IssueJobSummary {
+  issue_number: number;
+  attempts: number;
+  latest: AgentJob;
+}
+
+/**
+ * Agent job that opened a pull request, with a link to it
+ */
+export interface JobWithPr {
+  job_id: number;
+  repository_id: number;
+  issue_number: number;
+  pr_number: number;
+  pr_url: string;
+  status: AgentJobStatus;
+}
+
 export interface Repository {
   id: number;
   mcp_server_name: string;
@@ -45,6 +72,10 @@ export interface Repository {
   owner: string;
   repo_name: string;
   local_path: string | null;
+  /** Stable directory name for agent worktrees */
+  repo_slug: string;
+  /** Result of the last runner token check: "valid", "invalid" or null */
+  token_status: string | null;
   last_synced_at: string | null;
   created_at: string;
   updated_at: string;
@@ -61,6 +92,22 @@ export interface CreateRepositoryRequest {
   local_path: string | null;
 }
 
+/**
+ * Supported platform and the features available for it
+ */
+export interface PlatformInfo {
+  id: "GitHub" | "Gitea";
+  display_name: string;
+  default_host: string | null;
+  token_env_key: string;
+  supports: {
+    issues: boolean;
+    pulls: boolean;
+    comments: boolean;
+    search: boolean;
+  };
+}
+
 export interface McpServerInfo {
   name: string;
   description: string | null;
@@ -157,6 +204,103 @@ export interface PullRequest {
   updated_at: string;
 }
 
+/**
+ * Pull requests related to an issue, from a bounded scan
+ */
+export interface RelatedPullRequests {
+  items: PullRequest[];
+  scanned: number;
+  truncated: boolean;
+}
+
+/**
+ * Repository visible to the authenticated platform user
+ */
+export interface RemoteRepository {
+  owner: string;
+  repo_name: string;
+  url: string;
+  description: string | null;
+  private: boolean;
+}
+
+/**
+ * One page of remote repositories
+ */
+export interface PaginatedRemoteRepositories {
+  items: RemoteRepository[];
+  page: number;
+  per_page: number;
+  has_next_page: boolean;
+}
+
+/**
+ * One page of pull requests
+ */
+export interface PaginatedPullRequests {
+  items: PullRequest[];
+  page: number;
+  per_page: number;
+  total_count: number | null;
+  has_next_page: boolean;
+}
+
+/**
+ * Repository label from GitHub/Gitea
+ */
+export interface Label {
+  name: string;
+  /** Hex color without `#`, lowercase */
+  color: string | null;
+  description: string | null;
+}
+
+/**
+ * Entry in an issue's history (labels, assignments, references, renames)
+ */
+export interface TimelineEvent {
+  event: string;
+  actor: string | null;
+  created_at: string;
+  detail: string | null;
+}
+
+/**
+ * Inline review comment on a pull request diff
+ */
+export interface ReviewComment {
+  id: number;
+  body: string;
+  user: string;
+  path: string;
+  line: number | null;
+  diff_hunk: string | null;
+  /** GitHub only; Gitea threads are grouped by review_id, path and line */
+  in_reply_to: number | null;
+  review_id: number | null;
+  html_url: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export type AppEventType =
+  | "RunnerCreated"
+  | "RepositoryAdded"
+  | "RepositoryRemoved"
+  | "JobStarted"
+  | "JobFinished"
+  | "SettingsChanged";
+
+/**
+ * Entry in the app-wide activity log (message is redacted)
+ */
+export interface AppEvent {
+  id: number;
+  event_type: AppEventType;
+  message: string;
+  created_at: string;
+}
+
 /**
  * Build the web-facing base URL from a Gitea API base URL.
  */